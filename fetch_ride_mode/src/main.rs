@@ -4,7 +4,9 @@ use aws_sdk_sns::Client as SnsClient;
 use lambda_runtime::{service_fn, Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use field_validator::validate_json_for_type;
+use field_validator::migrate::{self, MigrationRegistry};
+use field_validator::redact;
+use field_validator::{DispatchError, Dispatcher, StringEnum, ValidationError, ValidatorOptions};
 use field_validator::ValidateFields;
 use field_validator_derive::ValidateFields;
 
@@ -12,7 +14,10 @@ use field_validator_derive::ValidateFields;
 // use std::time::Instant;
 
 mod rdbc;
-use crate::rdbc::get_vcu_data;
+use crate::rdbc::{get_vcu_data, BikeRideModes};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::LazyLock;
 
 #[derive(ValidateFields, Deserialize, Debug)]
 struct BikeDetails {
@@ -25,25 +30,67 @@ struct BikeDetails {
 #[derive(ValidateFields, Deserialize, Debug)]
 struct Request {
     #[serde(rename = "bike_identifier")]
+    #[field_validator(sensitive)]
     bike_identifier: String,
     
     #[serde(rename = "change_to_mode")]
-    change_to_mode: String,
+    #[field_validator(enum)]
+    change_to_mode: BikeRideModes,
     
     #[serde(rename = "current_mode")]
     current_mode: Option<String>,
 
     #[serde(rename = "bike_info")]
+    #[field_validator(nested)]
     bike_info: BikeDetails,
 }
 
-#[derive(Serialize)]
+#[derive(ValidateFields, Deserialize, Debug)]
+struct GetModeRequest {
+    #[serde(rename = "bike_identifier")]
+    #[field_validator(sensitive)]
+    bike_identifier: String,
+}
+
+#[derive(ValidateFields, Serialize)]
 struct SnsPayload {
     bike_identifier: String,
     steps: usize,
 }
 
-const MODES: [&str; 3] = ["glide", "combat", "ballistic"];
+/// Upgrades requests from older app releases: v1 sent `mode` instead of
+/// `change_to_mode`, and v2 sent `model`/`year` at the top level.
+static MIGRATIONS: LazyLock<MigrationRegistry> = LazyLock::new(|| {
+    MigrationRegistry::new(3)
+        .step(1, "rename mode to change_to_mode", |v| {
+            migrate::rename_field(v, "mode", "change_to_mode")
+        })
+        .try_step(2, "nest model and year under bike_info", |v| {
+            migrate::nest_fields(v, &["model", "year"], "bike_info")
+        })
+});
+
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<Value, Error>> + Send>>;
+
+/// One route per `action`; requests without one predate it and are mode changes.
+static DISPATCHER: LazyLock<Dispatcher<HandlerFuture>> = LazyLock::new(|| {
+    Dispatcher::new("action")
+        .route_migrated::<Request, _>("change_mode", LazyLock::force(&MIGRATIONS), |req| -> HandlerFuture {
+            Box::pin(change_mode(req))
+        })
+        .route::<GetModeRequest, _>("get_mode", |req| -> HandlerFuture { Box::pin(get_mode(req)) })
+        .fallback("change_mode")
+});
+
+/// Older releases send no `api_version`, so unversioned requests start at
+/// v1; the steps leave already-current payloads untouched.
+fn validator_options() -> ValidatorOptions {
+    ValidatorOptions {
+        api_version: Some(1),
+        version_field: Some("api_version".to_string()),
+        ..ValidatorOptions::default()
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<(), lambda_runtime::Error> {
@@ -53,31 +100,81 @@ async fn main() -> Result<(), lambda_runtime::Error> {
 async fn lambda_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
     let json_str = event.payload.to_string();
 
-    // 1) Missing‐fields check
-    if let Err(missing) = validate_json_for_type::<Request>(&json_str) {
-        return Ok(json!({
-            "statusCode": 400,
-            "body": {
-                "error": "Validation Error",
-                "message": format!("{}", missing),
-                "missingFields": missing.missing_fields
-            }
-        }));
-    }
-
-    // 2) JSON syntax / type errors
-    let payload: Request = match serde_json::from_str(&json_str) {
-        Ok(p) => p,
-        Err(e) => {
+    // Payload limits, routing on `action`, legacy migrations, missing‐field
+    // check and deserialization into the action's request type
+    let (response, report) = match DISPATCHER.dispatch(&json_str, &validator_options()) {
+        Ok(dispatched) => dispatched,
+        Err(DispatchError::Invalid { error: ValidationError::MissingFields(missing), .. }) => {
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "error": "Validation Error",
+                    "message": format!("{}", missing),
+                    "missingFields": missing.missing_fields
+                }
+            }));
+        }
+        Err(DispatchError::Invalid { error: ValidationError::InvalidFields(findings), .. }) => {
+            let message = findings.iter().map(|f| f.to_string()).collect::<Vec<_>>().join("; ");
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "error": "Validation Error",
+                    "message": message,
+                    "invalidFields": findings.iter().map(|f| f.path.as_str()).collect::<Vec<_>>()
+                }
+            }));
+        }
+        Err(DispatchError::Deserialize { error, .. }) => {
             return Ok(json!({
                 "statusCode": 400,
                 "body": {
                     "error": "Missing field",
-                    "message": e.to_string()
+                    "message": error.to_string()
+                }
+            }));
+        }
+        Err(err @ (DispatchError::UnknownAction { .. } | DispatchError::MissingAction { .. })) => {
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "error": "Bad Request",
+                    "message": err.to_string(),
+                    "code": err.code(),
+                    "validActions": DISPATCHER.actions()
+                }
+            }));
+        }
+        Err(err) => {
+            return Ok(json!({
+                "statusCode": 400,
+                "body": {
+                    "error": "Bad Request",
+                    "message": err.to_string(),
+                    "code": err.code()
                 }
             }));
         }
     };
+    for step in &report.migrations {
+        println!("Applied migration from v{}: {}", step.from, step.name);
+    }
+    for warning in &report.warnings {
+        println!("Validation warning: {}", warning);
+    }
+
+    response.await
+}
+
+async fn get_mode(payload: GetModeRequest) -> Result<Value, Error> {
+    let current_mode = fetch_current_mode(&payload.bike_identifier).await;
+    Ok(json!({
+        "status":  "success",
+        "current_mode": current_mode
+    }))
+}
+
+async fn change_mode(payload: Request) -> Result<Value, Error> {
 
     ///this is a test comment 
 
@@ -99,35 +196,28 @@ async fn lambda_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
         _ => fetch_current_mode(&bike_identifier).await,
     };
 
-    let current_index = match MODES.iter().position(|&m| m == current_mode) {
+    // Modes cycle in declaration order
+    let modes = BikeRideModes::VALUES;
+    let current_index = match modes.iter().position(|&m| m == current_mode) {
         Some(idx) => idx,
         None => {
             return Ok(json!({
                 "statusCode": 400,
                 "body": {
                     "error": "Invalid Field",
-                    "message": format!("Invalid current_mode: `{}`", current_mode),
+                    "message": format!("Invalid current_mode: `{}`", redact::field_value::<Request>("current_mode", &current_mode)),
                     "invalidFields": ["current_mode"]
                 }
             }))
         }
     };
 
-    let target_index = match MODES.iter().position(|&m| m == target_mode) {
-        Some(idx) => idx,
-        None => {
-            return Ok(json!({
-                "statusCode": 400,
-                "body": {
-                    "error": "Validation Error",
-                    "message": format!("Invalid change_to_mode: `{}`", target_mode),
-                    "invalidFields": ["change_to_mode"]
-                }
-            }))
-        }
-    };
+    let target_index = modes
+        .iter()
+        .position(|&m| m == target_mode.as_str())
+        .expect("every deserializable mode is listed");
 
-    let steps = (target_index + MODES.len() - current_index) % MODES.len();
+    let steps = (target_index + modes.len() - current_index) % modes.len();
 
     if steps == 0 {
         return Ok(json!({
@@ -140,13 +230,15 @@ async fn lambda_handler(event: LambdaEvent<Value>) -> Result<Value, Error> {
         bike_identifier: bike_identifier.clone(),
         steps,
     };
+    // Relayed to the bike over MQTT; catch contract breaks in dev builds
+    field_validator::debug_assert_serialized(&sns_data);
     let topic_arn = "arn:aws:sns:ap-south-1:776601892319:RideModeMqttWrite";
     let message   = serde_json::to_string(&sns_data)?;
     snspush(&sns_client, topic_arn, &message).await;
 
     Ok(json!({
         "status":  "success",
-        "message": format!("Mode change request processed for bike: {}", bike_identifier)
+        "message": format!("Mode change request processed for bike: {}", redact::field_value::<Request>("bike_identifier", &bike_identifier))
     }))
 }
 
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use chrono::{Datelike, Duration, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use field_validator_derive::StringEnum;
 use serde::{Deserialize, Serialize};
 
 use redis::{Client as RDBClient, Commands, Connection as RDBConn};
@@ -30,8 +31,9 @@ pub struct RedisTravelHistory {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
-enum BikeRideModes {
+/// Ride modes in the order the bike cycles through them.
+#[derive(Serialize, Deserialize, StringEnum, Debug, Clone, PartialEq, Default)]
+pub enum BikeRideModes {
     #[default]
     glide = 4,
     combat = 2,
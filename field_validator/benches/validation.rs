@@ -0,0 +1,148 @@
+// field_validator/benches/validation.rs
+//
+// Compares the precompiled `ValidationPlan` against the original
+// implementation, which walked `required_fields()` with a lookup per field.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use field_validator::{validate_json_for_type, ValidateFields};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[allow(dead_code)]
+#[derive(ValidateFields, Deserialize)]
+struct BikeDetails {
+    model: String,
+    year: u32,
+}
+
+#[allow(dead_code)]
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    change_to_mode: String,
+    current_mode: Option<String>,
+    #[field_validator(nested)]
+    bike_info: BikeDetails,
+}
+
+#[allow(dead_code)]
+#[derive(ValidateFields, Deserialize)]
+struct Telemetry {
+    bike_identifier: String,
+    start_dttm: u64,
+    stop_dttm: u64,
+    from: String,
+    to: String,
+    ride_distance: f32,
+    top_speed: f32,
+    avg_speed: Option<f32>,
+    energy_consumed: f64,
+    battery_soc: u8,
+    odometer: f64,
+    lat: f64,
+    lng: f64,
+    ride_mode: String,
+    firmware: String,
+    incognito: Option<bool>,
+}
+
+/// The implementation `validate_json_for_type` had before plans.
+fn legacy_validate<T: ValidateFields>(json: &str) -> Result<(), Vec<String>> {
+    let v: Value = serde_json::from_str(json).map_err(|_| vec![])?;
+    legacy_check::<T>(&v)
+}
+
+fn legacy_check<T: ValidateFields>(v: &Value) -> Result<(), Vec<String>> {
+    let obj = match v.as_object() {
+        Some(obj) => obj,
+        None => return Err(T::required_fields().iter().map(|s| s.to_string()).collect()),
+    };
+    let mut missing = Vec::new();
+    for &field in T::required_fields() {
+        if !obj.contains_key(field) || obj[field].is_null() {
+            missing.push(field.to_string());
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(missing)
+    }
+}
+
+/// A named payload with its legacy and plan-based validators.
+type Case = (&'static str, String, fn(&str) -> bool, fn(&str) -> bool);
+
+fn payloads() -> Vec<Case> {
+    let request = json!({
+        "bike_identifier": "MD9ABCDE1234567",
+        "change_to_mode": "combat",
+        "current_mode": "glide",
+        "bike_info": { "model": "F77", "year": 2024 }
+    });
+    let telemetry = json!({
+        "bike_identifier": "MD9ABCDE1234567",
+        "start_dttm": 1_717_171_717u64,
+        "stop_dttm": 1_717_175_317u64,
+        "from": "Indiranagar",
+        "to": "Whitefield",
+        "ride_distance": 18.4,
+        "top_speed": 96.0,
+        "avg_speed": 31.5,
+        "energy_consumed": 1.92,
+        "battery_soc": 64,
+        "odometer": 12034.7,
+        "lat": 12.9716,
+        "lng": 77.5946,
+        "ride_mode": "ballistic",
+        "firmware": "4.2.1",
+        "incognito": false
+    });
+    vec![
+        (
+            "request",
+            request.to_string(),
+            |j| legacy_validate::<Request>(j).is_ok(),
+            |j| validate_json_for_type::<Request>(j).is_ok(),
+        ),
+        (
+            "telemetry",
+            telemetry.to_string(),
+            |j| legacy_validate::<Telemetry>(j).is_ok(),
+            |j| validate_json_for_type::<Telemetry>(j).is_ok(),
+        ),
+    ]
+}
+
+/// End to end: parse the text and check required fields.
+fn parse_and_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_and_check");
+    for (name, payload, legacy, planned) in payloads() {
+        group.throughput(Throughput::Bytes(payload.len() as u64));
+        group.bench_with_input(BenchmarkId::new("legacy", name), &payload, |b, p| {
+            b.iter(|| legacy(black_box(p)))
+        });
+        group.bench_with_input(BenchmarkId::new("plan", name), &payload, |b, p| {
+            b.iter(|| planned(black_box(p)))
+        });
+    }
+    group.finish();
+}
+
+/// Only the required-field walk, on an already parsed payload.
+fn check_only(c: &mut Criterion) {
+    let value: Value = serde_json::from_str(&payloads()[1].1).unwrap();
+    let mut group = c.benchmark_group("check_only");
+    group.throughput(Throughput::Elements(1));
+    group.bench_function("legacy/telemetry", |b| {
+        b.iter(|| legacy_check::<Telemetry>(black_box(&value)))
+    });
+    group.bench_function("plan/telemetry", |b| {
+        b.iter(|| Telemetry::validation_plan().check(black_box(&value)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_and_check, check_only);
+criterion_main!(benches);
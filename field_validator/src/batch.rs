@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Read};
+use std::str::Utf8Error;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::limits::{LimitError, LimitKind};
+use crate::options::ValidatorOptions;
+use crate::report::ValidationReport;
+use crate::validate::{validate_json_with_report, ValidateFields, ValidationError};
+
+/// Lines read and validated together; bounds memory for large streams.
+const CHUNK_LINES: usize = 16 * 1024;
+
+/// Aggregate results of validating an NDJSON stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchStats {
+    /// Non-blank lines validated.
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    /// Invalid records per `ValidationError::code()`.
+    pub by_code: BTreeMap<String, usize>,
+    /// Missing or invalid fields per path, with array indexes replaced by
+    /// `*` (e.g. `history.*.model`) so elements aggregate together.
+    pub by_path: BTreeMap<String, usize>,
+    /// Warnings, such as deprecated fields, per path.
+    pub warnings_by_path: BTreeMap<String, usize>,
+    /// 1-based line numbers of the invalid records, in order.
+    pub failed_lines: Vec<usize>,
+}
+
+impl BatchStats {
+    fn record(&mut self, line: usize, result: Result<ValidationReport, ValidationError>) {
+        self.total += 1;
+        match result {
+            Ok(report) => {
+                self.valid += 1;
+                for warning in &report.warnings {
+                    *self.warnings_by_path.entry(aggregate_path(&warning.path)).or_default() += 1;
+                }
+            }
+            Err(err) => {
+                self.invalid += 1;
+                self.failed_lines.push(line);
+                *self.by_code.entry(err.code().to_string()).or_default() += 1;
+                let paths: Vec<&str> = match &err {
+                    ValidationError::MissingFields(missing) => {
+                        missing.missing_fields.iter().map(String::as_str).collect()
+                    }
+                    ValidationError::InvalidFields(findings) => {
+                        findings.iter().map(|f| f.path.as_str()).collect()
+                    }
+                    ValidationError::Limit(limit) => vec![limit.path.as_str()],
+                    _ => Vec::new(),
+                };
+                for path in paths.into_iter().filter(|p| !p.is_empty()) {
+                    *self.by_path.entry(aggregate_path(path)).or_default() += 1;
+                }
+            }
+        }
+    }
+}
+
+/// `history.3.model` -> `history.*.model`.
+pub(crate) fn aggregate_path(path: &str) -> String {
+    path.split('.')
+        .map(|seg| if !seg.is_empty() && seg.bytes().all(|b| b.is_ascii_digit()) { "*" } else { seg })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Validate every line of an NDJSON stream as a `T` payload.
+///
+/// Blank lines are skipped. At most `opts.max_bytes + 1` bytes of a line
+/// are buffered; a longer line is skipped and fails with a `Bytes` limit
+/// error, and a line that is not valid UTF-8 fails as invalid JSON. Only
+/// errors reading the stream itself are returned as `Err`. With the
+/// `rayon` feature, lines are validated
+/// in parallel; the statistics are the same either way.
+pub fn validate_ndjson<T: ValidateFields, R: BufRead>(
+    reader: R,
+    opts: &ValidatorOptions,
+) -> io::Result<BatchStats> {
+    validate_ndjson_with(reader, opts, validate_json_with_report::<T>)
+}
+
+/// Like `validate_ndjson`, checking each line with `validate`, e.g. a
+/// `DynamicSchema`'s `validate_json_with_report`.
+pub fn validate_ndjson_with<R, F>(reader: R, opts: &ValidatorOptions, validate: F) -> io::Result<BatchStats>
+where
+    R: BufRead,
+    F: Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync,
+{
+    validate_ndjson_each(reader, opts, validate, |_, _, _| {})
+}
+
+/// Like `validate_ndjson_with`, also handing each record's line number,
+/// text and result to `each`, in line order, so callers can report on
+/// records as they stream past. Lines over `opts.max_bytes` are passed as
+/// empty text. Lines that are not valid UTF-8 fail as invalid JSON and are
+/// passed with the invalid sequences replaced by U+FFFD.
+pub fn validate_ndjson_each<R, F, E>(
+    mut reader: R,
+    opts: &ValidatorOptions,
+    validate: F,
+    mut each: E,
+) -> io::Result<BatchStats>
+where
+    R: BufRead,
+    F: Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync,
+    E: FnMut(usize, &str, &Result<ValidationReport, ValidationError>),
+{
+    let mut stats = BatchStats::default();
+    let mut chunk = Vec::with_capacity(CHUNK_LINES);
+    let mut buf = Vec::new();
+    let mut line = 0;
+    loop {
+        chunk.clear();
+        while let Some(fits) = read_line(&mut reader, opts.max_bytes, &mut buf)? {
+            line += 1;
+            if !fits {
+                chunk.push((line, Line::TooLong));
+            } else if !buf.trim_ascii().is_empty() {
+                let record = match String::from_utf8(std::mem::take(&mut buf)) {
+                    Ok(text) => Line::Text(text),
+                    Err(e) => Line::NotUtf8(String::from_utf8_lossy(e.as_bytes()).into_owned(), e.utf8_error()),
+                };
+                chunk.push((line, record));
+            } else {
+                continue;
+            }
+            if chunk.len() == CHUNK_LINES {
+                break;
+            }
+        }
+        if chunk.is_empty() {
+            break;
+        }
+        let results = validate_chunk(&chunk, opts, &validate);
+        for ((line, record), result) in chunk.iter().zip(results) {
+            each(*line, record.text(), &result);
+            stats.record(*line, result);
+        }
+    }
+    Ok(stats)
+}
+
+/// Read the next line into `buf` without its line ending, buffering at most
+/// `max_bytes + 1` bytes of it. `Some(false)` means the line was longer
+/// than `max_bytes`; the rest of it has been skipped. `None` at the end of
+/// the stream.
+fn read_line<R: BufRead>(reader: &mut R, max_bytes: usize, buf: &mut Vec<u8>) -> io::Result<Option<bool>> {
+    buf.clear();
+    let cap = (max_bytes as u64).saturating_add(1);
+    if reader.by_ref().take(cap).read_until(b'\n', buf)? == 0 {
+        return Ok(None);
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+    } else if buf.len() > max_bytes {
+        skip_line(reader)?;
+        return Ok(Some(false));
+    }
+    Ok(Some(true))
+}
+
+/// Discard input up to and including the next `\n`.
+fn skip_line<R: BufRead>(reader: &mut R) -> io::Result<()> {
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            return Ok(());
+        }
+        match available.iter().position(|&b| b == b'\n') {
+            Some(i) => {
+                reader.consume(i + 1);
+                return Ok(());
+            }
+            None => {
+                let len = available.len();
+                reader.consume(len);
+            }
+        }
+    }
+}
+
+/// A chunk of `(line number, text)`; the text is `None` for a line over the
+/// byte limit.
+/// One non-blank line of the stream, as buffered for validation.
+enum Line {
+    Text(String),
+    /// Longer than `max_bytes`; its text was not kept.
+    TooLong,
+    /// Not valid UTF-8; the text has invalid sequences replaced.
+    NotUtf8(String, Utf8Error),
+}
+
+impl Line {
+    fn text(&self) -> &str {
+        match self {
+            Line::Text(text) | Line::NotUtf8(text, _) => text,
+            Line::TooLong => "",
+        }
+    }
+}
+
+type Chunk = [(usize, Line)];
+type ChunkResults = Vec<Result<ValidationReport, ValidationError>>;
+
+fn validate_line<F>(line: &Line, opts: &ValidatorOptions, validate: &F) -> Result<ValidationReport, ValidationError>
+where
+    F: Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync,
+{
+    match line {
+        Line::Text(json) => validate(json, opts),
+        Line::TooLong => Err(ValidationError::Limit(LimitError {
+            kind: LimitKind::Bytes,
+            limit: opts.max_bytes,
+            path: String::new(),
+        })),
+        Line::NotUtf8(_, e) => Err(ValidationError::Syntax(serde::de::Error::custom(format!(
+            "invalid UTF-8 after byte {}",
+            e.valid_up_to()
+        )))),
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn validate_chunk<F>(chunk: &Chunk, opts: &ValidatorOptions, validate: &F) -> ChunkResults
+where
+    F: Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync,
+{
+    chunk.par_iter().map(|(_, line)| validate_line(line, opts, validate)).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn validate_chunk<F>(chunk: &Chunk, opts: &ValidatorOptions, validate: &F) -> ChunkResults
+where
+    F: Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync,
+{
+    chunk.iter().map(|(_, line)| validate_line(line, opts, validate)).collect()
+}
@@ -0,0 +1,6 @@
+use std::process::ExitCode;
+
+/// Validate JSON and JSON Lines files against a schema file; see `--help`.
+fn main() -> ExitCode {
+    field_validator::cli::Cli::new().run(std::env::args().skip(1))
+}
@@ -0,0 +1,475 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::batch::validate_ndjson_each;
+use crate::finding::Finding;
+use crate::options::ValidatorOptions;
+use crate::plan::ValidationPlan;
+use crate::render::SourceRenderer;
+use crate::report::ValidationReport;
+use crate::schema::DynamicSchema;
+use crate::typescript::TypeScriptGenerator;
+use crate::validate::{read_payload, validate_json_with_report, ValidateFields, ValidationError};
+
+type ValidateFn<'a> = dyn Fn(&str, &ValidatorOptions) -> Result<ValidationReport, ValidationError> + Sync + 'a;
+type Validator = Box<ValidateFn<'static>>;
+
+const USAGE: &str = "\
+usage: field-validator (--type NAME | --schema FILE) [options] PATH...
+       field-validator typescript [--type NAME]... [--schema FILE]... [--validators] [--out FILE]
+
+Validate JSON documents, JSON Lines files or directories of them.
+Files ending in .jsonl or .ndjson hold one payload per line; `-` reads stdin.
+
+options:
+  --type NAME          validate against a registered type
+  --schema FILE        validate against a JSON (or, with the yaml feature,
+                       YAML) schema file
+  --format human|json  report format (default: human)
+  --ndjson             treat every input as JSON Lines
+  --annotate           show each problem in the payload's source (human format)
+  --api-version N      validate as API version N
+  --fail-fast          report only the first problem in each payload
+  --max-errors N       report at most N problems per payload
+  -h, --help           show this message
+
+The typescript command prints TypeScript interfaces for the given types and
+schemas (every registered type if none are given), or writes them to
+--out FILE; --validators adds runtime validator functions.
+
+exit status: 0 if every payload is valid, 1 if any is not, 2 on usage or I/O errors";
+
+/// The `field-validator` command line, with the types it can check.
+///
+/// The bundled binary only knows schema files. To check payloads against
+/// your own types, build a binary that registers them:
+///
+/// ```no_run
+/// # use field_validator::ValidateFields;
+/// # struct Request;
+/// # impl ValidateFields for Request { fn required_fields() -> &'static [&'static str] { &[] } }
+/// fn main() -> std::process::ExitCode {
+///     field_validator::cli::Cli::new()
+///         .register::<Request>("Request")
+///         .run(std::env::args().skip(1))
+/// }
+/// ```
+#[derive(Default)]
+pub struct Cli {
+    types: BTreeMap<String, Registered>,
+}
+
+struct Registered {
+    validate: Validator,
+    plan: &'static ValidationPlan,
+    typescript: fn(TypeScriptGenerator, &str) -> TypeScriptGenerator,
+}
+
+struct Args {
+    target: Target,
+    json_output: bool,
+    ndjson: bool,
+    annotate: bool,
+    opts: ValidatorOptions,
+    paths: Vec<String>,
+}
+
+enum Target {
+    Type(String),
+    Schema(PathBuf),
+}
+
+/// One payload to validate and where it came from.
+struct Record<'a> {
+    source: &'a str,
+    /// 1-based line number within a JSON Lines input.
+    line: Option<usize>,
+    json: &'a str,
+}
+
+/// Validates inputs one record at a time, printing each result as it goes.
+struct Checker<'a> {
+    args: &'a Args,
+    validate: &'a ValidateFn<'a>,
+    plan: &'a ValidationPlan,
+    checked: usize,
+    invalid: usize,
+}
+
+impl Cli {
+    pub fn new() -> Self {
+        Cli::default()
+    }
+
+    /// Make `T` available as `--type NAME`.
+    pub fn register<T: ValidateFields + 'static>(mut self, name: &str) -> Self {
+        let registered = Registered {
+            validate: Box::new(|json, opts| validate_json_with_report::<T>(json, opts)),
+            plan: T::validation_plan(),
+            typescript: |generator, name| generator.add_type::<T>(name),
+        };
+        self.types.insert(name.to_string(), registered);
+        self
+    }
+
+    /// Run with the given arguments (without the program name), printing
+    /// reports to stdout and problems to stderr.
+    pub fn run(&self, args: impl IntoIterator<Item = String>) -> ExitCode {
+        match self.try_run(args) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::from(1),
+            Err(msg) => {
+                eprintln!("field-validator: {}", msg);
+                ExitCode::from(2)
+            }
+        }
+    }
+
+    /// Whether every payload was valid, or a usage / I/O error.
+    fn try_run(&self, args: impl IntoIterator<Item = String>) -> Result<bool, String> {
+        let mut args = args.into_iter().peekable();
+        if args.peek().is_some_and(|a| a == "typescript") {
+            args.next();
+            return self.typescript(args).map(|()| true);
+        }
+        let Some(args) = parse_args(args)? else {
+            println!("{}", USAGE);
+            return Ok(true);
+        };
+
+        let schema;
+        let (validate, plan): (&ValidateFn<'_>, &ValidationPlan) = match &args.target {
+            Target::Type(name) => {
+                let registered = self.registered(name)?;
+                (&registered.validate, registered.plan)
+            }
+            Target::Schema(path) => {
+                schema = DynamicSchema::from_path(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                (&|json, opts| schema.validate_json_with_report(json, opts), schema.plan())
+            }
+        };
+
+        let mut checker = Checker { args: &args, validate, plan, checked: 0, invalid: 0 };
+        for path in &args.paths {
+            checker.check_path(path)?;
+        }
+
+        let summary = format!(
+            "checked {} payloads: {} valid, {} invalid",
+            checker.checked,
+            checker.checked - checker.invalid,
+            checker.invalid
+        );
+        if args.json_output {
+            eprintln!("{}", summary);
+        } else {
+            println!("{}", summary);
+        }
+        Ok(checker.invalid == 0)
+    }
+}
+
+impl Cli {
+    fn registered(&self, name: &str) -> Result<&Registered, String> {
+        self.types.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.types.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                format!("unknown type `{}`; no types are registered, use --schema", name)
+            } else {
+                format!("unknown type `{}`; expected one of: {}", name, known.join(", "))
+            }
+        })
+    }
+
+    /// The `typescript` command.
+    fn typescript(&self, args: impl Iterator<Item = String>) -> Result<(), String> {
+        let mut generator = TypeScriptGenerator::new();
+        let mut any = false;
+        let mut out = None;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |flag: &str| args.next().ok_or_else(|| format!("`{}` expects a value", flag));
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    return Ok(());
+                }
+                "--type" => {
+                    let name = value("--type")?;
+                    generator = (self.registered(&name)?.typescript)(generator, &name);
+                    any = true;
+                }
+                "--schema" => {
+                    let path = PathBuf::from(value("--schema")?);
+                    let schema =
+                        DynamicSchema::from_path(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+                    generator = generator.add_schema(&schema);
+                    any = true;
+                }
+                "--validators" => generator = generator.validators(true),
+                "--out" => out = Some(PathBuf::from(value("--out")?)),
+                other => return Err(format!("unexpected argument `{}`\n\n{}", other, USAGE)),
+            }
+        }
+        if !any {
+            if self.types.is_empty() {
+                return Err(format!("no types are registered, use --schema\n\n{}", USAGE));
+            }
+            for (name, registered) in &self.types {
+                generator = (registered.typescript)(generator, name);
+            }
+        }
+        match out {
+            Some(path) => {
+                generator
+                    .write_if_changed(&path)
+                    .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+            }
+            None => print!("{}", generator.generate()),
+        }
+        Ok(())
+    }
+}
+
+/// `None` when help was requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut target = None;
+    let mut json_output = false;
+    let mut ndjson = false;
+    let mut annotate = false;
+    let mut opts = ValidatorOptions::default();
+    let mut paths = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| args.next().ok_or_else(|| format!("`{}` expects a value", flag));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--type" => set_target(&mut target, Target::Type(value("--type")?))?,
+            "--schema" => set_target(&mut target, Target::Schema(value("--schema")?.into()))?,
+            "--format" => {
+                json_output = match value("--format")?.as_str() {
+                    "human" => false,
+                    "json" => true,
+                    other => return Err(format!("unknown format `{}`; expected human or json", other)),
+                }
+            }
+            "--ndjson" => ndjson = true,
+            "--annotate" => annotate = true,
+            "--api-version" => {
+                let v = value("--api-version")?;
+                let version = crate::options::parse_version(&v)
+                    .ok_or_else(|| format!("invalid API version `{}`", v))?;
+                opts.api_version = Some(version);
+            }
+            "--fail-fast" => opts.mode = crate::options::ValidationMode::FailFast,
+            "--max-errors" => {
+                let v = value("--max-errors")?;
+                opts.max_errors = v
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("invalid error count `{}`", v))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{}`\n\n{}", flag, USAGE)),
+            _ => paths.push(arg),
+        }
+    }
+
+    let target = target.ok_or_else(|| format!("one of --type or --schema is required\n\n{}", USAGE))?;
+    if paths.is_empty() {
+        return Err(format!("no input paths given\n\n{}", USAGE));
+    }
+    Ok(Some(Args { target, json_output, ndjson, annotate, opts, paths }))
+}
+
+fn set_target(slot: &mut Option<Target>, target: Target) -> Result<(), String> {
+    if slot.is_some() {
+        return Err("only one of --type or --schema may be given".to_string());
+    }
+    *slot = Some(target);
+    Ok(())
+}
+
+impl Checker<'_> {
+    fn check_path(&mut self, path: &str) -> Result<(), String> {
+        if path == "-" {
+            let source = "<stdin>";
+            let failed = |e: io::Error| format!("cannot read stdin: {}", e);
+            if self.args.ndjson {
+                return self.check_lines(source, io::stdin().lock()).map_err(failed);
+            }
+            return self.check_whole(source, io::stdin().lock()).map_err(failed);
+        }
+        let path = Path::new(path);
+        if path.is_dir() {
+            let mut files = Vec::new();
+            walk_dir(path, &mut files)?;
+            files.sort();
+            for file in files {
+                self.check_file(&file)?;
+            }
+            Ok(())
+        } else {
+            self.check_file(path)
+        }
+    }
+
+    /// JSON Lines files are streamed rather than read whole.
+    fn check_file(&mut self, path: &Path) -> Result<(), String> {
+        let source = path.display().to_string();
+        let failed = |e: io::Error| format!("cannot read {}: {}", source, e);
+        let lines = self.args.ndjson
+            || matches!(path.extension().and_then(|e| e.to_str()), Some("jsonl" | "ndjson"));
+        let file = File::open(path).map_err(failed)?;
+        if lines {
+            self.check_lines(&source, BufReader::new(file)).map_err(failed)
+        } else {
+            self.check_whole(&source, file).map_err(failed)
+        }
+    }
+
+    /// Read one document, buffering at most `max_bytes + 1` bytes of it. A
+    /// longer document fails with a `Bytes` limit error and one that is not
+    /// valid UTF-8 as invalid JSON, like lines of a JSON Lines input.
+    fn check_whole(&mut self, source: &str, reader: impl Read) -> io::Result<()> {
+        let body = match read_payload(reader, &self.args.opts) {
+            Ok(body) => body,
+            Err(ValidationError::Io(e)) => return Err(e),
+            Err(err) => {
+                self.tally(&Record { source, line: None, json: "" }, Err(err));
+                return Ok(());
+            }
+        };
+        match String::from_utf8(body) {
+            Ok(text) => self.check(Record { source, line: None, json: &text }),
+            Err(e) => {
+                // serde_json reports where the invalid sequence is
+                let err = serde_json::from_slice::<Value>(e.as_bytes())
+                    .expect_err("invalid UTF-8 is never valid JSON");
+                let text = String::from_utf8_lossy(e.as_bytes());
+                self.tally(&Record { source, line: None, json: &text }, Err(ValidationError::Syntax(err)));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_lines(&mut self, source: &str, reader: impl io::BufRead) -> io::Result<()> {
+        let (args, plan) = (self.args, self.plan);
+        let stats = validate_ndjson_each(reader, &args.opts, self.validate, |line, json, result| {
+            report(args, plan, &Record { source, line: Some(line), json }, result);
+        })?;
+        self.checked += stats.total;
+        self.invalid += stats.invalid;
+        Ok(())
+    }
+
+    fn check(&mut self, record: Record<'_>) {
+        let result = (self.validate)(record.json, &self.args.opts);
+        self.tally(&record, result);
+    }
+
+    fn tally(&mut self, record: &Record<'_>, result: Result<ValidationReport, ValidationError>) {
+        self.checked += 1;
+        if result.is_err() {
+            self.invalid += 1;
+        }
+        report(self.args, self.plan, record, &result);
+    }
+}
+
+fn report(args: &Args, plan: &ValidationPlan, record: &Record<'_>, result: &Result<ValidationReport, ValidationError>) {
+    if args.json_output {
+        println!("{}", json_report(record, result));
+    } else {
+        print_human(record, result);
+        if args.annotate {
+            print_annotated(record, result, plan);
+        }
+    }
+}
+
+/// Collect the `.json`, `.jsonl` and `.ndjson` files under `dir`.
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("cannot read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("cannot read {}: {}", dir.display(), e))?.path();
+        if path.is_dir() {
+            walk_dir(&path, files)?;
+        } else if matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("json" | "jsonl" | "ndjson")
+        ) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn print_human(record: &Record<'_>, result: &Result<ValidationReport, ValidationError>) {
+    let location = match record.line {
+        Some(line) => format!("{}:{}", record.source, line),
+        None => record.source.to_string(),
+    };
+    match result {
+        Ok(report) if report.warnings.is_empty() => println!("{}: ok", location),
+        Ok(report) => {
+            println!("{}: ok, {} warning(s)", location, report.warnings.len());
+            for warning in &report.warnings {
+                println!("    warning: {}", warning);
+            }
+        }
+        Err(err) => println!("{}: invalid ({}): {}", location, err.code(), err),
+    }
+}
+
+/// The problems of `record` rendered against its source, indented.
+fn print_annotated(record: &Record<'_>, result: &Result<ValidationReport, ValidationError>, plan: &ValidationPlan) {
+    let renderer = SourceRenderer::with_plan(record.json, plan);
+    let rendered = match result {
+        Ok(report) if report.warnings.is_empty() => return,
+        Ok(report) => renderer.render_findings(&report.warnings),
+        Err(err) => renderer.render_error(err),
+    };
+    for line in rendered.lines() {
+        if line.is_empty() {
+            println!();
+        } else {
+            println!("    {}", line);
+        }
+    }
+}
+
+fn json_report(record: &Record<'_>, result: &Result<ValidationReport, ValidationError>) -> Value {
+    let mut out = json!({ "source": record.source, "line": record.line, "valid": result.is_ok() });
+    match result {
+        Ok(report) => {
+            out["warnings"] = findings_json(&report.warnings);
+        }
+        Err(err) => {
+            out["code"] = json!(err.code());
+            out["message"] = json!(err.to_string());
+            match err {
+                ValidationError::MissingFields(missing) => {
+                    out["missingFields"] = json!(missing.missing_fields);
+                }
+                ValidationError::InvalidFields(findings) => {
+                    out["findings"] = findings_json(findings);
+                }
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+fn findings_json(findings: &[Finding]) -> Value {
+    findings
+        .iter()
+        .map(|f| json!({ "path": f.path, "code": f.kind.code(), "message": f.to_string() }))
+        .collect()
+}
@@ -0,0 +1,173 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::format::Format;
+
+/// The JSON shape a field's value is expected to have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeShape {
+    String,
+    Integer,
+    Float,
+    Bool,
+    /// A JSON array whose elements have the given shape.
+    Array(&'static TypeShape),
+    /// A JSON object with arbitrary keys whose values have the given shape.
+    Map(&'static TypeShape),
+    /// A nested struct; see [`FieldDescriptor::nested`] for its fields.
+    Object,
+    /// Any JSON value (`serde_json::Value`).
+    Any,
+    /// A type the derive does not know how to describe.
+    Other,
+}
+
+impl TypeShape {
+    /// Whether a non-null JSON value has this shape. `null` elements inside
+    /// arrays and maps are accepted, since the shape does not record whether
+    /// they are `Option`s.
+    pub fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (TypeShape::Any | TypeShape::Other, _) => true,
+            (TypeShape::String, Value::String(_)) => true,
+            (TypeShape::Integer, Value::Number(n)) => n.is_i64() || n.is_u64(),
+            (TypeShape::Float, Value::Number(_)) => true,
+            (TypeShape::Bool, Value::Bool(_)) => true,
+            (TypeShape::Object, Value::Object(_)) => true,
+            (TypeShape::Array(inner), Value::Array(items)) => {
+                items.iter().all(|v| v.is_null() || inner.matches(v))
+            }
+            (TypeShape::Map(inner), Value::Object(map)) => {
+                map.values().all(|v| v.is_null() || inner.matches(v))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for TypeShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeShape::String => write!(f, "string"),
+            TypeShape::Integer => write!(f, "integer"),
+            TypeShape::Float => write!(f, "number"),
+            TypeShape::Bool => write!(f, "boolean"),
+            TypeShape::Array(inner) => write!(f, "array of {}", inner),
+            TypeShape::Map(inner) => write!(f, "map of {}", inner),
+            TypeShape::Object => write!(f, "object"),
+            TypeShape::Any | TypeShape::Other => write!(f, "any"),
+        }
+    }
+}
+
+/// A validation rule attached to a field beyond plain required-ness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Constraint {
+    /// Required only when the named sibling field is present and non-null.
+    RequiredIf(&'static str),
+    /// A supplied value must have this format.
+    Format(Format),
+    /// A supplied value must be one of these strings, e.g. the wire names
+    /// of a `StringEnum`.
+    OneOf(&'static [&'static str]),
+    /// Another key the field is accepted under, from `#[serde(alias)]`.
+    Alias(&'static str),
+}
+
+/// Link from a field to the `ValidateFields` type it contains.
+#[derive(Debug, Clone, Copy)]
+pub struct NestedType {
+    /// The Rust name of the nested type.
+    pub name: &'static str,
+    /// That type's descriptor table.
+    pub fields: fn() -> &'static [FieldDescriptor],
+}
+
+/// Static description of one field of a `ValidateFields` type.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldDescriptor {
+    /// The key used on the wire, after serde renames.
+    pub name: &'static str,
+    /// The Rust field identifier.
+    pub rust_name: &'static str,
+    /// The expected JSON shape, looking through `Option`.
+    pub shape: TypeShape,
+    /// Whether the field may be absent (it is not in `required_fields()`).
+    pub optional: bool,
+    /// Whether the field may be `null` (its type is an `Option`).
+    pub nullable: bool,
+    pub constraints: &'static [Constraint],
+    /// First API version in which the field exists, if limited.
+    pub since: Option<u32>,
+    /// First API version in which the field no longer exists, if limited.
+    pub until: Option<u32>,
+    /// What to use instead, for fields that still work but should not be sent.
+    pub deprecated: Option<&'static str>,
+    /// Whether messages about the field must not echo its value.
+    pub sensitive: bool,
+    /// Message template for problems with the field, replacing the one
+    /// for the problem's code.
+    pub message: Option<&'static str>,
+    /// The field's doc comment, or an empty string.
+    pub doc: &'static str,
+    /// The nested type for fields marked `#[field_validator(nested)]`.
+    pub nested: Option<NestedType>,
+}
+
+impl FieldDescriptor {
+    /// Descriptors of the nested type, or an empty slice.
+    pub fn nested_fields(&self) -> &'static [FieldDescriptor] {
+        self.nested.map(|n| (n.fields)()).unwrap_or(&[])
+    }
+
+    /// Whether the field exists in `version`; `None` means the latest
+    /// version, in which fields with an `until` are gone.
+    pub fn exists_in(&self, version: Option<u32>) -> bool {
+        version_in_range(version, self.since, self.until)
+    }
+
+    /// Whether the field is unconditionally required in `version`.
+    pub fn required_in(&self, version: Option<u32>) -> bool {
+        !self.optional && self.exists_in(version)
+    }
+
+    /// The sibling fields whose presence makes this field required.
+    pub fn required_if(&self) -> impl Iterator<Item = &'static str> {
+        self.constraints.iter().filter_map(|c| match *c {
+            Constraint::RequiredIf(trigger) => Some(trigger),
+            _ => None,
+        })
+    }
+
+    /// Other keys the field is accepted under.
+    pub fn aliases(&self) -> impl Iterator<Item = &'static str> {
+        self.constraints.iter().filter_map(|c| match *c {
+            Constraint::Alias(alias) => Some(alias),
+            _ => None,
+        })
+    }
+
+    /// The strings supplied values must be one of, if limited.
+    pub fn allowed(&self) -> Option<&'static [&'static str]> {
+        self.constraints.iter().find_map(|c| match *c {
+            Constraint::OneOf(values) => Some(values),
+            _ => None,
+        })
+    }
+
+    /// The format supplied values must have, if any.
+    pub fn format(&self) -> Option<Format> {
+        self.constraints.iter().find_map(|c| match *c {
+            Constraint::Format(format) => Some(format),
+            _ => None,
+        })
+    }
+}
+
+/// Whether `version` falls in `[since, until)`; `None` is the latest version.
+pub(crate) fn version_in_range(version: Option<u32>, since: Option<u32>, until: Option<u32>) -> bool {
+    match version {
+        None => until.is_none(),
+        Some(v) => since.is_none_or(|s| v >= s) && until.is_none_or(|u| v < u),
+    }
+}
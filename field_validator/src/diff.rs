@@ -0,0 +1,293 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::descriptor::TypeShape;
+use crate::format::Format;
+use crate::schema::{DynamicSchema, SchemaField};
+use crate::validate::ValidateFields;
+
+/// Set to accept breaking changes in `assert_schema_compatible` once they
+/// have been reviewed; the snapshot is rewritten instead of failing.
+pub const ACCEPT_BREAKING_ENV: &str = "FIELD_VALIDATOR_ACCEPT_BREAKING";
+
+/// The changes between two snapshots of a request schema, from the point of
+/// view of clients sending payloads built against the older one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub changes: Vec<SchemaChange>,
+}
+
+/// One change to one field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    /// Dotted path of the field; fields of array and map elements are
+    /// written `history.*.model`.
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+/// When a field has to be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Requirement {
+    Always,
+    /// Only when this sibling is present and non-null.
+    If(String),
+    Never,
+}
+
+/// What changed about a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The field is new; breaking if clients must send it.
+    Added { requirement: Requirement },
+    /// The field is gone, so payloads still sending it are rejected.
+    /// Removing a field already retired with `until` is not reported.
+    Removed,
+    /// The field is required in more or fewer situations.
+    RequirementChanged { from: Requirement, to: Requirement },
+    /// A supplied `null` is now accepted or rejected.
+    NullabilityChanged { nullable: bool },
+    /// The JSON type changed; compatible only if every old value is still
+    /// accepted, e.g. `integer` to `number` or anything to `any`.
+    TypeChanged { from: TypeShape, to: TypeShape },
+    /// The format was added, changed or dropped.
+    FormatChanged { from: Option<Format>, to: Option<Format> },
+    /// The list of allowed string values was added, changed or dropped;
+    /// compatible only if no old value was removed.
+    AllowedValuesChanged { from: Option<Vec<String>>, to: Option<Vec<String>> },
+    /// The field was deprecated.
+    Deprecated { note: String },
+    /// The field was retired from API version `until` on; clients pinned to
+    /// older versions are unaffected.
+    Retired { until: u32 },
+}
+
+impl ChangeKind {
+    /// Whether payloads valid under the old schema may be rejected now.
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            ChangeKind::Added { requirement } => *requirement != Requirement::Never,
+            ChangeKind::Removed => true,
+            ChangeKind::RequirementChanged { from, to } => {
+                !matches!((from, to), (_, Requirement::Never) | (Requirement::Always, _))
+            }
+            ChangeKind::NullabilityChanged { nullable } => !nullable,
+            ChangeKind::TypeChanged { from, to } => !widens(*from, *to),
+            ChangeKind::FormatChanged { to, .. } => to.is_some(),
+            ChangeKind::AllowedValuesChanged { from, to } => match (from, to) {
+                (_, None) => false,
+                (None, Some(_)) => true,
+                (Some(from), Some(to)) => from.iter().any(|value| !to.contains(value)),
+            },
+            ChangeKind::Deprecated { .. } | ChangeKind::Retired { .. } => false,
+        }
+    }
+}
+
+impl SchemaChange {
+    pub fn is_breaking(&self) -> bool {
+        self.kind.is_breaking()
+    }
+}
+
+impl SchemaDiff {
+    /// Compare two snapshots of a schema.
+    pub fn between(old: &DynamicSchema, new: &DynamicSchema) -> Self {
+        let mut changes = Vec::new();
+        diff_fields(old.fields(), new.fields(), "", &mut changes);
+        SchemaDiff { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(SchemaChange::is_breaking)
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    pub fn compatible(&self) -> impl Iterator<Item = &SchemaChange> {
+        self.changes.iter().filter(|c| !c.is_breaking())
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Always => write!(f, "required"),
+            Requirement::If(trigger) => write!(f, "required if `{}` is present", trigger),
+            Requirement::Never => write!(f, "optional"),
+        }
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = if self.is_breaking() { "breaking" } else { "compatible" };
+        write!(f, "{}: `{}` ", label, self.path)?;
+        match &self.kind {
+            ChangeKind::Added { requirement } => write!(f, "was added ({})", requirement),
+            ChangeKind::Removed => write!(f, "was removed"),
+            ChangeKind::RequirementChanged { from, to } => write!(f, "changed from {} to {}", from, to),
+            ChangeKind::NullabilityChanged { nullable: true } => write!(f, "now accepts null"),
+            ChangeKind::NullabilityChanged { nullable: false } => write!(f, "no longer accepts null"),
+            ChangeKind::TypeChanged { from, to } => write!(f, "changed type from {} to {}", from, to),
+            ChangeKind::FormatChanged { from, to } => match (from, to) {
+                (None, Some(to)) => write!(f, "now requires format {}", to),
+                (Some(from), None) => write!(f, "no longer requires format {}", from),
+                (Some(from), Some(to)) => write!(f, "changed format from {} to {}", from, to),
+                (None, None) => write!(f, "changed format"),
+            },
+            ChangeKind::AllowedValuesChanged { from, to } => match (from, to) {
+                (None, Some(to)) => write!(f, "now only allows {}", to.join(", ")),
+                (Some(_), None) => write!(f, "no longer restricts its values"),
+                (Some(from), Some(to)) => {
+                    write!(f, "changed allowed values from {} to {}", from.join(", "), to.join(", "))
+                }
+                (None, None) => write!(f, "changed allowed values"),
+            },
+            ChangeKind::Deprecated { note } => write!(f, "was deprecated: {}", note),
+            ChangeKind::Retired { until } => write!(f, "was retired from API version {}", until),
+        }
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether every value of shape `from` is also a value of shape `to`.
+fn widens(from: TypeShape, to: TypeShape) -> bool {
+    match (from, to) {
+        (_, TypeShape::Any | TypeShape::Other) => true,
+        (TypeShape::Integer, TypeShape::Float) => true,
+        (TypeShape::Array(from), TypeShape::Array(to)) | (TypeShape::Map(from), TypeShape::Map(to)) => {
+            widens(*from, *to)
+        }
+        (from, to) => from == to,
+    }
+}
+
+fn requirement(field: &SchemaField) -> Requirement {
+    match &field.required_if {
+        Some(trigger) => Requirement::If(trigger.clone()),
+        None if field.optional => Requirement::Never,
+        None => Requirement::Always,
+    }
+}
+
+fn diff_fields(old: &[SchemaField], new: &[SchemaField], prefix: &str, out: &mut Vec<SchemaChange>) {
+    let path_of = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        }
+    };
+    for old_field in old {
+        if old_field.until.is_none() && !new.iter().any(|f| f.name == old_field.name) {
+            out.push(SchemaChange { path: path_of(&old_field.name), kind: ChangeKind::Removed });
+        }
+    }
+    for new_field in new {
+        let path = path_of(&new_field.name);
+        let Some(old_field) = old.iter().find(|f| f.name == new_field.name) else {
+            out.push(SchemaChange { path, kind: ChangeKind::Added { requirement: requirement(new_field) } });
+            continue;
+        };
+        let mut push = |kind| out.push(SchemaChange { path: path.clone(), kind });
+        let (from, to) = (requirement(old_field), requirement(new_field));
+        // Nullability follows the requirement unless set explicitly, and a
+        // null required field is reported as missing anyway.
+        let implied = from != to && old_field.nullable.is_none() && new_field.nullable.is_none();
+        if from != to {
+            push(ChangeKind::RequirementChanged { from, to });
+        }
+        if !implied && old_field.is_nullable() != new_field.is_nullable() {
+            push(ChangeKind::NullabilityChanged { nullable: new_field.is_nullable() });
+        }
+        if let (Some(from), Some(to)) = (old_field.shape(), new_field.shape()) {
+            if from != to {
+                push(ChangeKind::TypeChanged { from, to });
+            }
+        }
+        let (from, to) = (old_field.format(), new_field.format());
+        if from != to {
+            push(ChangeKind::FormatChanged { from, to });
+        }
+        if old_field.allowed != new_field.allowed {
+            push(ChangeKind::AllowedValuesChanged {
+                from: old_field.allowed.clone(),
+                to: new_field.allowed.clone(),
+            });
+        }
+        if old_field.deprecated.is_none() {
+            if let Some(note) = &new_field.deprecated {
+                push(ChangeKind::Deprecated { note: note.clone() });
+            }
+        }
+        if old_field.until != new_field.until {
+            if let Some(until) = new_field.until {
+                push(ChangeKind::Retired { until });
+            }
+        }
+        // A field that stopped listing its fields no longer checks them,
+        // which only accepts more payloads.
+        if !new_field.fields.is_empty() {
+            let child = match new_field.ty.as_str() {
+                "array" | "map" => format!("{}.*", path),
+                _ => path,
+            };
+            diff_fields(&old_field.fields, &new_field.fields, &child, out);
+        }
+    }
+}
+
+/// Compare `T`'s current schema with the snapshot committed at `snapshot`,
+/// panicking if it has a breaking change; for use in tests.
+///
+/// A missing snapshot is written, and so is one with only compatible
+/// changes, so the change shows up in review. Once a breaking change has
+/// been reviewed, run the test with `FIELD_VALIDATOR_ACCEPT_BREAKING=1` to
+/// rewrite the snapshot.
+#[track_caller]
+pub fn assert_schema_compatible<T: ValidateFields>(snapshot: impl AsRef<Path>) -> SchemaDiff {
+    let snapshot = snapshot.as_ref();
+    let type_name = std::any::type_name::<T>();
+    let name = type_name.rsplit("::").next().unwrap_or(type_name);
+    let current = DynamicSchema::for_type::<T>(name);
+    let write = || {
+        std::fs::write(snapshot, current.to_json() + "\n")
+            .unwrap_or_else(|e| panic!("cannot write schema snapshot {}: {}", snapshot.display(), e))
+    };
+    if !snapshot.exists() {
+        write();
+        return SchemaDiff::default();
+    }
+    let committed = DynamicSchema::from_path(snapshot)
+        .unwrap_or_else(|e| panic!("cannot read schema snapshot {}: {}", snapshot.display(), e));
+    let diff = committed.diff(&current);
+    let accepted = std::env::var_os(ACCEPT_BREAKING_ENV).is_some_and(|v| !v.is_empty() && v != "0");
+    if diff.is_breaking() && !accepted {
+        panic!(
+            "{} has breaking changes against {}:\n{}\nIf they are intended, rerun with {}=1 to update the snapshot.",
+            type_name,
+            snapshot.display(),
+            diff,
+            ACCEPT_BREAKING_ENV
+        );
+    }
+    if committed.to_json() != current.to_json() {
+        write();
+    }
+    diff
+}
@@ -0,0 +1,202 @@
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::limits::{parse_slice_with_limits, parse_with_limits};
+use crate::migrate::MigrationRegistry;
+use crate::observe::observe;
+use crate::options::ValidatorOptions;
+use crate::redact;
+use crate::report::ValidationReport;
+use crate::validate::{ValidateFields, ValidationError};
+
+type Route<Out> =
+    Box<dyn Fn(Value, &ValidatorOptions) -> Result<(Out, ValidationReport), DispatchError> + Send + Sync>;
+
+/// Routes payloads to a type and handler by a discriminator field such as
+/// `action`, so one entry point can serve several request kinds.
+///
+/// Each payload is parsed once, validated against the type registered for
+/// its discriminator value, deserialized into it and passed to that
+/// handler. Handlers return any `Out`; async handlers return a boxed future.
+///
+/// ```
+/// # use field_validator::ValidateFields;
+/// # use field_validator::dispatch::Dispatcher;
+/// # #[derive(serde::Deserialize)] struct GetMode { bike_identifier: String }
+/// # impl ValidateFields for GetMode { fn required_fields() -> &'static [&'static str] { &["bike_identifier"] } }
+/// let dispatcher = Dispatcher::new("action")
+///     .route::<GetMode, _>("get_mode", |req| format!("mode of {}", req.bike_identifier));
+///
+/// let json = r#"{"action": "get_mode", "bike_identifier": "B1"}"#;
+/// let (out, _report) = dispatcher.dispatch(json, &Default::default()).unwrap();
+/// assert_eq!(out, "mode of B1");
+/// ```
+pub struct Dispatcher<Out> {
+    field: String,
+    fallback: Option<String>,
+    routes: BTreeMap<String, Route<Out>>,
+}
+
+/// Why a payload could not be dispatched.
+#[derive(Debug)]
+pub enum DispatchError {
+    /// The payload could not be parsed under the configured limits.
+    Parse(ValidationError),
+    /// The discriminator field is absent or not a string, and there is no fallback.
+    MissingAction { field: String, valid: Vec<String> },
+    /// No type is registered for the discriminator value.
+    UnknownAction { field: String, action: String, valid: Vec<String> },
+    /// The payload failed validation against the action's type.
+    Invalid { action: String, error: ValidationError },
+    /// The payload passed validation but could not be deserialized.
+    Deserialize { action: String, error: Box<dyn std::error::Error> },
+}
+
+impl DispatchError {
+    /// Stable identifier used in responses and metrics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DispatchError::Parse(e) | DispatchError::Invalid { error: e, .. } => e.code(),
+            DispatchError::MissingAction { .. } => "missing_action",
+            DispatchError::UnknownAction { .. } => "unknown_action",
+            DispatchError::Deserialize { .. } => "invalid_payload",
+        }
+    }
+}
+
+impl fmt::Display for DispatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DispatchError::Parse(e) => e.fmt(f),
+            DispatchError::MissingAction { field, valid } => {
+                write!(f, "missing `{}`; expected one of: {}", field, valid.join(", "))
+            }
+            DispatchError::UnknownAction { field, action, valid } => write!(
+                f,
+                "unknown {} `{}`; expected one of: {}",
+                field,
+                redact::value(action),
+                valid.join(", ")
+            ),
+            DispatchError::Invalid { error, .. } => error.fmt(f),
+            DispatchError::Deserialize { error, .. } => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DispatchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DispatchError::Parse(e) | DispatchError::Invalid { error: e, .. } => Some(e),
+            DispatchError::Deserialize { error, .. } => Some(&**error),
+            _ => None,
+        }
+    }
+}
+
+impl<Out> Dispatcher<Out> {
+    /// A dispatcher keyed on the top-level string field `field`.
+    pub fn new(field: impl Into<String>) -> Self {
+        Dispatcher { field: field.into(), fallback: None, routes: BTreeMap::new() }
+    }
+
+    /// Handle payloads whose discriminator is `action` as a `T`, replacing
+    /// any route already registered for it.
+    pub fn route<T, F>(mut self, action: &str, handler: F) -> Self
+    where
+        T: ValidateFields + DeserializeOwned,
+        F: Fn(T) -> Out + Send + Sync + 'static,
+    {
+        let name = action.to_string();
+        self.routes.insert(
+            action.to_string(),
+            Box::new(move |value, opts| {
+                let check = || {
+                    T::validation_plan().check_payload_limited(&value, opts.version_for(&value), opts.error_limit())
+                };
+                let report = observe(opts, std::any::type_name::<T>(), check, |report| report)
+                    .map_err(|error| DispatchError::Invalid { action: name.clone(), error })?;
+                let data = deserialize::<T>(&name, value)?;
+                Ok((handler(data), report))
+            }),
+        );
+        self
+    }
+
+    /// Like `route`, upgrading legacy payloads through `migrations` first.
+    pub fn route_migrated<T, F>(mut self, action: &str, migrations: &'static MigrationRegistry, handler: F) -> Self
+    where
+        T: ValidateFields + DeserializeOwned,
+        F: Fn(T) -> Out + Send + Sync + 'static,
+    {
+        let name = action.to_string();
+        self.routes.insert(
+            action.to_string(),
+            Box::new(move |value, opts| {
+                let (value, report) = migrations
+                    .migrate_and_validate_value::<T>(value, opts)
+                    .map_err(|error| DispatchError::Invalid { action: name.clone(), error })?;
+                let data = deserialize::<T>(&name, value)?;
+                Ok((handler(data), report))
+            }),
+        );
+        self
+    }
+
+    /// Send payloads without the discriminator field to `action`, e.g. for
+    /// clients that predate it.
+    pub fn fallback(mut self, action: &str) -> Self {
+        self.fallback = Some(action.to_string());
+        self
+    }
+
+    /// The registered discriminator values, sorted.
+    pub fn actions(&self) -> Vec<String> {
+        self.routes.keys().cloned().collect()
+    }
+
+    /// Parse `json` under `opts` and run the handler for its discriminator.
+    pub fn dispatch(&self, json: &str, opts: &ValidatorOptions) -> Result<(Out, ValidationReport), DispatchError> {
+        let value = parse_with_limits(json, opts).map_err(|e| DispatchError::Parse(e.into()))?;
+        self.dispatch_value(value, opts)
+    }
+
+    /// `dispatch` for a raw body, without converting it to a `String` first.
+    pub fn dispatch_slice(&self, json: &[u8], opts: &ValidatorOptions) -> Result<(Out, ValidationReport), DispatchError> {
+        let value = parse_slice_with_limits(json, opts).map_err(|e| DispatchError::Parse(e.into()))?;
+        self.dispatch_value(value, opts)
+    }
+
+    /// `dispatch` for an already-parsed payload; limits are not re-checked.
+    pub fn dispatch_value(&self, value: Value, opts: &ValidatorOptions) -> Result<(Out, ValidationReport), DispatchError> {
+        let action = match value.get(&self.field).and_then(Value::as_str) {
+            Some(action) => action.to_string(),
+            None => match &self.fallback {
+                Some(fallback) => fallback.clone(),
+                None => {
+                    return Err(DispatchError::MissingAction {
+                        field: self.field.clone(),
+                        valid: self.actions(),
+                    })
+                }
+            },
+        };
+        match self.routes.get(&action) {
+            Some(route) => route(value, opts),
+            None => Err(DispatchError::UnknownAction {
+                field: self.field.clone(),
+                action,
+                valid: self.actions(),
+            }),
+        }
+    }
+}
+
+fn deserialize<T: ValidateFields + DeserializeOwned>(action: &str, value: Value) -> Result<T, DispatchError> {
+    serde_json::from_value(value).map_err(|e| DispatchError::Deserialize {
+        action: action.to_string(),
+        error: redact::deserialize_error(e, T::validation_plan().has_sensitive_fields()),
+    })
+}
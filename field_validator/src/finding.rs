@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::descriptor::TypeShape;
+use crate::format::Format;
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The request is still processed; surface it to the client.
+    Warning,
+    /// The request is rejected.
+    Error,
+}
+
+/// What is wrong with a single field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// A required field is absent or null.
+    Missing,
+    /// The value does not have the expected JSON shape.
+    WrongType { expected: TypeShape },
+    /// The key is not a field of the type.
+    UnknownField,
+    /// The value has the right type but not the field's format.
+    InvalidFormat { format: Format },
+    /// The value is not one of the field's allowed values.
+    InvalidValue { allowed: Vec<String> },
+    /// The field is deprecated; `note` says what to use instead.
+    Deprecated { note: String },
+}
+
+impl FindingKind {
+    /// Stable identifier used in responses and metrics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            FindingKind::Missing => "missing_field",
+            FindingKind::WrongType { .. } => "wrong_type",
+            FindingKind::UnknownField => "unknown_field",
+            FindingKind::InvalidFormat { .. } => "invalid_format",
+            FindingKind::InvalidValue { .. } => "invalid_value",
+            FindingKind::Deprecated { .. } => "deprecated_field",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            FindingKind::Deprecated { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+/// A problem with one field of a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Dotted path of the field, e.g. `bike_info.year`.
+    pub path: String,
+    pub kind: FindingKind,
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subject = if self.path.is_empty() {
+            "payload".to_string()
+        } else {
+            format!("`{}`", self.path)
+        };
+        match &self.kind {
+            FindingKind::Missing => write!(f, "{} is required", subject),
+            FindingKind::WrongType { expected } => {
+                write!(f, "{} must be {} {}", subject, article(expected), expected)
+            }
+            FindingKind::UnknownField => write!(f, "{} is not a known field", subject),
+            FindingKind::InvalidFormat { format } => {
+                write!(f, "{} must be {}", subject, format.description())
+            }
+            FindingKind::InvalidValue { allowed } => {
+                write!(f, "{} must be one of: {}", subject, allowed.join(", "))
+            }
+            FindingKind::Deprecated { note } => write!(f, "{} is deprecated: {}", subject, note),
+        }
+    }
+}
+
+fn article(shape: &TypeShape) -> &'static str {
+    match shape.to_string().chars().next() {
+        Some('a' | 'e' | 'i' | 'o' | 'u') => "an",
+        _ => "a",
+    }
+}
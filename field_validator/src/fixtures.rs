@@ -0,0 +1,360 @@
+use serde_json::{Map, Value};
+
+use crate::descriptor::{FieldDescriptor, TypeShape};
+use crate::validate::ValidateFields;
+
+/// How an invalid fixture was derived from the minimal example.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    /// The required field was removed.
+    Removed,
+    /// The required field was set to `null`.
+    Nulled,
+    /// The field was given a value of the wrong JSON type.
+    WrongType,
+    /// The trigger of a `required_if` field was set but the field was left out.
+    TriggerWithoutField,
+    /// The field was given a value of the right type but not its format.
+    BadFormat,
+    /// The field was given a string that is not one of its allowed values.
+    NotAllowed,
+}
+
+/// A payload that must fail validation, and why.
+#[derive(Debug, Clone)]
+pub struct InvalidFixture {
+    /// Dotted path of the field that was broken, e.g. `bike_info.model`.
+    pub path: String,
+    pub mutation: Mutation,
+    pub payload: Value,
+}
+
+/// Build the smallest payload that satisfies `T`'s required fields.
+///
+/// Only required fields are included; nested types are filled in
+/// recursively. Fields whose shape is not described fall back to a string.
+pub fn example_json<T: ValidateFields>() -> Value {
+    if T::fields().is_empty() {
+        let obj = T::required_fields()
+            .iter()
+            .map(|&name| (name.to_string(), Value::String("example".to_string())))
+            .collect();
+        return Value::Object(obj);
+    }
+    example_object(T::fields(), &mut Vec::new())
+}
+
+/// Build one payload per way of breaking `T`'s rules: every required field
+/// (including those of nested types) removed, nulled, given the wrong type,
+/// given a value without its format and given a value outside its allowed
+/// values, plus every `required_if` field missing while its trigger is set.
+pub fn invalid_variants<T: ValidateFields>() -> Vec<InvalidFixture> {
+    let example = example_json::<T>();
+    let mut out = Vec::new();
+    if T::fields().is_empty() {
+        for &name in T::required_fields() {
+            push_removed_and_nulled(&example, &[name], &mut out);
+        }
+        return out;
+    }
+    collect_invalid(T::fields(), &example, &mut Vec::new(), &mut Vec::new(), &mut out);
+    out
+}
+
+/// `stack` holds the descriptor tables currently being expanded, as in
+/// `ValidationPlan::build`, so a recursive type stops at its first repetition.
+fn example_object(fields: &[FieldDescriptor], stack: &mut Vec<*const FieldDescriptor>) -> Value {
+    stack.push(fields.as_ptr());
+    let obj: Map<String, Value> = fields
+        .iter()
+        .filter(|f| f.required_in(None))
+        .map(|f| (f.name.to_string(), example_value(f, stack)))
+        .collect();
+    stack.pop();
+    Value::Object(obj)
+}
+
+fn example_value(field: &FieldDescriptor, stack: &mut Vec<*const FieldDescriptor>) -> Value {
+    match (field.shape, field.format(), field.allowed()) {
+        (TypeShape::Object, _, _) if repeats(field, stack) => example_for_shape(field.shape),
+        (TypeShape::Object, _, _) => example_object(field.nested_fields(), stack),
+        (TypeShape::Array(_) | TypeShape::Map(_), _, _) => example_for_shape(field.shape),
+        (_, _, Some(&[first, ..])) => Value::from(first),
+        (_, Some(format), _) => format.example(),
+        _ => example_for_shape(field.shape),
+    }
+}
+
+/// Whether `field` nests a descriptor table that is already being expanded.
+fn repeats(field: &FieldDescriptor, stack: &[*const FieldDescriptor]) -> bool {
+    stack.contains(&field.nested_fields().as_ptr())
+}
+
+/// A string that is none of `allowed`.
+fn not_allowed(allowed: &[&str]) -> Value {
+    let mut value = "invalid".to_string();
+    while allowed.contains(&value.as_str()) {
+        value.push('_');
+    }
+    Value::String(value)
+}
+
+/// A valid value for a shape. Containers are left empty.
+pub(crate) fn example_for_shape(shape: TypeShape) -> Value {
+    match shape {
+        TypeShape::String | TypeShape::Other => Value::String("example".to_string()),
+        TypeShape::Integer => Value::from(0),
+        TypeShape::Float => Value::from(0.0),
+        TypeShape::Bool => Value::Bool(false),
+        TypeShape::Array(_) => Value::Array(Vec::new()),
+        TypeShape::Map(_) | TypeShape::Object | TypeShape::Any => Value::Object(Map::new()),
+    }
+}
+
+/// A value that can never deserialize into `shape`, if one exists.
+pub(crate) fn wrong_type_for_shape(shape: TypeShape) -> Option<Value> {
+    match shape {
+        TypeShape::String => Some(Value::from(0)),
+        TypeShape::Integer | TypeShape::Float => Some(Value::String("not a number".to_string())),
+        TypeShape::Bool => Some(Value::String("not a boolean".to_string())),
+        TypeShape::Array(_) | TypeShape::Map(_) | TypeShape::Object => Some(Value::Bool(true)),
+        TypeShape::Any | TypeShape::Other => None,
+    }
+}
+
+fn collect_invalid<'a>(
+    fields: &'a [FieldDescriptor],
+    example: &Value,
+    prefix: &mut Vec<&'a str>,
+    stack: &mut Vec<*const FieldDescriptor>,
+    out: &mut Vec<InvalidFixture>,
+) {
+    stack.push(fields.as_ptr());
+    for field in fields {
+        if !field.exists_in(None) {
+            continue;
+        }
+        prefix.push(field.name);
+        if !field.optional {
+            push_removed_and_nulled(example, prefix, out);
+            if let Some(wrong) = wrong_type_for_shape(field.shape) {
+                let mut payload = example.clone();
+                if let Some(slot) = lookup_mut(&mut payload, prefix) {
+                    *slot = wrong;
+                    out.push(InvalidFixture {
+                        path: prefix.join("."),
+                        mutation: Mutation::WrongType,
+                        payload,
+                    });
+                }
+            }
+            let counterexample = match field.shape {
+                TypeShape::Array(_) | TypeShape::Map(_) => None,
+                _ => field.format().and_then(|f| f.counterexample()),
+            };
+            if let Some(bad) = counterexample {
+                let mut payload = example.clone();
+                if let Some(slot) = lookup_mut(&mut payload, prefix) {
+                    *slot = bad;
+                    out.push(InvalidFixture {
+                        path: prefix.join("."),
+                        mutation: Mutation::BadFormat,
+                        payload,
+                    });
+                }
+            }
+            let outside = match field.shape {
+                TypeShape::Array(_) | TypeShape::Map(_) => None,
+                _ => field.allowed().map(not_allowed),
+            };
+            if let Some(bad) = outside {
+                let mut payload = example.clone();
+                if let Some(slot) = lookup_mut(&mut payload, prefix) {
+                    *slot = bad;
+                    out.push(InvalidFixture {
+                        path: prefix.join("."),
+                        mutation: Mutation::NotAllowed,
+                        payload,
+                    });
+                }
+            }
+            if field.shape == TypeShape::Object && !repeats(field, stack) {
+                collect_invalid(field.nested_fields(), example, prefix, stack, out);
+            }
+        }
+        for trigger in field.required_if() {
+            let Some(trigger_field) = fields.iter().find(|f| f.name == trigger) else {
+                continue;
+            };
+            let mut payload = example.clone();
+            let parent = &prefix[..prefix.len() - 1];
+            if let Some(Value::Object(obj)) = lookup_mut(&mut payload, parent) {
+                obj.insert(trigger.to_string(), example_value(trigger_field, stack));
+                obj.remove(field.name);
+                out.push(InvalidFixture {
+                    path: prefix.join("."),
+                    mutation: Mutation::TriggerWithoutField,
+                    payload,
+                });
+            }
+        }
+        prefix.pop();
+    }
+    stack.pop();
+}
+
+fn push_removed_and_nulled(example: &Value, path: &[&str], out: &mut Vec<InvalidFixture>) {
+    let (last, parent) = match path.split_last() {
+        Some(split) => split,
+        None => return,
+    };
+
+    let mut removed = example.clone();
+    if let Some(Value::Object(obj)) = lookup_mut(&mut removed, parent) {
+        obj.remove(*last);
+        out.push(InvalidFixture {
+            path: path.join("."),
+            mutation: Mutation::Removed,
+            payload: removed,
+        });
+    }
+
+    let mut nulled = example.clone();
+    if let Some(slot) = lookup_mut(&mut nulled, path) {
+        *slot = Value::Null;
+        out.push(InvalidFixture {
+            path: path.join("."),
+            mutation: Mutation::Nulled,
+            payload: nulled,
+        });
+    }
+}
+
+fn lookup_mut<'v>(value: &'v mut Value, path: &[&str]) -> Option<&'v mut Value> {
+    path.iter().try_fold(value, |v, key| v.as_object_mut()?.get_mut(*key))
+}
+
+/// `proptest` strategies producing valid and invalid payloads for a type.
+#[cfg(feature = "proptest")]
+pub mod strategy {
+    use proptest::prelude::*;
+    use serde_json::{Map, Value};
+
+    use super::{invalid_variants, InvalidFixture};
+    use crate::descriptor::{FieldDescriptor, TypeShape};
+    use crate::validate::ValidateFields;
+
+    /// Random payloads that satisfy `T`'s required fields. Optional fields
+    /// are included at random; containers hold up to a few elements.
+    pub fn valid_payload<T: ValidateFields>() -> BoxedStrategy<Value> {
+        if T::fields().is_empty() {
+            return Just(super::example_json::<T>()).boxed();
+        }
+        object_strategy(T::fields(), &mut Vec::new())
+    }
+
+    /// One of `invalid_variants::<T>()`, picked at random. Types without
+    /// required fields only reject non-objects, so they yield a `null` payload.
+    pub fn invalid_payload<T: ValidateFields>() -> BoxedStrategy<InvalidFixture> {
+        let variants = invalid_variants::<T>();
+        if variants.is_empty() {
+            return Just(InvalidFixture {
+                path: String::new(),
+                mutation: super::Mutation::WrongType,
+                payload: Value::Null,
+            })
+            .boxed();
+        }
+        proptest::sample::select(variants).boxed()
+    }
+
+    /// `stack` works as in `example_object`: optional fields that would
+    /// repeat a type being expanded are left out, required ones get an
+    /// empty container.
+    fn object_strategy(
+        fields: &'static [FieldDescriptor],
+        stack: &mut Vec<*const FieldDescriptor>,
+    ) -> BoxedStrategy<Value> {
+        stack.push(fields.as_ptr());
+        let entries: Vec<BoxedStrategy<Option<(String, Value)>>> = fields
+            .iter()
+            .filter_map(|field| {
+                if field.optional && nests(field) && super::repeats(field, stack) {
+                    return None;
+                }
+                let name = field.name.to_string();
+                let value = field_strategy(field, stack);
+                Some(if field.optional {
+                    proptest::option::of(value.prop_map(move |v| (name.clone(), v))).boxed()
+                } else {
+                    value.prop_map(move |v| Some((name.clone(), v))).boxed()
+                })
+            })
+            .collect();
+        stack.pop();
+        entries
+            .prop_map(move |entries| {
+                let mut obj: Map<String, Value> = entries.into_iter().flatten().collect();
+                // Keep `required_if` fields consistent with their triggers
+                for field in fields {
+                    for trigger in field.required_if() {
+                        let triggered = obj.get(trigger).is_some_and(|v| !v.is_null());
+                        let present = obj.get(field.name).is_some_and(|v| !v.is_null());
+                        if triggered && !present {
+                            obj.insert(field.name.to_string(), super::example_value(field, &mut Vec::new()));
+                        }
+                    }
+                }
+                Value::Object(obj)
+            })
+            .boxed()
+    }
+
+    fn nests(field: &FieldDescriptor) -> bool {
+        field.nested.is_some() && matches!(field.shape, TypeShape::Object | TypeShape::Array(&TypeShape::Object))
+    }
+
+    fn field_strategy(
+        field: &'static FieldDescriptor,
+        stack: &mut Vec<*const FieldDescriptor>,
+    ) -> BoxedStrategy<Value> {
+        match field.shape {
+            _ if nests(field) && super::repeats(field, stack) => Just(super::example_for_shape(field.shape)).boxed(),
+            TypeShape::Object if field.nested.is_some() => object_strategy(field.nested_fields(), stack),
+            TypeShape::Array(_) | TypeShape::Map(_) if field.format().is_some() || field.allowed().is_some() => {
+                Just(super::example_for_shape(field.shape)).boxed()
+            }
+            _ if field.allowed().is_some_and(|allowed| !allowed.is_empty()) => {
+                let allowed = field.allowed().unwrap_or_default();
+                proptest::sample::select(allowed).prop_map(Value::from).boxed()
+            }
+            _ if field.format().is_some() || field.allowed().is_some() => {
+                Just(super::example_value(field, stack)).boxed()
+            }
+            TypeShape::Array(&TypeShape::Object) if field.nested.is_some() => {
+                proptest::collection::vec(object_strategy(field.nested_fields(), stack), 0..4)
+                    .prop_map(Value::Array)
+                    .boxed()
+            }
+            shape => shape_strategy(shape),
+        }
+    }
+
+    fn shape_strategy(shape: TypeShape) -> BoxedStrategy<Value> {
+        match shape {
+            TypeShape::String | TypeShape::Other => any::<String>().prop_map(Value::String).boxed(),
+            TypeShape::Integer => (0u8..=127).prop_map(Value::from).boxed(),
+            TypeShape::Float => (-1.0e6f64..1.0e6).prop_map(Value::from).boxed(),
+            TypeShape::Bool => any::<bool>().prop_map(Value::Bool).boxed(),
+            TypeShape::Array(inner) => proptest::collection::vec(shape_strategy(*inner), 0..4)
+                .prop_map(Value::Array)
+                .boxed(),
+            TypeShape::Map(inner) => {
+                proptest::collection::btree_map("[a-z]{1,8}", shape_strategy(*inner), 0..4)
+                    .prop_map(|m| Value::Object(m.into_iter().collect()))
+                    .boxed()
+            }
+            TypeShape::Object | TypeShape::Any => Just(Value::Object(Map::new())).boxed(),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use serde_json::Value;
+use std::fmt;
+use std::net::IpAddr;
+
+/// A built-in format a field's value must have, beyond its JSON type.
+///
+/// Written as `format = "uuid"` in derive attributes and schema files;
+/// `epoch_seconds` takes optional bounds, e.g. `epoch_seconds(1500000000, 4102444800)`
+/// or `epoch_seconds(0,)`. Values that do not match are reported with the
+/// code `invalid_format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// An integer count of seconds since the Unix epoch, within the
+    /// inclusive bounds if given.
+    EpochSeconds { min: Option<i64>, max: Option<i64> },
+    /// A timestamp such as `2024-05-01T09:30:00+05:30`.
+    Rfc3339,
+    /// A number from -90 to 90.
+    Latitude,
+    /// A number from -180 to 180.
+    Longitude,
+    /// A 17-character vehicle identification number; the check digit is
+    /// not verified since it is only mandatory in North America.
+    Vin,
+    /// A hyphenated UUID such as `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    Uuid,
+    /// An IPv4 or IPv6 address.
+    Ip,
+    /// An absolute URL with a host, such as `https://example.com/path`.
+    Url,
+}
+
+impl Format {
+    /// Parse the written form, e.g. `latitude` or `epoch_seconds(0, 4102444800)`.
+    pub fn parse(s: &str) -> Result<Format, String> {
+        let s = s.trim();
+        let (name, args) = match s.split_once('(') {
+            Some((name, rest)) => {
+                let args = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| format!("unclosed `(` in format `{}`", s))?;
+                (name.trim(), Some(args))
+            }
+            None => (s, None),
+        };
+        let format = match name {
+            "epoch_seconds" => {
+                let (min, max) = match args {
+                    None => (None, None),
+                    Some(args) => {
+                        let (min, max) = args.split_once(',').ok_or_else(|| {
+                            "`epoch_seconds` bounds are written `epoch_seconds(min, max)`".to_string()
+                        })?;
+                        (bound(min)?, bound(max)?)
+                    }
+                };
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(format!("`epoch_seconds` minimum {} is above the maximum {}", min, max));
+                    }
+                }
+                return Ok(Format::EpochSeconds { min, max });
+            }
+            "rfc3339" => Format::Rfc3339,
+            "latitude" => Format::Latitude,
+            "longitude" => Format::Longitude,
+            "vin" => Format::Vin,
+            "uuid" => Format::Uuid,
+            "ip" => Format::Ip,
+            "url" => Format::Url,
+            _ => {
+                return Err(format!(
+                    "unknown format `{}`; expected one of: epoch_seconds, rfc3339, latitude, longitude, vin, uuid, ip, url",
+                    name
+                ))
+            }
+        };
+        match args {
+            Some(_) => Err(format!("format `{}` does not take arguments", name)),
+            None => Ok(format),
+        }
+    }
+
+    /// Whether a non-null JSON value has this format.
+    pub fn matches(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Format::EpochSeconds { min, max }, Value::Number(n)) => n.as_i64().is_some_and(|secs| {
+                min.is_none_or(|min| secs >= min) && max.is_none_or(|max| secs <= max)
+            }),
+            (Format::Latitude, Value::Number(n)) => n.as_f64().is_some_and(|v| (-90.0..=90.0).contains(&v)),
+            (Format::Longitude, Value::Number(n)) => {
+                n.as_f64().is_some_and(|v| (-180.0..=180.0).contains(&v))
+            }
+            (Format::Rfc3339, Value::String(s)) => is_rfc3339(s),
+            (Format::Vin, Value::String(s)) => {
+                s.len() == 17
+                    && s.bytes().all(|b| b.is_ascii_alphanumeric() && !matches!(b.to_ascii_uppercase(), b'I' | b'O' | b'Q'))
+            }
+            (Format::Uuid, Value::String(s)) => is_uuid(s),
+            (Format::Ip, Value::String(s)) => s.parse::<IpAddr>().is_ok(),
+            (Format::Url, Value::String(s)) => is_url(s),
+            _ => false,
+        }
+    }
+
+    /// What a matching value is, for messages: "a UUID".
+    pub fn description(&self) -> String {
+        match self {
+            Format::EpochSeconds { min: None, max: None } => "Unix epoch seconds".to_string(),
+            Format::EpochSeconds { min: Some(min), max: None } => {
+                format!("Unix epoch seconds no earlier than {}", min)
+            }
+            Format::EpochSeconds { min: None, max: Some(max) } => {
+                format!("Unix epoch seconds no later than {}", max)
+            }
+            Format::EpochSeconds { min: Some(min), max: Some(max) } => {
+                format!("Unix epoch seconds between {} and {}", min, max)
+            }
+            Format::Rfc3339 => "an RFC 3339 timestamp".to_string(),
+            Format::Latitude => "a latitude from -90 to 90".to_string(),
+            Format::Longitude => "a longitude from -180 to 180".to_string(),
+            Format::Vin => "a 17-character VIN".to_string(),
+            Format::Uuid => "a UUID".to_string(),
+            Format::Ip => "an IP address".to_string(),
+            Format::Url => "an absolute URL".to_string(),
+        }
+    }
+
+    /// A value that matches, for fixtures.
+    pub fn example(&self) -> Value {
+        match *self {
+            Format::EpochSeconds { min, max } => {
+                Value::from(1_700_000_000i64.clamp(min.unwrap_or(i64::MIN), max.unwrap_or(i64::MAX)))
+            }
+            Format::Rfc3339 => Value::from("2024-01-01T00:00:00Z"),
+            Format::Latitude | Format::Longitude => Value::from(0.0),
+            Format::Vin => Value::from("1HGCM82633A004352"),
+            Format::Uuid => Value::from("67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            Format::Ip => Value::from("192.0.2.1"),
+            Format::Url => Value::from("https://example.com/"),
+        }
+    }
+
+    /// A value of the right JSON type that does not match, if one exists.
+    pub fn counterexample(&self) -> Option<Value> {
+        match *self {
+            Format::EpochSeconds { max: Some(max), .. } if max < i64::MAX => Some(Value::from(max + 1)),
+            Format::EpochSeconds { min: Some(min), .. } if min > i64::MIN => Some(Value::from(min - 1)),
+            Format::EpochSeconds { .. } => None,
+            Format::Rfc3339 => Some(Value::from("2024-13-01T00:00:00Z")),
+            Format::Latitude => Some(Value::from(91.0)),
+            Format::Longitude => Some(Value::from(181.0)),
+            Format::Vin => Some(Value::from("NOT-A-VIN")),
+            Format::Uuid => Some(Value::from("not-a-uuid")),
+            Format::Ip => Some(Value::from("192.0.2.256")),
+            Format::Url => Some(Value::from("example.com")),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    /// The written form `parse` accepts.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::EpochSeconds { min: None, max: None } => write!(f, "epoch_seconds"),
+            Format::EpochSeconds { min, max } => {
+                let min = min.map(|min| min.to_string()).unwrap_or_default();
+                let max = max.map(|max| format!(" {}", max)).unwrap_or_default();
+                write!(f, "epoch_seconds({},{})", min, max)
+            }
+            Format::Rfc3339 => write!(f, "rfc3339"),
+            Format::Latitude => write!(f, "latitude"),
+            Format::Longitude => write!(f, "longitude"),
+            Format::Vin => write!(f, "vin"),
+            Format::Uuid => write!(f, "uuid"),
+            Format::Ip => write!(f, "ip"),
+            Format::Url => write!(f, "url"),
+        }
+    }
+}
+
+/// An `epoch_seconds` bound; empty means unbounded.
+fn bound(s: &str) -> Result<Option<i64>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse()
+        .map(Some)
+        .map_err(|_| format!("`epoch_seconds` bound `{}` is not an integer", s))
+}
+
+/// `YYYY-MM-DDTHH:MM:SS[.frac](Z|±HH:MM)`, with real calendar dates.
+fn is_rfc3339(s: &str) -> bool {
+    let b = s.as_bytes();
+    let num = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = b.get(range)?;
+        digits
+            .iter()
+            .all(u8::is_ascii_digit)
+            .then(|| digits.iter().fold(0, |n, d| n * 10 + u32::from(d - b'0')))
+    };
+    let at = |i: usize, allowed: &[u8]| b.get(i).is_some_and(|c| allowed.contains(c));
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) =
+        (num(0..4), num(5..7), num(8..10), num(11..13), num(14..16), num(17..19))
+    else {
+        return false;
+    };
+    if !(at(4, b"-") && at(7, b"-") && at(10, b"Tt ") && at(13, b":") && at(16, b":")) {
+        return false;
+    }
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    if day == 0 || day > days || hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+    let mut i = 19;
+    if at(i, b".") {
+        i += 1;
+        let start = i;
+        while b.get(i).is_some_and(u8::is_ascii_digit) {
+            i += 1;
+        }
+        if i == start {
+            return false;
+        }
+    }
+    match b.get(i) {
+        Some(b'Z' | b'z') => i + 1 == b.len(),
+        Some(b'+' | b'-') => {
+            i + 6 == b.len()
+                && at(i + 3, b":")
+                && num(i + 1..i + 3).is_some_and(|h| h <= 23)
+                && num(i + 4..i + 6).is_some_and(|m| m <= 59)
+        }
+        _ => false,
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.bytes().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// `scheme://host...` with no whitespace or control characters.
+fn is_url(s: &str) -> bool {
+    let Some((scheme, rest)) = s.split_once("://") else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    let scheme_ok = scheme_chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        // IPv6 literal, optionally followed by a port
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    scheme_ok && !host.is_empty() && !s.chars().any(|c| c.is_whitespace() || c.is_control())
+}
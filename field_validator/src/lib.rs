@@ -1,6 +1,58 @@
+pub mod batch;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod descriptor;
+pub mod diff;
+pub mod dispatch;
+pub mod finding;
+pub mod fixtures;
+pub mod format;
+pub mod limits;
+pub mod messages;
+pub mod migrate;
+pub mod observe;
+pub mod options;
+pub mod outbound;
+pub mod plan;
+pub mod redact;
+pub mod render;
+pub mod report;
+pub mod schema;
+pub mod typescript;
 pub mod validate;
+
+/// Re-exported for code generated by the derives.
+pub use serde;
 pub use validate::{
   ValidateFields, MissingFieldsError, 
-  validate_json_for_type, validate_and_deserialize,
-  handle_json_request
+  validate_json_for_type, validate_json_for_type_with, validate_and_deserialize,
+  handle_json_request,
+  ValidationError, validate_json_with_options,
+  validate_and_deserialize_with_options, handle_json_request_with_options,
+  validate_partial, validate_partial_with_options, validate_and_deserialize_patch,
+  Patchable, StringEnum, validate_for_version,
+  validate_json_with_report, validate_and_deserialize_with_report,
+  validate_slice_with_report, read_payload, validate_and_deserialize_borrowed,
+  validate_and_deserialize_borrowed_with_report, validate_and_deserialize_reader
 };
+pub use batch::{validate_ndjson, validate_ndjson_each, validate_ndjson_with, BatchStats};
+pub use dispatch::{DispatchError, Dispatcher};
+pub use descriptor::{Constraint, FieldDescriptor, NestedType, TypeShape};
+pub use diff::{assert_schema_compatible, ChangeKind, Requirement, SchemaChange, SchemaDiff};
+pub use finding::{Finding, FindingKind, Severity};
+pub use format::Format;
+pub use fixtures::{example_json, invalid_variants, InvalidFixture, Mutation};
+pub use limits::{LimitError, LimitKind};
+pub use messages::{CatalogError, FieldMessage, MessageCatalog};
+pub use migrate::{MigrationError, MigrationRegistry};
+#[cfg(feature = "tracing")]
+pub use observe::TracingObserver;
+pub use observe::{TypeCounts, ValidationCounters, ValidationEvent, ValidationObserver};
+pub use options::{parse_version, ValidationMode, ValidatorOptions};
+pub use outbound::{assert_serialized_valid, debug_assert_serialized, validate_serialized};
+pub use plan::ValidationPlan;
+pub use redact::{set_redaction_policy, RedactionPolicy};
+pub use render::{assert_valid, SourceRenderer};
+pub use report::{AppliedMigration, ValidationReport};
+pub use schema::{DynamicSchema, SchemaError, SchemaField};
+pub use typescript::TypeScriptGenerator;
@@ -0,0 +1,269 @@
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use std::cell::RefCell;
+use std::fmt;
+
+use crate::options::ValidatorOptions;
+use crate::plan::ValidationPlan;
+use crate::redact::{redaction_policy, RedactionPolicy};
+use crate::validate::ValidationError;
+
+/// Which limit in `ValidatorOptions` a payload exceeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LimitKind {
+    Bytes,
+    Depth,
+    ArrayLength,
+    ObjectKeys,
+    StringLength,
+}
+
+impl LimitKind {
+    /// Stable identifier used in responses and metrics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LimitKind::Bytes => "max_bytes_exceeded",
+            LimitKind::Depth => "max_depth_exceeded",
+            LimitKind::ArrayLength => "max_array_len_exceeded",
+            LimitKind::ObjectKeys => "max_object_keys_exceeded",
+            LimitKind::StringLength => "max_string_len_exceeded",
+        }
+    }
+}
+
+/// Error returned when a payload exceeds one of the configured limits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitError {
+    pub kind: LimitKind,
+    /// The configured limit that was exceeded.
+    pub limit: usize,
+    /// Dotted path of the offending value; empty for the whole payload.
+    pub path: String,
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            LimitKind::Bytes => "payload is larger than",
+            LimitKind::Depth => "payload is nested deeper than",
+            LimitKind::ArrayLength => "array has more elements than",
+            LimitKind::ObjectKeys => "object has more keys than",
+            LimitKind::StringLength => "string is longer than",
+        };
+        if self.path.is_empty() {
+            write!(f, "{} the limit of {}", what, self.limit)
+        } else {
+            write!(f, "{} the limit of {} at `{}`", what, self.limit, self.path)
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+/// Why `parse_with_limits` failed.
+#[derive(Debug)]
+pub(crate) enum ParseError {
+    Syntax(serde_json::Error),
+    /// The limit error, with the path to render into it.
+    Limit(LimitError, Vec<PathSegment>),
+}
+
+/// One step of the path to a value that exceeded a limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PathSegment {
+    Index(usize),
+    Key(String),
+}
+
+impl ParseError {
+    /// The error to report. Under `RedactionPolicy::All`, keys in a limit's
+    /// path that do not name a field of `plan` are rendered as `*`, like map
+    /// keys in findings; without a plan every key is.
+    pub(crate) fn into_error(self, plan: Option<&ValidationPlan>) -> ValidationError {
+        match self {
+            ParseError::Syntax(e) => ValidationError::Syntax(e),
+            ParseError::Limit(mut limit, segments) => {
+                limit.path = if redaction_policy() == RedactionPolicy::All {
+                    plan.unwrap_or(&ValidationPlan::default()).redact_path(&segments)
+                } else {
+                    let raw: Vec<String> = segments
+                        .into_iter()
+                        .map(|segment| match segment {
+                            PathSegment::Index(i) => i.to_string(),
+                            PathSegment::Key(key) => key,
+                        })
+                        .collect();
+                    raw.join(".")
+                };
+                ValidationError::Limit(limit)
+            }
+        }
+    }
+}
+
+/// Parse `json` into a `Value`, enforcing every limit in `opts` as the
+/// document is read rather than after it has been fully built.
+pub(crate) fn parse_with_limits(json: &str, opts: &ValidatorOptions) -> Result<Value, ParseError> {
+    parse_slice_with_limits(json.as_bytes(), opts)
+}
+
+/// `parse_with_limits` for a raw body; invalid UTF-8 is a syntax error.
+pub(crate) fn parse_slice_with_limits(json: &[u8], opts: &ValidatorOptions) -> Result<Value, ParseError> {
+    if json.len() > opts.max_bytes {
+        return Err(ParseError::Limit(
+            LimitError { kind: LimitKind::Bytes, limit: opts.max_bytes, path: String::new() },
+            Vec::new(),
+        ));
+    }
+
+    let state = State { opts, violation: RefCell::new(None) };
+    let mut de = serde_json::Deserializer::from_slice(json);
+    let parsed = ValueSeed { state: &state, depth: 0 }
+        .deserialize(&mut de)
+        .and_then(|value| de.end().map(|()| value));
+    match (parsed, state.violation.into_inner()) {
+        (_, Some((kind, limit, mut path))) => {
+            path.reverse();
+            Err(ParseError::Limit(LimitError { kind, limit, path: String::new() }, path))
+        }
+        (Ok(value), None) => Ok(value),
+        (Err(e), None) => Err(ParseError::Syntax(e)),
+    }
+}
+
+/// Shared bookkeeping for one parse.
+struct State<'a> {
+    opts: &'a ValidatorOptions,
+    /// The violated limit, with the path segments collected innermost first
+    /// as the error unwinds, so the happy path never tracks where it is.
+    violation: RefCell<Option<(LimitKind, usize, Vec<PathSegment>)>>,
+}
+
+impl State<'_> {
+    /// Record a violation and produce the error that aborts deserialization.
+    fn exceeded<E: de::Error>(&self, kind: LimitKind, limit: usize) -> E {
+        *self.violation.borrow_mut() = Some((kind, limit, Vec::new()));
+        E::custom(kind.code())
+    }
+
+    /// Add `segment` to the path of a violation unwinding through it.
+    fn unwind<T, E>(&self, result: Result<T, E>, segment: impl FnOnce() -> PathSegment) -> Result<T, E> {
+        if result.is_err() {
+            if let Some((_, _, path)) = self.violation.borrow_mut().as_mut() {
+                path.push(segment());
+            }
+        }
+        result
+    }
+
+    fn check_str<E: de::Error>(&self, s: &str) -> Result<(), E> {
+        if s.len() > self.opts.max_string_len {
+            return Err(self.exceeded(LimitKind::StringLength, self.opts.max_string_len));
+        }
+        Ok(())
+    }
+}
+
+struct ValueSeed<'s, 'a> {
+    state: &'s State<'a>,
+    depth: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for ValueSeed<'_, '_> {
+    type Value = Value;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ValueSeed<'_, '_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(v).map_or(Value::Null, Value::Number))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        self.state.check_str(v)?;
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Value, E> {
+        self.state.check_str(&v)?;
+        Ok(Value::String(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let opts = self.state.opts;
+        let depth = self.depth + 1;
+        if depth > opts.max_depth {
+            return Err(self.state.exceeded(LimitKind::Depth, opts.max_depth));
+        }
+        let mut items = Vec::new();
+        loop {
+            if items.len() == opts.max_array_len {
+                // One more element would exceed the limit; only fail if it exists.
+                let seed = ValueSeed { state: self.state, depth };
+                let next = seq.next_element_seed(seed);
+                if self.state.unwind(next, || PathSegment::Index(items.len()))?.is_some() {
+                    return Err(self.state.exceeded(LimitKind::ArrayLength, opts.max_array_len));
+                }
+                break;
+            }
+            let next = seq.next_element_seed(ValueSeed { state: self.state, depth });
+            match self.state.unwind(next, || PathSegment::Index(items.len()))? {
+                Some(item) => items.push(item),
+                None => break,
+            }
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let opts = self.state.opts;
+        let depth = self.depth + 1;
+        if depth > opts.max_depth {
+            return Err(self.state.exceeded(LimitKind::Depth, opts.max_depth));
+        }
+        let mut obj = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if obj.len() == opts.max_object_keys {
+                return Err(self.state.exceeded(LimitKind::ObjectKeys, opts.max_object_keys));
+            }
+            self.state.check_str(&key)?;
+            let value = map.next_value_seed(ValueSeed { state: self.state, depth });
+            let value = self.state.unwind(value, || PathSegment::Key(key.clone()))?;
+            obj.insert(key, value);
+        }
+        Ok(Value::Object(obj))
+    }
+}
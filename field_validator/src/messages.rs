@@ -0,0 +1,327 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::batch::aggregate_path;
+use crate::finding::{Finding, FindingKind};
+use crate::migrate::MigrationError;
+use crate::plan::ValidationPlan;
+use crate::validate::ValidationError;
+
+/// Message templates by locale and code, for rendering validation problems
+/// in the client's language.
+///
+/// Templates are keyed by the codes of `FindingKind::code()` and
+/// `ValidationError::code()` and fill in these placeholders:
+///
+/// | code | placeholders |
+/// |---|---|
+/// | `missing_field`, `unknown_field` | `{field}` |
+/// | `wrong_type` | `{field}`, `{expected}` |
+/// | `invalid_format` | `{field}`, `{format}`, `{expected}` |
+/// | `invalid_value` | `{field}`, `{allowed}` |
+/// | `deprecated_field` | `{field}`, `{note}` |
+/// | `missing_fields` | `{fields}`, `{count}` |
+/// | `invalid_fields`, `invalid_json`, `unserializable`, `unreadable` | `{detail}` |
+/// | `max_*_exceeded` | `{field}`, `{limit}` |
+/// | `unsupported_version` | `{version}` |
+/// | `migration_failed` | `{field}` |
+///
+/// A `field:<path>` key (array indexes written as `*`) replaces the message
+/// for every problem with that field. A field's `message` attribute counts
+/// as its message in the default locale. Lookups try the requested locale,
+/// then its language (`pt` for `pt-BR`), then the default locale, and fall
+/// back to the built-in English messages.
+///
+/// ```
+/// use field_validator::messages::MessageCatalog;
+///
+/// let catalog = MessageCatalog::new()
+///     .default_locale("en")
+///     .message("de", "missing_field", "{field} fehlt")
+///     .message("de", "field:bike_identifier", "Bitte die Fahrzeug-ID angeben");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    default_locale: Option<String>,
+    locales: HashMap<String, HashMap<String, String>>,
+}
+
+/// The rendered message for one field, as listed in responses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldMessage {
+    pub field: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Error returned when a catalog file cannot be loaded.
+#[derive(Debug)]
+pub enum CatalogError {
+    Read { path: PathBuf, error: std::io::Error },
+    /// The document is not an object of string templates.
+    Parse { source: String, message: String },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Read { path, error } => write!(f, "cannot read {}: {}", path.display(), error),
+            CatalogError::Parse { source, message } => {
+                write!(f, "invalid message catalog {}: {}", source, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
+
+impl MessageCatalog {
+    /// An empty catalog: only `message` attributes and the built-in
+    /// messages apply.
+    pub fn new() -> Self {
+        MessageCatalog::default()
+    }
+
+    /// Load every `<locale>.json` file in `dir` (and, with the `yaml`
+    /// feature, `<locale>.yaml`/`.yml`); other files are ignored.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self, CatalogError> {
+        let dir = dir.as_ref();
+        let read_err = |error| CatalogError::Read { path: dir.to_path_buf(), error };
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir).map_err(read_err)? {
+            paths.push(entry.map_err(read_err)?.path());
+        }
+        paths.sort();
+        let mut catalog = MessageCatalog::new();
+        for path in paths {
+            if matches!(path.extension().and_then(|e| e.to_str()), Some("json" | "yaml" | "yml")) {
+                catalog.load_file(&path)?;
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Add the templates in `path` to the locale named by its file stem,
+    /// e.g. `messages/de.json`.
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), CatalogError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| CatalogError::Read { path: path.to_path_buf(), error })?;
+        let locale = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let parse_err = |message: String| CatalogError::Parse { source: path.display().to_string(), message };
+        let templates: HashMap<String, String> = match path.extension().and_then(|e| e.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|e| parse_err(e.to_string()))?,
+            #[cfg(not(feature = "yaml"))]
+            Some("yaml" | "yml") => return Err(parse_err("YAML catalogs need the `yaml` feature".to_string())),
+            _ => serde_json::from_str(&text).map_err(|e| parse_err(e.to_string()))?,
+        };
+        self.locales.entry(normalize(locale)).or_default().extend(templates);
+        Ok(())
+    }
+
+    /// Add the templates of a JSON object to `locale`.
+    pub fn add_json(&mut self, locale: &str, json: &str) -> Result<(), CatalogError> {
+        let templates: HashMap<String, String> = serde_json::from_str(json)
+            .map_err(|e| CatalogError::Parse { source: format!("for `{}`", locale), message: e.to_string() })?;
+        self.locales.entry(normalize(locale)).or_default().extend(templates);
+        Ok(())
+    }
+
+    /// The locale tried after the requested one; usually the language the
+    /// `message` attributes are written in.
+    pub fn default_locale(mut self, locale: &str) -> Self {
+        self.default_locale = Some(normalize(locale));
+        self
+    }
+
+    /// Set the template for `key`, a code or `field:<path>`, in `locale`.
+    pub fn message(mut self, locale: &str, key: &str, template: &str) -> Self {
+        self.locales.entry(normalize(locale)).or_default().insert(key.to_string(), template.to_string());
+        self
+    }
+
+    /// The loaded locales, sorted.
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.locales.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    /// The loaded locale best matching an `Accept-Language` header value,
+    /// honouring `q` weights.
+    pub fn negotiate(&self, accept_language: &str) -> Option<&str> {
+        let mut ranges: Vec<(f32, String)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = normalize(parts.next()?.trim());
+                let q = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse().ok())?;
+                (!tag.is_empty() && q > 0.0).then_some((q, tag))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+        ranges.iter().find_map(|(_, tag)| {
+            let primary = tag.split('-').next().unwrap_or(tag);
+            [tag.as_str(), primary]
+                .into_iter()
+                .find_map(|l| self.locales.get_key_value(l).map(|(k, _)| k.as_str()))
+        })
+    }
+
+    /// The message for one finding in `locale`; give `plan` so field
+    /// `message` attributes apply.
+    pub fn render_finding(&self, locale: Option<&str>, finding: &Finding, plan: Option<&ValidationPlan>) -> String {
+        let mut args = vec![("field", finding.path.clone())];
+        match &finding.kind {
+            FindingKind::WrongType { expected } => args.push(("expected", expected.to_string())),
+            FindingKind::InvalidFormat { format } => {
+                args.push(("format", format.to_string()));
+                args.push(("expected", format.description()));
+            }
+            FindingKind::InvalidValue { allowed } => args.push(("allowed", allowed.join(", "))),
+            FindingKind::Deprecated { note } => args.push(("note", note.clone())),
+            FindingKind::Missing | FindingKind::UnknownField => {}
+        }
+        match self.finding_template(locale, finding, plan) {
+            Some(template) => fill(template, &args),
+            None => finding.to_string(),
+        }
+    }
+
+    /// The message for `error` as a whole in `locale`.
+    pub fn render_error(&self, locale: Option<&str>, error: &ValidationError, plan: Option<&ValidationPlan>) -> String {
+        let code = error.code();
+        let (args, fallback) = match error {
+            ValidationError::Syntax(e) | ValidationError::Serialize(e) => {
+                (vec![("detail", e.to_string())], error.to_string())
+            }
+            ValidationError::Io(e) => (vec![("detail", e.to_string())], error.to_string()),
+            ValidationError::Limit(limit) => (
+                vec![("field", limit.path.clone()), ("limit", limit.limit.to_string())],
+                error.to_string(),
+            ),
+            ValidationError::MissingFields(missing) => {
+                let fields: Vec<String> = missing.missing_fields.iter().map(|f| format!("`{}`", f)).collect();
+                (
+                    vec![("fields", fields.join(", ")), ("count", fields.len().to_string())],
+                    error.to_string(),
+                )
+            }
+            ValidationError::InvalidFields(findings) => {
+                let detail: Vec<String> =
+                    findings.iter().map(|f| self.render_finding(locale, f, plan)).collect();
+                let detail = detail.join("; ");
+                let fallback = format!("invalid fields: {}", detail);
+                (vec![("detail", detail)], fallback)
+            }
+            ValidationError::Migration(MigrationError::MissingStep(version)) => {
+                (vec![("version", version.to_string())], error.to_string())
+            }
+            ValidationError::Migration(MigrationError::NotAnObject { field }) => {
+                (vec![("field", field.clone())], error.to_string())
+            }
+        };
+        match self.chain(locale).find_map(|l| self.get(l, code)) {
+            Some(template) => fill(template, &args),
+            None => fallback,
+        }
+    }
+
+    /// One message per field named by `error`: each missing field, or each
+    /// finding of `InvalidFields`. Empty for other errors.
+    pub fn field_errors(
+        &self,
+        locale: Option<&str>,
+        error: &ValidationError,
+        plan: Option<&ValidationPlan>,
+    ) -> Vec<FieldMessage> {
+        let findings: Vec<Finding> = match error {
+            ValidationError::MissingFields(missing) => missing
+                .missing_fields
+                .iter()
+                .map(|path| Finding { path: path.clone(), kind: FindingKind::Missing })
+                .collect(),
+            ValidationError::InvalidFields(findings) => findings.clone(),
+            _ => Vec::new(),
+        };
+        findings
+            .iter()
+            .map(|f| FieldMessage {
+                field: f.path.clone(),
+                code: f.kind.code(),
+                message: self.render_finding(locale, f, plan),
+            })
+            .collect()
+    }
+
+    fn finding_template<'a>(
+        &'a self,
+        locale: Option<&str>,
+        finding: &Finding,
+        plan: Option<&'a ValidationPlan>,
+    ) -> Option<&'a str> {
+        let field_key = format!("field:{}", aggregate_path(&finding.path));
+        let code = finding.kind.code();
+        let default = self.default_locale.as_deref();
+        self.chain(locale)
+            .filter(|&l| Some(l) != default)
+            .find_map(|l| self.get(l, &field_key).or_else(|| self.get(l, code)))
+            .or_else(|| default.and_then(|d| self.get(d, &field_key)))
+            .or_else(|| plan.and_then(|p| p.field_message(&finding.path)))
+            .or_else(|| default.and_then(|d| self.get(d, code)))
+    }
+
+    /// The requested locale, its language, then the default locale.
+    fn chain<'a>(&'a self, locale: Option<&str>) -> impl Iterator<Item = &'a str> {
+        let requested = locale.map(normalize);
+        let language = requested
+            .as_deref()
+            .and_then(|l| l.split_once('-'))
+            .map(|(language, _)| language.to_string());
+        [requested, language]
+            .into_iter()
+            .flatten()
+            .filter_map(|l| self.locales.get_key_value(&l).map(|(k, _)| k.as_str()))
+            .chain(self.default_locale.as_deref())
+    }
+
+    fn get(&self, locale: &str, key: &str) -> Option<&str> {
+        self.locales.get(locale)?.get(key).map(String::as_str)
+    }
+}
+
+/// `pt_BR` and `PT-br` are both `pt-br`.
+fn normalize(locale: &str) -> String {
+    locale.trim().replace('_', "-").to_ascii_lowercase()
+}
+
+/// Replace each `{name}` in `template` with its value; unknown placeholders
+/// are left as written.
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}').and_then(|end| {
+            let name = &after[..end];
+            args.iter().find(|(n, _)| *n == name).map(|(_, v)| (end, v))
+        }) {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,218 @@
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::limits::parse_with_limits;
+use crate::observe::observe;
+use crate::options::ValidatorOptions;
+use crate::redact;
+use crate::report::{AppliedMigration, ValidationReport};
+use crate::validate::{boxed_error, ValidateFields, ValidationError};
+
+type Step = Box<dyn Fn(&mut Value) -> Result<(), MigrationError> + Send + Sync>;
+
+/// Upgrades legacy payloads one version at a time before validation.
+///
+/// Each step rewrites a payload from version N to N+1. The payload's
+/// version comes from `ValidatorOptions` (`version_field`, then
+/// `api_version`); payloads without one are taken to be current.
+///
+/// ```
+/// use field_validator::migrate::{self, MigrationRegistry};
+///
+/// let registry = MigrationRegistry::new(3)
+///     .step(1, "rename mode", |v| migrate::rename_field(v, "mode", "change_to_mode"))
+///     .try_step(2, "nest bike_info", |v| migrate::nest_fields(v, &["model", "year"], "bike_info"));
+/// ```
+pub struct MigrationRegistry {
+    current: u32,
+    steps: BTreeMap<u32, (&'static str, Step)>,
+}
+
+/// Error returned when a payload cannot be brought up to the current version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationError {
+    /// No step upgrades payloads from this version.
+    MissingStep(u32),
+    /// A step needed `field` to hold an object, but the payload has
+    /// another value there.
+    NotAnObject { field: String },
+}
+
+impl MigrationError {
+    /// Stable identifier used in responses and metrics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MigrationError::MissingStep(_) => "unsupported_version",
+            MigrationError::NotAnObject { .. } => "migration_failed",
+        }
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::MissingStep(version) => write!(f, "no migration registered from version {}", version),
+            MigrationError::NotAnObject { field } => {
+                write!(f, "cannot migrate payload: `{}` is not an object", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl MigrationRegistry {
+    /// A registry whose payloads end up at `current_version`.
+    pub fn new(current_version: u32) -> Self {
+        MigrationRegistry { current: current_version, steps: BTreeMap::new() }
+    }
+
+    /// Register the step from version `from` to `from + 1`, replacing any
+    /// earlier step for the same version.
+    pub fn step(
+        self,
+        from: u32,
+        name: &'static str,
+        migrate: impl Fn(&mut Value) + Send + Sync + 'static,
+    ) -> Self {
+        self.try_step(from, name, move |value| {
+            migrate(value);
+            Ok(())
+        })
+    }
+
+    /// Like `step`, for a step that can fail, such as one using `nest_fields`.
+    /// Its error aborts the migration and is returned as is.
+    pub fn try_step(
+        mut self,
+        from: u32,
+        name: &'static str,
+        migrate: impl Fn(&mut Value) -> Result<(), MigrationError> + Send + Sync + 'static,
+    ) -> Self {
+        self.steps.insert(from, (name, Box::new(migrate)));
+        self
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.current
+    }
+
+    /// Run every step from `version` up to the current version, in order.
+    pub fn migrate(&self, value: &mut Value, version: u32) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let mut applied = Vec::new();
+        for from in version..self.current {
+            let (name, step) = self.steps.get(&from).ok_or(MigrationError::MissingStep(from))?;
+            step(value)?;
+            applied.push(AppliedMigration { from, name });
+        }
+        Ok(applied)
+    }
+
+    /// Parse under `opts`, migrate to the current version, then check
+    /// required fields as of that version.
+    pub fn validate_json<T: ValidateFields>(
+        &self,
+        json: &str,
+        opts: &ValidatorOptions,
+    ) -> Result<ValidationReport, ValidationError> {
+        self.migrate_and_validate::<T>(json, opts).map(|(_, report)| report)
+    }
+
+    /// `validate_json`, then deserialize the migrated payload into `T`.
+    ///
+    /// Errors are boxed as by `crate::validate_and_deserialize`.
+    pub fn validate_and_deserialize<T>(
+        &self,
+        json: &str,
+        opts: &ValidatorOptions,
+    ) -> Result<(T, ValidationReport), Box<dyn std::error::Error>>
+    where
+        T: ValidateFields + DeserializeOwned,
+    {
+        let (value, report) = self.migrate_and_validate::<T>(json, opts).map_err(boxed_error)?;
+        let data = serde_json::from_value(value)
+            .map_err(|e| redact::deserialize_error(e, T::validation_plan().has_sensitive_fields()))?;
+        Ok((data, report))
+    }
+
+    /// `validate_json`, returning the migrated payload alongside the report.
+    pub fn migrate_and_validate<T: ValidateFields>(
+        &self,
+        json: &str,
+        opts: &ValidatorOptions,
+    ) -> Result<(Value, ValidationReport), ValidationError> {
+        let check = || {
+            let value = parse_with_limits(json, opts).map_err(|e| e.into_error(Some(T::validation_plan())))?;
+            self.check_migrated::<T>(value, opts)
+        };
+        observe(opts, std::any::type_name::<T>(), check, |(_, report)| report)
+    }
+
+    /// `migrate_and_validate` for an already-parsed payload.
+    pub fn migrate_and_validate_value<T: ValidateFields>(
+        &self,
+        value: Value,
+        opts: &ValidatorOptions,
+    ) -> Result<(Value, ValidationReport), ValidationError> {
+        let check = || self.check_migrated::<T>(value, opts);
+        observe(opts, std::any::type_name::<T>(), check, |(_, report)| report)
+    }
+
+    fn check_migrated<T: ValidateFields>(
+        &self,
+        mut value: Value,
+        opts: &ValidatorOptions,
+    ) -> Result<(Value, ValidationReport), ValidationError> {
+        let version = opts.version_for(&value).unwrap_or(self.current);
+        let migrations = self.migrate(&mut value, version).map_err(ValidationError::Migration)?;
+        let report = T::validation_plan().check_payload_limited(
+            &value,
+            Some(version.max(self.current)),
+            opts.error_limit(),
+        )?;
+        Ok((value, ValidationReport { migrations, ..report }))
+    }
+}
+
+/// Rename `from` to `to` in a payload object, unless `to` is already set.
+pub fn rename_field(value: &mut Value, from: &str, to: &str) {
+    if let Some(obj) = value.as_object_mut() {
+        if !obj.contains_key(to) {
+            if let Some(v) = obj.remove(from) {
+                obj.insert(to.to_string(), v);
+            }
+        }
+    }
+}
+
+/// Move the top-level `fields` into a nested object under `into`, merging
+/// with any object already there. Fields already nested are left alone.
+///
+/// A `null` under `into` is replaced by the new object. Any other
+/// non-object there fails with `MigrationError::NotAnObject`, leaving the
+/// payload untouched.
+pub fn nest_fields(value: &mut Value, fields: &[&str], into: &str) -> Result<(), MigrationError> {
+    let Some(obj) = value.as_object_mut() else { return Ok(()) };
+    if !fields.iter().any(|&f| obj.contains_key(f)) {
+        return Ok(());
+    }
+    match obj.get(into) {
+        None | Some(Value::Null) => {
+            obj.insert(into.to_string(), Value::Object(Map::new()));
+        }
+        Some(Value::Object(_)) => {}
+        Some(_) => return Err(MigrationError::NotAnObject { field: into.to_string() }),
+    }
+    let moved: Vec<(String, Value)> = fields
+        .iter()
+        .filter_map(|&f| obj.remove(f).map(|v| (f.to_string(), v)))
+        .collect();
+    if let Some(Value::Object(target)) = obj.get_mut(into) {
+        for (k, v) in moved {
+            target.entry(k).or_insert(v);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::batch::aggregate_path;
+use crate::options::ValidatorOptions;
+use crate::report::ValidationReport;
+use crate::validate::ValidationError;
+
+/// Receives the outcome of every validation run with `ValidatorOptions::observer`
+/// set, e.g. to count rejection causes per request type.
+///
+/// Called synchronously on the validating thread, so implementations
+/// should be cheap; the counters and tracing observer below are.
+pub trait ValidationObserver: Send + Sync {
+    fn on_validation(&self, event: &ValidationEvent<'_>);
+}
+
+impl fmt::Debug for dyn ValidationObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ValidationObserver")
+    }
+}
+
+/// One validation run: which type, how long it took and what it found.
+#[derive(Debug, Clone, Copy)]
+pub struct ValidationEvent<'a> {
+    /// The validated type, e.g. `fetch_ride_mode::FetchRideModeRequest`,
+    /// or the name of a `DynamicSchema`.
+    pub type_name: &'a str,
+    /// Time spent parsing and checking the payload.
+    pub duration: Duration,
+    pub result: Result<&'a ValidationReport, &'a ValidationError>,
+}
+
+impl<'a> ValidationEvent<'a> {
+    pub fn is_valid(&self) -> bool {
+        self.result.is_ok()
+    }
+
+    /// The rejection's `ValidationError::code`, if the payload was rejected.
+    pub fn error_code(&self) -> Option<&'static str> {
+        self.result.err().map(ValidationError::code)
+    }
+
+    /// Every field-level problem and warning as `(path, code)`, e.g.
+    /// `("bike_info.model", "missing_field")`. Rejections that are not
+    /// about particular fields, such as invalid JSON, have none.
+    pub fn fields(&self) -> Vec<(&'a str, &'static str)> {
+        match self.result {
+            Ok(report) => report.warnings.iter().map(|w| (w.path.as_str(), w.kind.code())).collect(),
+            Err(ValidationError::MissingFields(e)) => {
+                e.missing_fields.iter().map(|path| (path.as_str(), "missing_field")).collect()
+            }
+            Err(ValidationError::InvalidFields(findings)) => {
+                findings.iter().map(|f| (f.path.as_str(), f.kind.code())).collect()
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Run `check` for `type_name`, reporting its outcome to `opts.observer`.
+/// `report` picks the report out of a successful result.
+pub(crate) fn observe<R>(
+    opts: &ValidatorOptions,
+    type_name: &str,
+    check: impl FnOnce() -> Result<R, ValidationError>,
+    report: impl FnOnce(&R) -> &ValidationReport,
+) -> Result<R, ValidationError> {
+    let Some(observer) = &opts.observer else {
+        return check();
+    };
+    let start = Instant::now();
+    let result = check();
+    let duration = start.elapsed();
+    observer.on_validation(&ValidationEvent {
+        type_name,
+        duration,
+        result: result.as_ref().map(report),
+    });
+    result
+}
+
+/// Per-type tallies kept by `ValidationCounters`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeCounts {
+    pub validated: u64,
+    pub rejected: u64,
+    /// Rejections by `ValidationError::code`.
+    pub by_code: BTreeMap<String, u64>,
+    /// Field problems and warnings by path, with array indexes and map keys
+    /// collapsed to `*`, then by finding code.
+    pub by_field: BTreeMap<String, BTreeMap<String, u64>>,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+}
+
+/// A `ValidationObserver` that counts outcomes in memory, for a metrics
+/// endpoint or a periodic log line.
+///
+/// ```
+/// use std::sync::Arc;
+/// use field_validator::{ValidationCounters, ValidatorOptions};
+///
+/// let counters = Arc::new(ValidationCounters::new());
+/// let opts = ValidatorOptions { observer: Some(counters.clone()), ..ValidatorOptions::default() };
+/// ```
+#[derive(Debug, Default)]
+pub struct ValidationCounters {
+    types: Mutex<BTreeMap<String, TypeCounts>>,
+}
+
+impl ValidationCounters {
+    pub fn new() -> Self {
+        ValidationCounters::default()
+    }
+
+    /// The tallies for `type_name`, if it has been validated.
+    pub fn get(&self, type_name: &str) -> Option<TypeCounts> {
+        self.lock().get(type_name).cloned()
+    }
+
+    /// The tallies of every type validated so far.
+    pub fn snapshot(&self) -> BTreeMap<String, TypeCounts> {
+        self.lock().clone()
+    }
+
+    /// Return the tallies and start again from zero.
+    pub fn take(&self) -> BTreeMap<String, TypeCounts> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, TypeCounts>> {
+        self.types.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ValidationObserver for ValidationCounters {
+    fn on_validation(&self, event: &ValidationEvent<'_>) {
+        let mut types = self.lock();
+        let counts = types.entry(event.type_name.to_string()).or_default();
+        counts.validated += 1;
+        if let Some(code) = event.error_code() {
+            counts.rejected += 1;
+            *counts.by_code.entry(code.to_string()).or_default() += 1;
+        }
+        for (path, code) in event.fields() {
+            *counts
+                .by_field
+                .entry(aggregate_path(path))
+                .or_default()
+                .entry(code.to_string())
+                .or_default() += 1;
+        }
+        counts.total_duration += event.duration;
+        counts.max_duration = counts.max_duration.max(event.duration);
+    }
+}
+
+/// A `ValidationObserver` that emits a `tracing` event per validation
+/// under the `field_validator` target: `debug` for valid payloads, `info`
+/// for rejections, with the type, error code, field paths and duration.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl ValidationObserver for TracingObserver {
+    fn on_validation(&self, event: &ValidationEvent<'_>) {
+        let fields = event
+            .fields()
+            .iter()
+            .map(|(path, code)| format!("{}:{}", path, code))
+            .collect::<Vec<_>>()
+            .join(",");
+        let duration_us = event.duration.as_micros() as u64;
+        match event.error_code() {
+            None => tracing::debug!(
+                target: "field_validator",
+                type_name = event.type_name,
+                duration_us,
+                warnings = %fields,
+                "payload valid"
+            ),
+            Some(code) => tracing::info!(
+                target: "field_validator",
+                type_name = event.type_name,
+                code,
+                fields = %fields,
+                duration_us,
+                "payload rejected"
+            ),
+        }
+    }
+}
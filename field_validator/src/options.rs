@@ -0,0 +1,126 @@
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::messages::MessageCatalog;
+use crate::observe::ValidationObserver;
+
+/// Settings shared by every validation entry point.
+///
+/// Start from `ValidatorOptions::default()` and override what you need:
+///
+/// ```
+/// use field_validator::ValidatorOptions;
+///
+/// let opts = ValidatorOptions { max_depth: 8, ..ValidatorOptions::default() };
+/// ```
+#[derive(Debug, Clone)]
+pub struct ValidatorOptions {
+    /// Maximum size of the raw payload, in bytes.
+    pub max_bytes: usize,
+    /// Maximum nesting of arrays and objects; the top-level object is depth 1.
+    pub max_depth: usize,
+    /// Maximum number of elements in any single array.
+    pub max_array_len: usize,
+    /// Maximum number of keys in any single object.
+    pub max_object_keys: usize,
+    /// Maximum length of any string value or object key, in bytes.
+    pub max_string_len: usize,
+    /// The client's API version, e.g. from a request header (see
+    /// `parse_version`). Fields outside their `since`/`until` range for
+    /// this version are not required. `None` means the latest version.
+    pub api_version: Option<u32>,
+    /// Payload field carrying the API version. When set and present in the
+    /// payload it takes precedence over `api_version`.
+    pub version_field: Option<String>,
+    /// Templates for the messages of rendered responses. `None` uses the
+    /// fields' `message` attributes and the built-in English messages.
+    pub messages: Option<Arc<MessageCatalog>>,
+    /// The client's locale, e.g. from `MessageCatalog::negotiate` on the
+    /// `Accept-Language` header. `None` means the catalog's default.
+    pub locale: Option<String>,
+    /// Whether to stop at the first problem or look for all of them.
+    pub mode: ValidationMode,
+    /// Maximum number of missing or invalid fields to report in
+    /// `CollectAll` mode; the check stops once it has found this many.
+    pub max_errors: usize,
+    /// Told about every validation run, e.g. `ValidationCounters`.
+    pub observer: Option<Arc<dyn ValidationObserver>>,
+}
+
+/// How much of a payload a check examines once it has found a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Stop at the first missing or invalid field, which is cheapest when
+    /// the caller only needs to know the payload is bad.
+    FailFast,
+    /// Report every problem, across nested types, arrays and formats, up
+    /// to `max_errors`.
+    #[default]
+    CollectAll,
+}
+
+impl Default for ValidatorOptions {
+    /// Limits generous enough for ordinary API requests (the 6 MB Lambda
+    /// payload cap is far above any of our request types) while keeping a
+    /// hostile payload from costing much to parse.
+    fn default() -> Self {
+        ValidatorOptions {
+            max_bytes: 1024 * 1024,
+            max_depth: 32,
+            max_array_len: 10_000,
+            max_object_keys: 1_000,
+            max_string_len: 64 * 1024,
+            api_version: None,
+            version_field: None,
+            messages: None,
+            locale: None,
+            mode: ValidationMode::CollectAll,
+            max_errors: usize::MAX,
+            observer: None,
+        }
+    }
+}
+
+impl ValidatorOptions {
+    /// Options with every limit disabled.
+    pub fn unlimited() -> Self {
+        ValidatorOptions {
+            max_bytes: usize::MAX,
+            max_depth: usize::MAX,
+            max_array_len: usize::MAX,
+            max_object_keys: usize::MAX,
+            max_string_len: usize::MAX,
+            ..ValidatorOptions::default()
+        }
+    }
+
+    /// How many missing or invalid fields a check reports before stopping.
+    pub fn error_limit(&self) -> usize {
+        match self.mode {
+            ValidationMode::FailFast => 1,
+            ValidationMode::CollectAll => self.max_errors.max(1),
+        }
+    }
+
+    /// The API version to validate `payload` against: the `version_field`
+    /// if set and present, otherwise `api_version`.
+    pub fn version_for(&self, payload: &Value) -> Option<u32> {
+        let from_payload = self
+            .version_field
+            .as_deref()
+            .and_then(|field| payload.get(field))
+            .and_then(|v| match v {
+                Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+                Value::String(s) => parse_version(s),
+                _ => None,
+            });
+        from_payload.or(self.api_version)
+    }
+}
+
+/// Parse an API version as sent in a header or payload: `"2"` or `"v2"`.
+pub fn parse_version(s: &str) -> Option<u32> {
+    let s = s.trim();
+    let s = s.strip_prefix(['v', 'V']).unwrap_or(s);
+    s.parse().ok()
+}
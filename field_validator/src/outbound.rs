@@ -0,0 +1,77 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::plan::cached_serialized_plan;
+use crate::render::SourceRenderer;
+use crate::report::ValidationReport;
+use crate::validate::{ValidateFields, ValidationError};
+
+/// Check a value we are about to send, such as an SNS message or a
+/// response body, against its own `ValidateFields` contract.
+///
+/// The value is serialized to JSON and checked like an incoming payload
+/// for the latest API version, against the fields as `Serialize` writes
+/// them: serialize-side renames apply, `skip_serializing` fields must be
+/// absent and `skip_deserializing` ones present, and `skip_serializing_if`
+/// fields may be left out. Required fields must be present and non-null,
+/// and every field must have its type and format. Keys that are not
+/// written fields of `T` are reported too.
+pub fn validate_serialized<T>(value: &T) -> Result<ValidationReport, ValidationError>
+where
+    T: ValidateFields + Serialize,
+{
+    let json = serde_json::to_value(value).map_err(ValidationError::Serialize)?;
+    check_serialized::<T>(&json)
+}
+
+fn check_serialized<T: ValidateFields>(json: &Value) -> Result<ValidationReport, ValidationError> {
+    let plan = cached_serialized_plan::<T>();
+    let report = match plan.check_payload(json, None) {
+        Err(missing @ ValidationError::MissingFields(_)) => return Err(missing),
+        // Formats are checked again below, along with types
+        Err(_) => ValidationReport::default(),
+        Ok(report) => report,
+    };
+    let findings = plan.check_partial(json);
+    if findings.is_empty() {
+        Ok(report)
+    } else {
+        Err(ValidationError::InvalidFields(findings))
+    }
+}
+
+/// Panic with an annotated rendering of every problem if `value` breaks
+/// its outbound contract; for use in tests.
+#[track_caller]
+pub fn assert_serialized_valid<T>(value: &T) -> ValidationReport
+where
+    T: ValidateFields + Serialize,
+{
+    let json = match serde_json::to_value(value) {
+        Ok(json) => json,
+        Err(e) => panic!("cannot serialize {}: {}", std::any::type_name::<T>(), e),
+    };
+    match check_serialized::<T>(&json) {
+        Ok(report) => report,
+        Err(err) => {
+            let source = serde_json::to_string_pretty(&json).expect("a JSON value serializes");
+            panic!(
+                "outgoing {} breaks its contract:\n{}",
+                std::any::type_name::<T>(),
+                SourceRenderer::with_plan(&source, cached_serialized_plan::<T>()).render_error(&err)
+            )
+        }
+    }
+}
+
+/// `assert_serialized_valid` in debug builds; does nothing in release
+/// builds, so it can guard outgoing values on hot paths.
+#[track_caller]
+pub fn debug_assert_serialized<T>(value: &T)
+where
+    T: ValidateFields + Serialize,
+{
+    if cfg!(debug_assertions) {
+        assert_serialized_valid(value);
+    }
+}
@@ -0,0 +1,724 @@
+use serde_json::{Map, Value};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::descriptor::{version_in_range, FieldDescriptor, TypeShape};
+use crate::finding::{Finding, FindingKind};
+use crate::format::Format;
+use crate::limits::PathSegment;
+use crate::redact;
+use crate::schema::SchemaField;
+use crate::report::ValidationReport;
+use crate::validate::{MissingFieldsError, ValidateFields, ValidationError};
+
+/// A per-type validation program, compiled once from the type's
+/// descriptors and reused for every payload.
+///
+/// The plan flattens required fields, `required_if` constraints and the
+/// fields of `nested` types into a tree with precomputed dotted paths, so a
+/// check only allocates when it finds something missing.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationPlan {
+    fields: Vec<PlanField>,
+    /// Whether `fields` lists every field of the type, so other keys are
+    /// unknown. False for plans built from bare required-field lists.
+    closed: bool,
+    /// Whether any field, here or in a nested plan, is `sensitive`.
+    sensitive: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PlanField {
+    /// Wire name of the field within its parent object.
+    name: Box<str>,
+    /// Other keys the field is accepted under.
+    aliases: Vec<Box<str>>,
+    /// Dotted path from the payload root, or from the enclosing collection
+    /// element for plans under `NestedPlan::Each`; used in error reports.
+    path: Box<str>,
+    required: bool,
+    shape: TypeShape,
+    nullable: bool,
+    since: Option<u32>,
+    until: Option<u32>,
+    deprecated: Option<Box<str>>,
+    sensitive: bool,
+    message: Option<Box<str>>,
+    format: Option<Format>,
+    /// The strings supplied values must be one of, if limited.
+    allowed: Option<Vec<String>>,
+    /// Keys of siblings whose presence makes this field required,
+    /// including their aliases.
+    required_if: Vec<Box<str>>,
+    nested: Option<NestedPlan>,
+}
+
+#[derive(Debug, Clone)]
+enum NestedPlan {
+    /// The field holds one object of the nested type.
+    Object(ValidationPlan),
+    /// The field holds an array (or map) of such objects.
+    Each(ValidationPlan),
+}
+
+impl ValidationPlan {
+    /// Compile the plan for `T`, from its descriptors when it has them and
+    /// from `required_fields()` / `conditional_fields()` otherwise.
+    pub fn for_type<T: ValidateFields>() -> Self {
+        if T::fields().is_empty() {
+            return ValidationPlan::from_field_lists(
+                T::required_fields(),
+                T::conditional_fields(),
+            );
+        }
+        ValidationPlan::from_descriptors(T::fields())
+    }
+
+    /// Compile the plan for values of `T` as `Serialize` writes them, from
+    /// `T::serialized_fields()`; see `for_type` for types without descriptors.
+    pub fn for_serialized<T: ValidateFields>() -> Self {
+        if T::serialized_fields().is_empty() {
+            return ValidationPlan::for_type::<T>();
+        }
+        ValidationPlan::from_descriptors(T::serialized_fields())
+    }
+
+    /// Compile a plan from a descriptor table.
+    pub fn from_descriptors(fields: &'static [FieldDescriptor]) -> Self {
+        let mut stack = Vec::new();
+        ValidationPlan::build(fields, "", &mut stack)
+    }
+
+    fn from_field_lists(required: &[&str], conditional: &[(&str, &str)]) -> Self {
+        let mut fields: Vec<PlanField> = required
+            .iter()
+            .map(|&name| PlanField {
+                name: name.into(),
+                aliases: Vec::new(),
+                path: name.into(),
+                required: true,
+                shape: TypeShape::Other,
+                nullable: false,
+                since: None,
+                until: None,
+                deprecated: None,
+                sensitive: false,
+                message: None,
+                format: None,
+                allowed: None,
+                required_if: Vec::new(),
+                nested: None,
+            })
+            .collect();
+        for &(name, trigger) in conditional {
+            fields.push(PlanField {
+                name: name.into(),
+                aliases: Vec::new(),
+                path: name.into(),
+                required: false,
+                shape: TypeShape::Other,
+                nullable: true,
+                since: None,
+                until: None,
+                deprecated: None,
+                sensitive: false,
+                message: None,
+                format: None,
+                allowed: None,
+                required_if: vec![trigger.into()],
+                nested: None,
+            });
+        }
+        ValidationPlan { fields, closed: false, sensitive: false }
+    }
+
+    /// `stack` holds the descriptor tables currently being expanded, so a
+    /// recursive type stops at its first repetition instead of looping.
+    fn build(
+        fields: &'static [FieldDescriptor],
+        prefix: &str,
+        stack: &mut Vec<*const FieldDescriptor>,
+    ) -> Self {
+        stack.push(fields.as_ptr());
+        let mut sensitive = false;
+        let siblings = fields;
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let path = if prefix.is_empty() {
+                    field.name.to_string()
+                } else {
+                    format!("{}.{}", prefix, field.name)
+                };
+                let nested_fields = field.nested_fields();
+                let nested = if nested_fields.is_empty() || stack.contains(&nested_fields.as_ptr()) {
+                    None
+                } else {
+                    match field.shape {
+                        TypeShape::Object => Some(NestedPlan::Object(ValidationPlan::build(
+                            nested_fields,
+                            &path,
+                            stack,
+                        ))),
+                        // Element paths are relative; the index is spliced in at check time
+                        TypeShape::Array(_) | TypeShape::Map(_) => Some(NestedPlan::Each(
+                            ValidationPlan::build(nested_fields, "", stack),
+                        )),
+                        _ => None,
+                    }
+                };
+                sensitive |= field.sensitive
+                    || matches!(&nested, Some(NestedPlan::Object(p) | NestedPlan::Each(p)) if p.sensitive);
+                let required_if = field
+                    .required_if()
+                    .flat_map(|trigger| {
+                        let aliases = siblings.iter().filter(move |s| s.name == trigger).flat_map(|s| s.aliases());
+                        std::iter::once(trigger).chain(aliases)
+                    })
+                    .map(Into::into)
+                    .collect();
+                PlanField {
+                    name: field.name.into(),
+                    aliases: field.aliases().map(Into::into).collect(),
+                    required: !field.optional,
+                    shape: field.shape,
+                    nullable: field.nullable,
+                    since: field.since,
+                    until: field.until,
+                    deprecated: field.deprecated.map(Into::into),
+                    sensitive: field.sensitive,
+                    message: field.message.map(Into::into),
+                    format: field.format(),
+                    allowed: field.allowed().map(|values| values.iter().map(|v| v.to_string()).collect()),
+                    required_if,
+                    nested,
+                    path: path.into(),
+                }
+            })
+            .collect();
+        stack.pop();
+        ValidationPlan { fields, closed: true, sensitive }
+    }
+
+    /// Compile a plan from runtime schema fields; types must already have
+    /// been checked by `DynamicSchema`.
+    pub(crate) fn from_schema(fields: &[SchemaField]) -> Self {
+        ValidationPlan::build_schema(fields, "")
+    }
+
+    fn build_schema(fields: &[SchemaField], prefix: &str) -> Self {
+        let mut sensitive = false;
+        let siblings = fields;
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let path = if prefix.is_empty() {
+                    field.name.clone()
+                } else {
+                    format!("{}.{}", prefix, field.name)
+                };
+                let shape = field.shape().unwrap_or(TypeShape::Any);
+                let nested = if field.fields.is_empty() {
+                    None
+                } else {
+                    match shape {
+                        TypeShape::Object => {
+                            Some(NestedPlan::Object(ValidationPlan::build_schema(&field.fields, &path)))
+                        }
+                        TypeShape::Array(_) | TypeShape::Map(_) => {
+                            Some(NestedPlan::Each(ValidationPlan::build_schema(&field.fields, "")))
+                        }
+                        _ => None,
+                    }
+                };
+                sensitive |= field.sensitive
+                    || matches!(&nested, Some(NestedPlan::Object(p) | NestedPlan::Each(p)) if p.sensitive);
+                let required_if = field
+                    .required_if
+                    .iter()
+                    .flat_map(|trigger| {
+                        let aliases = siblings.iter().filter(move |s| s.name == *trigger).flat_map(|s| &s.aliases);
+                        std::iter::once(trigger).chain(aliases)
+                    })
+                    .map(|t| t.as_str().into())
+                    .collect();
+                PlanField {
+                    name: field.name.as_str().into(),
+                    aliases: field.aliases.iter().map(|a| a.as_str().into()).collect(),
+                    required: !field.optional && field.required_if.is_none(),
+                    shape,
+                    nullable: field.is_nullable(),
+                    since: field.since,
+                    until: field.until,
+                    deprecated: field.deprecated.as_deref().map(Into::into),
+                    sensitive: field.sensitive,
+                    message: field.message.as_deref().map(Into::into),
+                    format: field.format(),
+                    allowed: field.allowed.clone(),
+                    required_if,
+                    nested,
+                    path: path.into(),
+                }
+            })
+            .collect();
+        ValidationPlan { fields, closed: true, sensitive }
+    }
+
+    /// Whether the type has `sensitive` fields, directly or through nested types.
+    pub fn has_sensitive_fields(&self) -> bool {
+        self.sensitive
+    }
+
+    /// Whether the field at dotted `path` (as reported in findings) is
+    /// `sensitive`. Array indexes and map keys select nothing.
+    pub fn is_sensitive_path(&self, path: &str) -> bool {
+        let mut plan = self;
+        let mut segments = path.split('.');
+        while let Some(segment) = segments.next() {
+            let Some(field) = plan.fields.iter().find(|f| *f.name == *segment) else {
+                return false;
+            };
+            match &field.nested {
+                _ if field.sensitive => return true,
+                Some(NestedPlan::Object(inner)) => plan = inner,
+                Some(NestedPlan::Each(inner)) => {
+                    segments.next();
+                    plan = inner;
+                }
+                None => return false,
+            }
+        }
+        false
+    }
+
+    /// Render a payload path for `RedactionPolicy::All`: keys naming fields
+    /// of this plan (or its nested plans) and array indexes are kept, and
+    /// every other key, such as a map key, becomes `*`.
+    pub(crate) fn redact_path(&self, segments: &[PathSegment]) -> String {
+        let mut plan = Some(self);
+        let mut out = Vec::with_capacity(segments.len());
+        let mut segments = segments.iter();
+        while let Some(segment) = segments.next() {
+            let key = match segment {
+                PathSegment::Index(i) => {
+                    out.push(i.to_string());
+                    continue;
+                }
+                PathSegment::Key(key) => key,
+            };
+            let Some(field) = plan.and_then(|p| p.fields.iter().find(|f| f.answers_to(key))) else {
+                out.push("*".to_string());
+                plan = None;
+                continue;
+            };
+            out.push(key.clone());
+            plan = match &field.nested {
+                Some(NestedPlan::Object(inner)) => Some(inner),
+                Some(NestedPlan::Each(inner)) => {
+                    // The element's index or map key
+                    match segments.next() {
+                        Some(PathSegment::Index(i)) => out.push(i.to_string()),
+                        Some(PathSegment::Key(_)) => out.push("*".to_string()),
+                        None => {}
+                    }
+                    Some(inner)
+                }
+                None => None,
+            };
+        }
+        out.join(".")
+    }
+
+    /// The `message` template of the field at dotted `path`, if it has one.
+    /// Array indexes and map keys select the element's plan.
+    pub fn field_message(&self, path: &str) -> Option<&str> {
+        let mut plan = self;
+        let mut segments = path.split('.');
+        let mut field = None;
+        while let Some(segment) = segments.next() {
+            let found = plan.fields.iter().find(|f| *f.name == *segment)?;
+            field = Some(found);
+            match &found.nested {
+                Some(NestedPlan::Object(inner)) => plan = inner,
+                Some(NestedPlan::Each(inner)) => {
+                    segments.next()?;
+                    field = None;
+                    plan = inner;
+                }
+                None => break,
+            }
+        }
+        if segments.next().is_some() {
+            return None;
+        }
+        field?.message.as_deref()
+    }
+
+    /// Check a parsed payload, reporting every missing field by its dotted path.
+    ///
+    /// A payload that is not an object is missing all top-level required fields.
+    pub fn check(&self, value: &Value) -> Result<(), MissingFieldsError> {
+        self.check_version(value, None)
+    }
+
+    /// Like `check`, for a client on API `version` (`None` for the latest):
+    /// fields outside their `since`/`until` range are not required.
+    pub fn check_version(&self, value: &Value, version: Option<u32>) -> Result<(), MissingFieldsError> {
+        self.check_report(value, version).map(|_| ())
+    }
+
+    /// Like `check_version`, also collecting warnings for deprecated fields
+    /// the payload supplies. Formats and allowed values are not checked;
+    /// see `check_payload`.
+    pub fn check_report(
+        &self,
+        value: &Value,
+        version: Option<u32>,
+    ) -> Result<ValidationReport, MissingFieldsError> {
+        self.check_report_limited(value, version, usize::MAX)
+    }
+
+    /// Like `check_report`, stopping once `max_errors` missing fields have
+    /// been found.
+    pub fn check_report_limited(
+        &self,
+        value: &Value,
+        version: Option<u32>,
+        max_errors: usize,
+    ) -> Result<ValidationReport, MissingFieldsError> {
+        let outcome = self.walk(value, version, max_errors.max(1));
+        if outcome.missing.is_empty() {
+            Ok(ValidationReport { warnings: outcome.warnings, ..ValidationReport::default() })
+        } else {
+            Err(MissingFieldsError { missing_fields: outcome.missing })
+        }
+    }
+
+    /// Like `check_report`, also rejecting supplied values that have the
+    /// wrong JSON type, do not have their field's format or are not among
+    /// its allowed values. Missing fields are reported first.
+    pub fn check_payload(
+        &self,
+        value: &Value,
+        version: Option<u32>,
+    ) -> Result<ValidationReport, ValidationError> {
+        self.check_payload_limited(value, version, usize::MAX)
+    }
+
+    /// Like `check_payload`, stopping once `max_errors` missing or invalid
+    /// fields have been found (see `ValidatorOptions::error_limit`).
+    pub fn check_payload_limited(
+        &self,
+        value: &Value,
+        version: Option<u32>,
+        max_errors: usize,
+    ) -> Result<ValidationReport, ValidationError> {
+        let outcome = self.walk(value, version, max_errors.max(1));
+        if !outcome.missing.is_empty() {
+            Err(MissingFieldsError { missing_fields: outcome.missing }.into())
+        } else if !outcome.invalid.is_empty() {
+            Err(ValidationError::InvalidFields(outcome.invalid))
+        } else {
+            Ok(ValidationReport { warnings: outcome.warnings, ..ValidationReport::default() })
+        }
+    }
+
+    fn walk(&self, value: &Value, version: Option<u32>, limit: usize) -> Outcome {
+        let mut outcome = Outcome { limit, ..Outcome::default() };
+        match value.as_object() {
+            Some(obj) => self.check_object(obj, "", version, &mut outcome),
+            None => outcome.missing.extend(
+                self.fields
+                    .iter()
+                    .filter(|f| f.required && f.exists_in(version))
+                    .map(|f| f.path.to_string())
+                    .take(limit),
+            ),
+        }
+        outcome.invalid.truncate(limit - outcome.missing.len().min(limit));
+        outcome
+    }
+
+    /// `base` is the path of this object when it is an element of a
+    /// collection (e.g. `history.3`), and empty otherwise.
+    fn check_object(
+        &self,
+        obj: &Map<String, Value>,
+        base: &str,
+        version: Option<u32>,
+        outcome: &mut Outcome,
+    ) {
+        for field in &self.fields {
+            if outcome.is_full() {
+                return;
+            }
+            if !field.exists_in(version) {
+                continue;
+            }
+            let value = field.lookup(obj).filter(|v| !v.is_null());
+            let Some(value) = value else {
+                let triggered = field
+                    .required_if
+                    .iter()
+                    .any(|t| obj.get(&**t).is_some_and(|v| !v.is_null()));
+                if field.required || triggered {
+                    outcome.missing.push(field.display_path(base));
+                }
+                continue;
+            };
+            if let Some(note) = &field.deprecated {
+                outcome.warnings.push(Finding {
+                    path: field.display_path(base),
+                    kind: FindingKind::Deprecated { note: note.to_string() },
+                });
+            }
+            if !field.shape.matches(value) {
+                outcome.invalid.push(Finding {
+                    path: field.display_path(base),
+                    kind: FindingKind::WrongType { expected: field.shape },
+                });
+                continue;
+            }
+            field.check_value(value, base, &mut outcome.invalid);
+            match &field.nested {
+                Some(NestedPlan::Object(plan)) => {
+                    if let Some(inner) = value.as_object() {
+                        plan.check_object(inner, base, version, outcome);
+                    }
+                }
+                Some(NestedPlan::Each(plan)) => {
+                    let path = field.display_path(base);
+                    match value {
+                        Value::Array(items) => {
+                            for (i, item) in items.iter().enumerate() {
+                                if outcome.is_full() {
+                                    return;
+                                }
+                                if let Some(inner) = item.as_object() {
+                                    let element = format!("{}.{}", path, i);
+                                    plan.check_object(inner, &element, version, outcome);
+                                }
+                            }
+                        }
+                        Value::Object(map) => {
+                            for (key, item) in map {
+                                if outcome.is_full() {
+                                    return;
+                                }
+                                if let Some(inner) = item.as_object() {
+                                    let element = format!("{}.{}", path, redact::map_key(key));
+                                    plan.check_object(inner, &element, version, outcome);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+impl ValidationPlan {
+    /// Check only the fields a payload supplies, as for a PATCH request:
+    /// required-ness is ignored, but each supplied value must have the
+    /// field's type, format and allowed values (and may only be `null` if the field is an `Option`),
+    /// and keys that are not fields of the type are reported.
+    pub fn check_partial(&self, value: &Value) -> Vec<Finding> {
+        self.check_partial_limited(value, usize::MAX)
+    }
+
+    /// Like `check_partial`, stopping once `max_errors` findings have been made.
+    pub fn check_partial_limited(&self, value: &Value, max_errors: usize) -> Vec<Finding> {
+        let limit = max_errors.max(1);
+        let mut findings = Vec::new();
+        match value.as_object() {
+            Some(obj) => self.check_partial_object(obj, "", "", limit, &mut findings),
+            None => findings.push(Finding {
+                path: String::new(),
+                kind: FindingKind::WrongType { expected: TypeShape::Object },
+            }),
+        }
+        findings.truncate(limit);
+        findings
+    }
+
+    /// `base` is as for `check_object`; `at` is the full path of `obj`
+    /// itself, used to report its unknown keys.
+    fn check_partial_object(
+        &self,
+        obj: &Map<String, Value>,
+        base: &str,
+        at: &str,
+        limit: usize,
+        findings: &mut Vec<Finding>,
+    ) {
+        for (key, value) in obj {
+            if findings.len() >= limit {
+                return;
+            }
+            let Some(field) = self.fields.iter().find(|f| f.answers_to(key)) else {
+                if self.closed {
+                    let path = if at.is_empty() { key.clone() } else { format!("{}.{}", at, key) };
+                    findings.push(Finding { path, kind: FindingKind::UnknownField });
+                }
+                continue;
+            };
+            let wrong_type = if value.is_null() {
+                !field.nullable
+            } else {
+                !field.shape.matches(value)
+            };
+            if wrong_type {
+                findings.push(Finding {
+                    path: field.display_path(base),
+                    kind: FindingKind::WrongType { expected: field.shape },
+                });
+                continue;
+            }
+            if !value.is_null() {
+                field.check_value(value, base, findings);
+            }
+            match (&field.nested, value) {
+                (Some(NestedPlan::Object(plan)), Value::Object(inner)) => {
+                    plan.check_partial_object(inner, base, &field.display_path(base), limit, findings);
+                }
+                (Some(NestedPlan::Each(plan)), Value::Array(items)) => {
+                    let path = field.display_path(base);
+                    for (i, item) in items.iter().enumerate() {
+                        if let Some(inner) = item.as_object() {
+                            let element = format!("{}.{}", path, i);
+                            plan.check_partial_object(inner, &element, &element, limit, findings);
+                        }
+                    }
+                }
+                (Some(NestedPlan::Each(plan)), Value::Object(map)) => {
+                    let path = field.display_path(base);
+                    for (key, item) in map {
+                        if let Some(inner) = item.as_object() {
+                            let element = format!("{}.{}", path, redact::map_key(key));
+                            plan.check_partial_object(inner, &element, &element, limit, findings);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// What one walk over a payload found.
+#[derive(Default)]
+struct Outcome {
+    missing: Vec<String>,
+    warnings: Vec<Finding>,
+    /// Values without their field's format or not among its allowed values.
+    invalid: Vec<Finding>,
+    /// How many missing and invalid fields to look for.
+    limit: usize,
+}
+
+impl Outcome {
+    fn is_full(&self) -> bool {
+        self.missing.len() + self.invalid.len() >= self.limit
+    }
+}
+
+impl PlanField {
+    /// Report `value`, or each non-null element of an array or map of
+    /// scalars, if it does not have the field's format or is not one of its
+    /// allowed values.
+    fn check_value(&self, value: &Value, base: &str, findings: &mut Vec<Finding>) {
+        if self.format.is_none() && self.allowed.is_none() {
+            return;
+        }
+        let path = self.display_path(base);
+        let mut check = |path: String, value: &Value| {
+            if let Some(format) = self.format {
+                if !format.matches(value) {
+                    findings.push(Finding { path: path.clone(), kind: FindingKind::InvalidFormat { format } });
+                }
+            }
+            if let Some(allowed) = &self.allowed {
+                if !value.as_str().is_some_and(|s| allowed.iter().any(|a| a == s)) {
+                    findings.push(Finding { path, kind: FindingKind::InvalidValue { allowed: allowed.clone() } });
+                }
+            }
+        };
+        match (self.shape, value) {
+            (TypeShape::Array(_), Value::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    if !item.is_null() {
+                        check(format!("{}.{}", path, i), item);
+                    }
+                }
+            }
+            (TypeShape::Map(_), Value::Object(map)) => {
+                for (key, item) in map {
+                    if !item.is_null() {
+                        check(format!("{}.{}", path, redact::map_key(key)), item);
+                    }
+                }
+            }
+            _ => check(path, value),
+        }
+    }
+
+    fn exists_in(&self, version: Option<u32>) -> bool {
+        version_in_range(version, self.since, self.until)
+    }
+
+    /// The field's value in `obj`, under its name or the first alias present.
+    fn lookup<'v>(&self, obj: &'v Map<String, Value>) -> Option<&'v Value> {
+        obj.get(&*self.name).or_else(|| self.aliases.iter().find_map(|alias| obj.get(&**alias)))
+    }
+
+    /// Whether `key` is the field's name or one of its aliases.
+    fn answers_to(&self, key: &str) -> bool {
+        *self.name == *key || self.aliases.iter().any(|alias| **alias == *key)
+    }
+
+    /// The field's path, prefixed with the enclosing collection element's path.
+    fn display_path(&self, base: &str) -> String {
+        if base.is_empty() {
+            self.path.to_string()
+        } else {
+            format!("{}.{}", base, self.path)
+        }
+    }
+}
+
+/// Plan cache for types whose `ValidateFields` impl does not cache its own.
+/// Keyed by `TypeId` (through `typeid`, since borrowing types are not
+/// `'static`); plans are leaked since they live for the program.
+///
+/// The plan is built without holding the lock, so a `build` that needs
+/// other types' plans cannot deadlock. If two threads race, one plan wins
+/// and the other is dropped.
+pub(crate) fn cached_plan<T: ValidateFields + ?Sized>(
+    build: impl FnOnce() -> ValidationPlan,
+) -> &'static ValidationPlan {
+    static PLANS: PlanCache = OnceLock::new();
+    cached_in::<T>(&PLANS, build)
+}
+
+/// `ValidationPlan::for_serialized::<T>()`, cached like `cached_plan`.
+pub(crate) fn cached_serialized_plan<T: ValidateFields>() -> &'static ValidationPlan {
+    static PLANS: PlanCache = OnceLock::new();
+    cached_in::<T>(&PLANS, ValidationPlan::for_serialized::<T>)
+}
+
+type PlanCache = OnceLock<Mutex<HashMap<TypeId, &'static ValidationPlan>>>;
+
+fn cached_in<T: ?Sized>(cache: &'static PlanCache, build: impl FnOnce() -> ValidationPlan) -> &'static ValidationPlan {
+    let plans = cache.get_or_init(Default::default);
+    let lock = || plans.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let key = typeid::of::<T>();
+    if let Some(plan) = lock().get(&key).copied() {
+        return plan;
+    }
+    let plan = build();
+    lock().entry(key).or_insert_with(|| Box::leak(Box::new(plan)))
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::validate::ValidateFields;
+
+/// Replacement for a redacted value.
+pub const MASK: &str = "***";
+
+/// Which payload values may appear in error messages.
+///
+/// Findings, missing-field errors and reports only name fields, never their
+/// values, so their `Display` and `Debug` output is safe to log. Values can
+/// leak through serde's deserialization errors (`invalid type: string
+/// "...", expected u32`), through map keys in field paths, and through
+/// handlers' own messages; the policy decides which of those are masked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedactionPolicy {
+    /// Messages are left as serde and handlers produce them.
+    Off,
+    /// Values of `#[field_validator(sensitive)]` fields are masked.
+    #[default]
+    SensitiveFields,
+    /// Every payload value is masked, and map keys in field paths are
+    /// rendered as `*`.
+    All,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(RedactionPolicy::SensitiveFields as u8);
+
+/// Set the policy for the whole process, e.g. `All` in production.
+pub fn set_redaction_policy(policy: RedactionPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn redaction_policy() -> RedactionPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        0 => RedactionPolicy::Off,
+        1 => RedactionPolicy::SensitiveFields,
+        _ => RedactionPolicy::All,
+    }
+}
+
+/// Whether a value should be masked under the current policy.
+fn should_mask(sensitive: bool) -> bool {
+    match redaction_policy() {
+        RedactionPolicy::Off => false,
+        RedactionPolicy::SensitiveFields => sensitive,
+        RedactionPolicy::All => true,
+    }
+}
+
+/// Render the value of field `path` of `T` for a message, masking it if
+/// the field is sensitive or the policy masks every value.
+///
+/// `path` is a dotted wire path such as `bike_info.vin`, as reported in
+/// findings. A field is sensitive if it or any field containing it is; see
+/// `ValidationPlan::is_sensitive_path`.
+pub fn field_value<T: ValidateFields>(path: &str, value: &impl fmt::Display) -> String {
+    if should_mask(T::validation_plan().is_sensitive_path(path)) {
+        MASK.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render a payload value that belongs to no particular field, such as an
+/// unknown discriminator; masked only when the policy masks every value.
+pub fn value(value: &impl fmt::Display) -> String {
+    if should_mask(false) {
+        MASK.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// A map key as it appears in a field path.
+pub(crate) fn map_key(key: &str) -> &str {
+    if redaction_policy() == RedactionPolicy::All {
+        "*"
+    } else {
+        key
+    }
+}
+
+/// A deserialization error whose message had payload values masked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactedError {
+    message: String,
+}
+
+impl fmt::Display for RedactedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RedactedError {}
+
+/// Box a deserialization error, replacing it with a `RedactedError` when
+/// the policy requires. serde does not say which field an error is about,
+/// so any error for a type with sensitive fields is masked.
+pub(crate) fn deserialize_error(
+    e: serde_json::Error,
+    has_sensitive_fields: bool,
+) -> Box<dyn std::error::Error> {
+    if should_mask(has_sensitive_fields) {
+        Box::new(RedactedError { message: mask_values(&e.to_string()) })
+    } else {
+        Box::new(e)
+    }
+}
+
+/// How serde's `Unexpected` and `unknown_variant` render a value, and the
+/// character that ends it.
+const VALUE_PREFIXES: &[(&str, char)] = &[
+    ("string \"", '"'),
+    ("integer `", '`'),
+    ("floating point `", '`'),
+    ("boolean `", '`'),
+    ("character `", '`'),
+    ("variant `", '`'),
+];
+
+/// Replace every value quoted in a serde error message with `MASK`.
+fn mask_values(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut rest = message;
+    'scan: while !rest.is_empty() {
+        for &(prefix, close) in VALUE_PREFIXES {
+            if let Some(after) = rest.strip_prefix(prefix) {
+                out.push_str(prefix);
+                out.push_str(MASK);
+                let mut escaped = false;
+                for (i, c) in after.char_indices() {
+                    if c == close && !escaped {
+                        out.push(close);
+                        rest = &after[i + 1..];
+                        continue 'scan;
+                    }
+                    escaped = c == '\\' && !escaped;
+                }
+                // Unterminated value; mask the rest of the message
+                return out;
+            }
+        }
+        let c = rest.chars().next().expect("non-empty");
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
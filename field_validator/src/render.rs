@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::finding::{Finding, FindingKind, Severity};
+use crate::options::ValidatorOptions;
+use crate::plan::ValidationPlan;
+use crate::redact::{redaction_policy, RedactionPolicy};
+use crate::report::ValidationReport;
+use crate::validate::{validate_json_with_report, ValidateFields, ValidationError};
+
+/// Renders validation problems against the original JSON text, with the
+/// line and column of each one and carets under the offending key or value:
+///
+/// ```text
+/// error[missing_field]: `bike_info.model` is required
+///  --> line 3, column 16
+///   |
+/// 3 |   "bike_info": { "year": 2020 },
+///   |                ^ `model` is missing from this object
+/// ```
+///
+/// Missing fields are reported at the object that should contain them.
+/// Values shown in the source are masked per the `RedactionPolicy`; give
+/// the renderer the type's plan so `sensitive` fields are masked too.
+/// Objects and arrays nested deeper than the default `max_depth` are
+/// located as a whole rather than indexed, which bounds the scan's stack.
+pub struct SourceRenderer<'a> {
+    source: &'a str,
+    plan: Option<&'a ValidationPlan>,
+    /// Spans of every key and value in `source`, by dotted path.
+    spans: HashMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// Byte range of the key, for object members.
+    key: Option<(usize, usize)>,
+    /// Byte range of the value.
+    value: (usize, usize),
+    scalar: bool,
+}
+
+/// Where a diagnostic points.
+enum Location {
+    /// A byte range of the source.
+    Span(usize, usize),
+    /// 1-based line and byte column, as reported by serde.
+    LineColumn(usize, usize),
+}
+
+struct Diagnostic {
+    severity: Severity,
+    code: &'static str,
+    message: String,
+    location: Location,
+    label: String,
+}
+
+impl<'a> SourceRenderer<'a> {
+    /// A renderer that masks values only under `RedactionPolicy::All`.
+    pub fn new(source: &'a str) -> Self {
+        let mut scanner = Scanner {
+            src: source.as_bytes(),
+            pos: 0,
+            max_depth: ValidatorOptions::default().max_depth,
+            spans: HashMap::new(),
+        };
+        // Invalid JSON indexes as far as it goes; syntax errors carry their own position
+        let _ = scanner.value(String::new(), None, 0);
+        SourceRenderer { source, plan: None, spans: scanner.spans }
+    }
+
+    /// A renderer that also masks the values of `plan`'s sensitive fields.
+    pub fn with_plan(source: &'a str, plan: &'a ValidationPlan) -> Self {
+        SourceRenderer { plan: Some(plan), ..SourceRenderer::new(source) }
+    }
+
+    pub fn for_type<T: ValidateFields>(source: &'a str) -> Self {
+        SourceRenderer::with_plan(source, T::validation_plan())
+    }
+
+    /// Render every problem in `error`.
+    pub fn render_error(&self, error: &ValidationError) -> String {
+        let diagnostics = match error {
+            ValidationError::Syntax(e) => vec![Diagnostic {
+                severity: Severity::Error,
+                code: error.code(),
+                message: error.to_string(),
+                location: Location::LineColumn(e.line(), e.column()),
+                label: "here".to_string(),
+            }],
+            ValidationError::Limit(limit) => vec![Diagnostic {
+                severity: Severity::Error,
+                code: error.code(),
+                message: limit.to_string(),
+                location: self.value_location(&limit.path),
+                label: format!("the limit is {}", limit.limit),
+            }],
+            ValidationError::MissingFields(missing) => missing
+                .missing_fields
+                .iter()
+                .map(|path| {
+                    self.diagnostic(&Finding { path: path.clone(), kind: FindingKind::Missing })
+                })
+                .collect(),
+            ValidationError::InvalidFields(findings) => {
+                findings.iter().map(|f| self.diagnostic(f)).collect()
+            }
+            ValidationError::Migration(_) | ValidationError::Serialize(_) | ValidationError::Io(_) => vec![Diagnostic {
+                severity: Severity::Error,
+                code: error.code(),
+                message: error.to_string(),
+                location: self.value_location(""),
+                label: String::new(),
+            }],
+        };
+        self.render(&diagnostics)
+    }
+
+    /// Render findings, e.g. the warnings of a `ValidationReport`.
+    pub fn render_findings(&self, findings: &[Finding]) -> String {
+        let diagnostics: Vec<Diagnostic> = findings.iter().map(|f| self.diagnostic(f)).collect();
+        self.render(&diagnostics)
+    }
+
+    fn diagnostic(&self, finding: &Finding) -> Diagnostic {
+        let (location, label) = match &finding.kind {
+            FindingKind::Missing => {
+                let (parent, name) = match finding.path.rsplit_once('.') {
+                    Some((parent, name)) => (parent, name),
+                    None => ("", finding.path.as_str()),
+                };
+                let location = match self.entry(parent) {
+                    Some(entry) => Location::Span(entry.value.0, entry.value.0 + 1),
+                    None => self.value_location(""),
+                };
+                (location, format!("`{}` is missing from this object", name))
+            }
+            FindingKind::WrongType { expected } => {
+                (self.value_location(&finding.path), format!("expected {}", expected))
+            }
+            FindingKind::InvalidFormat { format } => {
+                (self.value_location(&finding.path), format!("expected {}", format.description()))
+            }
+            FindingKind::InvalidValue { allowed } => {
+                (self.value_location(&finding.path), format!("expected one of: {}", allowed.join(", ")))
+            }
+            FindingKind::UnknownField => (self.key_location(&finding.path), "not a field of this type".to_string()),
+            FindingKind::Deprecated { note } => (self.key_location(&finding.path), note.clone()),
+        };
+        Diagnostic {
+            severity: finding.severity(),
+            code: finding.kind.code(),
+            message: finding.to_string(),
+            location,
+            label,
+        }
+    }
+
+    /// The span entry for `path`. Redacted paths have `*` for map keys; such
+    /// a segment matches any key, and when several keys match, the entry is
+    /// the closest object or array holding all of them.
+    fn entry(&self, path: &str) -> Option<&Entry> {
+        if let Some(entry) = self.spans.get(path) {
+            return Some(entry);
+        }
+        let pattern: Vec<&str> = path.split('.').collect();
+        if !pattern.contains(&"*") {
+            return None;
+        }
+        let matches: Vec<Vec<&str>> = self
+            .spans
+            .keys()
+            .map(|candidate| candidate.split('.').collect::<Vec<_>>())
+            .filter(|candidate| {
+                candidate.len() == pattern.len()
+                    && candidate.iter().zip(&pattern).all(|(c, p)| *p == "*" || c == p)
+            })
+            .collect();
+        let (first, rest) = matches.split_first()?;
+        let common = rest.iter().fold(first.len(), |common, other| {
+            first.iter().zip(other).take(common).take_while(|(a, b)| a == b).count()
+        });
+        self.spans.get(&first[..common].join("."))
+    }
+
+    /// The value at `path`, or the start of the payload if it has no span.
+    fn value_location(&self, path: &str) -> Location {
+        match self.entry(path) {
+            Some(entry) => Location::Span(entry.value.0, entry.value.1),
+            None => Location::Span(0, 0),
+        }
+    }
+
+    /// The key at `path`, falling back to its value.
+    fn key_location(&self, path: &str) -> Location {
+        match self.entry(path).and_then(|e| e.key) {
+            Some((start, end)) => Location::Span(start, end),
+            None => self.value_location(path),
+        }
+    }
+
+    /// The source with masked scalar values replaced by `*`, keeping
+    /// every character's column.
+    fn masked_source(&self) -> String {
+        let policy = redaction_policy();
+        let masked: Vec<(usize, usize)> = self
+            .spans
+            .iter()
+            .filter(|(path, entry)| {
+                entry.scalar
+                    && match policy {
+                        RedactionPolicy::Off => false,
+                        RedactionPolicy::SensitiveFields => {
+                            self.plan.is_some_and(|p| p.is_sensitive_path(path))
+                        }
+                        RedactionPolicy::All => true,
+                    }
+            })
+            .map(|(_, entry)| entry.value)
+            .collect();
+        if masked.is_empty() {
+            return self.source.to_string();
+        }
+        let bytes = self.source.as_bytes();
+        let mut hidden = vec![false; bytes.len()];
+        for (start, end) in masked {
+            // Keep a string's quotes
+            let quoted = (bytes[start] == b'"') as usize;
+            hidden[start + quoted..end - quoted].fill(true);
+        }
+        self.source
+            .char_indices()
+            .map(|(i, c)| if hidden[i] { '*' } else { c })
+            .collect()
+    }
+
+    fn render(&self, diagnostics: &[Diagnostic]) -> String {
+        let source = self.masked_source();
+        let lines: Vec<&str> = source.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).collect();
+        let mut out = String::new();
+        for (i, d) in diagnostics.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let severity = match d.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            let _ = writeln!(out, "{}[{}]: {}", severity, d.code, d.message);
+
+            // 1-based line, 0-based char column, caret count
+            let (line, column, width) = match d.location {
+                Location::Span(start, end) => {
+                    let (line, column) = line_column(self.source, start);
+                    let text = lines.get(line - 1).copied().unwrap_or("");
+                    let rest = text.chars().count().saturating_sub(column);
+                    let width = self.source.get(start..end).map_or(1, |s| s.chars().count());
+                    (line, column, width.min(rest).max(1))
+                }
+                Location::LineColumn(line, byte_column) => {
+                    let text = lines.get(line.max(1) - 1).copied().unwrap_or("");
+                    let column = text
+                        .get(..byte_column.saturating_sub(1))
+                        .map_or(0, |s| s.chars().count());
+                    (line.max(1), column, 1)
+                }
+            };
+            let text = lines.get(line - 1).copied().unwrap_or("");
+            let gutter = " ".repeat(line.to_string().len());
+            let _ = writeln!(out, "{} --> line {}, column {}", gutter, line, column + 1);
+            let _ = writeln!(out, "{} |", gutter);
+            let _ = writeln!(out, "{} | {}", line, text);
+            let _ = writeln!(
+                out,
+                "{} | {}{} {}",
+                gutter,
+                " ".repeat(column),
+                "^".repeat(width),
+                d.label
+            );
+        }
+        out
+    }
+}
+
+/// 1-based line and 0-based char column of byte `offset`.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count())
+}
+
+/// Records the span of every key and value while walking JSON text.
+/// Stops at the first malformed token.
+struct Scanner<'s> {
+    src: &'s [u8],
+    pos: usize,
+    /// Objects and arrays below this many enclosing ones are skipped whole.
+    max_depth: usize,
+    spans: HashMap<String, Entry>,
+}
+
+impl Scanner<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.src.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.src.get(self.pos) == Some(&byte) {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Index the value at the cursor, inside `depth` objects and arrays.
+    fn value(&mut self, path: String, key: Option<(usize, usize)>, depth: usize) -> Option<()> {
+        self.skip_whitespace();
+        let start = self.pos;
+        if depth >= self.max_depth && matches!(self.src.get(self.pos), Some(b'{' | b'[')) {
+            self.skip_nested()?;
+            self.spans.insert(path, Entry { key, value: (start, self.pos), scalar: false });
+            return Some(());
+        }
+        let child = |path: &str, segment: &str| {
+            if path.is_empty() {
+                segment.to_string()
+            } else {
+                format!("{}.{}", path, segment)
+            }
+        };
+        let scalar = match *self.src.get(self.pos)? {
+            b'{' => {
+                self.pos += 1;
+                if self.eat(b'}').is_none() {
+                    loop {
+                        self.skip_whitespace();
+                        let key_start = self.pos;
+                        let name = self.string()?;
+                        let key = (key_start, self.pos);
+                        self.eat(b':')?;
+                        self.value(child(&path, &name), Some(key), depth + 1)?;
+                        if self.eat(b',').is_none() {
+                            self.eat(b'}')?;
+                            break;
+                        }
+                    }
+                }
+                false
+            }
+            b'[' => {
+                self.pos += 1;
+                if self.eat(b']').is_none() {
+                    let mut index = 0;
+                    loop {
+                        self.value(child(&path, &index.to_string()), None, depth + 1)?;
+                        index += 1;
+                        if self.eat(b',').is_none() {
+                            self.eat(b']')?;
+                            break;
+                        }
+                    }
+                }
+                false
+            }
+            b'"' => {
+                self.string()?;
+                true
+            }
+            _ => {
+                while self
+                    .src
+                    .get(self.pos)
+                    .is_some_and(|b| !matches!(b, b',' | b'}' | b']') && !b.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return None;
+                }
+                true
+            }
+        };
+        self.spans.insert(path, Entry { key, value: (start, self.pos), scalar });
+        Some(())
+    }
+
+    /// Move past the object or array at the cursor without recursing.
+    fn skip_nested(&mut self) -> Option<()> {
+        let mut open = 0usize;
+        loop {
+            match *self.src.get(self.pos)? {
+                b'{' | b'[' => open += 1,
+                b'}' | b']' => {
+                    open -= 1;
+                    if open == 0 {
+                        self.pos += 1;
+                        return Some(());
+                    }
+                }
+                b'"' => {
+                    self.skip_string()?;
+                    continue;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Move past the string literal at the cursor.
+    fn skip_string(&mut self) -> Option<()> {
+        if self.src.get(self.pos) != Some(&b'"') {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            match *self.src.get(self.pos)? {
+                b'\\' => self.pos += 2,
+                b'"' => {
+                    self.pos += 1;
+                    return Some(());
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    /// Read a string literal at the cursor and return its decoded value.
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        self.skip_string()?;
+        let literal = std::str::from_utf8(self.src.get(start..self.pos)?).ok()?;
+        serde_json::from_str(literal).ok()
+    }
+}
+
+/// Validate `json` as a `T`, panicking with an annotated rendering of every
+/// problem if it is invalid; for use in tests.
+#[track_caller]
+pub fn assert_valid<T: ValidateFields>(json: &str) -> ValidationReport {
+    match validate_json_with_report::<T>(json, &ValidatorOptions::default()) {
+        Ok(report) => report,
+        Err(err) => panic!(
+            "payload is not a valid {}:\n{}",
+            std::any::type_name::<T>(),
+            SourceRenderer::for_type::<T>(json).render_error(&err)
+        ),
+    }
+}
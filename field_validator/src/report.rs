@@ -0,0 +1,62 @@
+use crate::finding::Finding;
+
+/// What happened during a successful validation, beyond "it passed".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Migrations applied to the payload before it was validated, in order.
+    pub migrations: Vec<AppliedMigration>,
+    /// Non-fatal findings, such as deprecated fields the payload supplied.
+    pub warnings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// The warnings as messages, e.g. for a response body field.
+    pub fn warning_messages(&self) -> Vec<String> {
+        self.warnings.iter().map(ToString::to_string).collect()
+    }
+
+    /// The warnings as a single `Warning` header value (RFC 7234 code 299),
+    /// or `None` when there are none.
+    ///
+    /// Each message is a quoted-string with only `"` and `\` escaped.
+    /// Control characters, which a header cannot carry, become spaces.
+    /// RFC 9111 obsoletes the `Warning` header and clients may ignore it, so
+    /// prefer `warning_messages` in the body where the response format allows.
+    pub fn warning_header(&self) -> Option<String> {
+        if self.warnings.is_empty() {
+            return None;
+        }
+        let values: Vec<String> = self
+            .warnings
+            .iter()
+            .map(|w| format!("299 - {}", quoted_string(&w.to_string())))
+            .collect();
+        Some(values.join(", "))
+    }
+}
+
+/// `s` as an HTTP quoted-string (RFC 9110 section 5.6.4).
+fn quoted_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            c if c.is_control() => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// One migration step that ran on a payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// The version the step upgraded from; it produced `from + 1`.
+    pub from: u32,
+    pub name: &'static str,
+}
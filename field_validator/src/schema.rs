@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+use crate::descriptor::{FieldDescriptor, TypeShape};
+use crate::diff::SchemaDiff;
+use crate::finding::Finding;
+use crate::format::Format;
+use crate::limits::parse_with_limits;
+use crate::observe::observe;
+use crate::options::ValidatorOptions;
+use crate::plan::ValidationPlan;
+use crate::report::ValidationReport;
+use crate::validate::{ValidateFields, ValidationError};
+
+/// A request schema loaded at runtime instead of derived from a Rust type.
+///
+/// It compiles to the same `ValidationPlan` the derive produces, so it
+/// validates payloads and reports findings exactly as an equivalent struct
+/// would. Schemas are written in JSON or, with the `yaml` feature, YAML:
+///
+/// ```json
+/// {
+///   "name": "Request",
+///   "fields": [
+///     { "name": "bike_identifier", "type": "string" },
+///     { "name": "current_mode", "type": "string", "optional": true },
+///     { "name": "bike_info", "type": "object", "fields": [
+///       { "name": "model", "type": "string" },
+///       { "name": "year", "type": "integer" }
+///     ] }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicSchema {
+    name: String,
+    fields: Vec<SchemaField>,
+    plan: ValidationPlan,
+}
+
+/// One field of a `DynamicSchema`; mirrors the derive's field attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaField {
+    /// The key used on the wire.
+    pub name: String,
+    /// Other keys the field is accepted under, as with `#[serde(alias)]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// `string`, `integer`, `number`, `boolean`, `object`, `array`, `map` or `any`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Element type of an `array` or `map`; defaults to `any`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<String>,
+    /// Whether the field may be absent.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub optional: bool,
+    /// Whether a supplied value may be `null`; defaults to whether the
+    /// field is `optional` or `required_if`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+    /// Required only when this sibling is present and non-null.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_if: Option<String>,
+    /// A built-in format supplied values must have, written as for
+    /// `Format::parse`, e.g. `uuid` or `epoch_seconds(0, 4102444800)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The strings supplied values must be one of, written `"enum"` as in
+    /// JSON Schema.
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub allowed: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub sensitive: bool,
+    /// Message template for problems with the field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Fields of an `object`, or of each element of an `array` or `map`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SchemaField>,
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    name: String,
+    fields: Vec<SchemaField>,
+}
+
+/// Error returned when a schema document cannot be loaded.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The document is not a well-formed schema.
+    Parse(String),
+    /// A field's `type` or `items` is not a known type name.
+    UnknownType { path: String, ty: String },
+    /// A field's `format` is not a known format.
+    InvalidFormat { path: String, message: String },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Parse(msg) => write!(f, "invalid schema: {}", msg),
+            SchemaError::UnknownType { path, ty } => {
+                write!(f, "invalid schema: unknown type `{}` for `{}`", ty, path)
+            }
+            SchemaError::InvalidFormat { path, message } => {
+                write!(f, "invalid schema: bad format for `{}`: {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl DynamicSchema {
+    /// Load a schema from a JSON document.
+    pub fn from_json(json: &str) -> Result<Self, SchemaError> {
+        let file: SchemaFile =
+            serde_json::from_str(json).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        DynamicSchema::new(file.name, file.fields)
+    }
+
+    /// Load a schema from a YAML document.
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, SchemaError> {
+        let file: SchemaFile =
+            serde_yaml::from_str(yaml).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        DynamicSchema::new(file.name, file.fields)
+    }
+
+    /// Load a schema file, as YAML if it ends in `.yaml` or `.yml` and as
+    /// JSON otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| SchemaError::Parse(format!("cannot read {}: {}", path.display(), e)))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => DynamicSchema::from_yaml(&text),
+            #[cfg(not(feature = "yaml"))]
+            Some("yaml" | "yml") => Err(SchemaError::Parse(
+                "YAML schemas need the `yaml` feature".to_string(),
+            )),
+            _ => DynamicSchema::from_json(&text),
+        }
+    }
+
+    /// The schema equivalent to `T`'s derived descriptors, e.g. to hand a
+    /// Rust type's contract to tools that only read schema files.
+    ///
+    /// Recursive types are expanded down to their first repetition, as in
+    /// `ValidationPlan`; nested arrays are described as arrays of `any`.
+    pub fn for_type<T: ValidateFields>(name: impl Into<String>) -> Self {
+        let mut stack = Vec::new();
+        let fields = schema_fields(T::fields(), &mut stack);
+        let plan = ValidationPlan::for_type::<T>();
+        DynamicSchema { name: name.into(), fields, plan }
+    }
+
+    /// The schema as a JSON document `from_json` accepts.
+    pub fn to_json(&self) -> String {
+        let file = SchemaFile { name: self.name.clone(), fields: self.fields.clone() };
+        serde_json::to_string_pretty(&file).expect("schema serializes")
+    }
+
+    /// Build a schema from field definitions.
+    pub fn new(name: impl Into<String>, fields: Vec<SchemaField>) -> Result<Self, SchemaError> {
+        check_types(&fields, "")?;
+        let plan = ValidationPlan::from_schema(&fields);
+        Ok(DynamicSchema { name: name.into(), fields, plan })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn fields(&self) -> &[SchemaField] {
+        &self.fields
+    }
+
+    pub fn plan(&self) -> &ValidationPlan {
+        &self.plan
+    }
+
+    /// The changes from this snapshot to `newer`, classified as breaking or
+    /// compatible for clients of this one.
+    pub fn diff(&self, newer: &DynamicSchema) -> SchemaDiff {
+        SchemaDiff::between(self, newer)
+    }
+
+    /// Check required fields like `validate_json_with_report`.
+    pub fn validate_json_with_report(
+        &self,
+        json: &str,
+        opts: &ValidatorOptions,
+    ) -> Result<ValidationReport, ValidationError> {
+        let check = || {
+            let v = parse_with_limits(json, opts).map_err(|e| e.into_error(Some(&self.plan)))?;
+            self.plan.check_payload_limited(&v, opts.version_for(&v), opts.error_limit())
+        };
+        observe(opts, &self.name, check, |report| report)
+    }
+
+    /// Check an already-parsed payload as API `version` (`None` for the latest).
+    pub fn validate_value(
+        &self,
+        value: &Value,
+        version: Option<u32>,
+    ) -> Result<ValidationReport, ValidationError> {
+        self.plan.check_payload(value, version)
+    }
+
+    /// Validate a partial payload like `validate_partial_with_options`.
+    pub fn validate_partial(&self, json: &str, opts: &ValidatorOptions) -> Result<(), ValidationError> {
+        let check = || {
+            let v = parse_with_limits(json, opts).map_err(|e| e.into_error(Some(&self.plan)))?;
+            let findings: Vec<Finding> = self.plan.check_partial_limited(&v, opts.error_limit());
+            if findings.is_empty() {
+                Ok(ValidationReport::default())
+            } else {
+                Err(ValidationError::InvalidFields(findings))
+            }
+        };
+        observe(opts, &self.name, check, |report| report).map(|_| ())
+    }
+}
+
+impl SchemaField {
+    /// Whether a supplied value may be `null`.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable.unwrap_or(self.optional || self.required_if.is_some())
+    }
+
+    /// The field's `Format`, or `None` if it has none or it is invalid.
+    pub fn format(&self) -> Option<Format> {
+        self.format.as_deref().and_then(|f| Format::parse(f).ok())
+    }
+
+    /// The field's `TypeShape`, or `None` if `type`/`items` is unknown.
+    pub fn shape(&self) -> Option<TypeShape> {
+        let items = match self.items.as_deref() {
+            None => &TypeShape::Any,
+            Some(items) => scalar_shape(items)?,
+        };
+        match self.ty.as_str() {
+            "array" => Some(TypeShape::Array(items)),
+            "map" => Some(TypeShape::Map(items)),
+            other => scalar_shape(other).copied(),
+        }
+    }
+}
+
+/// The schema `type` and `items` names for a shape.
+fn shape_names(shape: TypeShape) -> (&'static str, Option<&'static str>) {
+    match shape {
+        TypeShape::Array(inner) => ("array", Some(scalar_name(*inner))),
+        TypeShape::Map(inner) => ("map", Some(scalar_name(*inner))),
+        other => (scalar_name(other), None),
+    }
+}
+
+fn scalar_name(shape: TypeShape) -> &'static str {
+    match shape {
+        TypeShape::String => "string",
+        TypeShape::Integer => "integer",
+        TypeShape::Float => "number",
+        TypeShape::Bool => "boolean",
+        TypeShape::Object => "object",
+        TypeShape::Array(_) | TypeShape::Map(_) | TypeShape::Any | TypeShape::Other => "any",
+    }
+}
+
+fn schema_fields(
+    fields: &'static [FieldDescriptor],
+    stack: &mut Vec<*const FieldDescriptor>,
+) -> Vec<SchemaField> {
+    stack.push(fields.as_ptr());
+    let out = fields
+        .iter()
+        .map(|field| {
+            let (ty, items) = shape_names(field.shape);
+            let nested = field.nested_fields();
+            let nested = if nested.is_empty() || stack.contains(&nested.as_ptr()) {
+                Vec::new()
+            } else {
+                schema_fields(nested, stack)
+            };
+            let required_if = field.required_if().next().map(str::to_string);
+            let optional = field.optional && required_if.is_none();
+            let nullable = field.nullable != (optional || required_if.is_some());
+            SchemaField {
+                name: field.name.to_string(),
+                aliases: field.aliases().map(str::to_string).collect(),
+                ty: ty.to_string(),
+                items: items.map(str::to_string),
+                optional,
+                nullable: nullable.then_some(field.nullable),
+                required_if,
+                format: field.format().map(|f| f.to_string()),
+                allowed: field.allowed().map(|values| values.iter().map(|v| v.to_string()).collect()),
+                since: field.since,
+                until: field.until,
+                deprecated: field.deprecated.map(str::to_string),
+                sensitive: field.sensitive,
+                message: field.message.map(str::to_string),
+                fields: nested,
+            }
+        })
+        .collect();
+    stack.pop();
+    out
+}
+
+fn scalar_shape(name: &str) -> Option<&'static TypeShape> {
+    match name {
+        "string" => Some(&TypeShape::String),
+        "integer" => Some(&TypeShape::Integer),
+        "number" => Some(&TypeShape::Float),
+        "boolean" => Some(&TypeShape::Bool),
+        "object" => Some(&TypeShape::Object),
+        "any" => Some(&TypeShape::Any),
+        _ => None,
+    }
+}
+
+fn check_types(fields: &[SchemaField], prefix: &str) -> Result<(), SchemaError> {
+    for (i, field) in fields.iter().enumerate() {
+        let path = if prefix.is_empty() {
+            field.name.clone()
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        if fields[..i].iter().any(|earlier| earlier.name == field.name) {
+            return Err(SchemaError::Parse(format!("`{}` is declared more than once", path)));
+        }
+        if field.shape().is_none() {
+            let ty = match field.items.as_deref() {
+                Some(items) if scalar_shape(items).is_none() => items.to_string(),
+                _ => field.ty.clone(),
+            };
+            return Err(SchemaError::UnknownType { path, ty });
+        }
+        if field.allowed.is_some()
+            && !matches!(
+                field.shape(),
+                Some(TypeShape::String | TypeShape::Array(TypeShape::String) | TypeShape::Map(TypeShape::String))
+            )
+        {
+            return Err(SchemaError::Parse(format!("`enum` on `{}` needs a string type or items", path)));
+        }
+        // Only objects, and arrays and maps of them, have their fields checked
+        if !field.fields.is_empty()
+            && !matches!(
+                field.shape(),
+                Some(
+                    TypeShape::Object
+                        | TypeShape::Array(TypeShape::Object | TypeShape::Any)
+                        | TypeShape::Map(TypeShape::Object | TypeShape::Any)
+                )
+            )
+        {
+            return Err(SchemaError::Parse(format!("`fields` on `{}` needs an object type or items", path)));
+        }
+        if let Some(format) = &field.format {
+            Format::parse(format).map_err(|message| SchemaError::InvalidFormat { path: path.clone(), message })?;
+        }
+        if let Some(trigger) = &field.required_if {
+            if *trigger == field.name {
+                return Err(SchemaError::Parse(format!("`{}` cannot be `required_if` itself", path)));
+            }
+            if !fields.iter().any(|sibling| sibling.name == *trigger) {
+                return Err(SchemaError::Parse(format!(
+                    "`required_if` on `{}` refers to unknown field `{}`",
+                    path, trigger
+                )));
+            }
+        }
+        if let (Some(since), Some(until)) = (field.since, field.until) {
+            if until <= since {
+                return Err(SchemaError::Parse(format!(
+                    "`until` on `{}` must be a later version than `since`",
+                    path
+                )));
+            }
+        }
+        check_types(&field.fields, &path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+use crate::descriptor::{version_in_range, FieldDescriptor, TypeShape};
+use crate::format::Format;
+use crate::schema::{DynamicSchema, SchemaField};
+use crate::validate::ValidateFields;
+
+/// Generates TypeScript interfaces, and optionally runtime validators, for
+/// request types, so clients share the server's contract instead of
+/// re-declaring it.
+///
+/// Interfaces use wire names and the latest API version's fields. A field
+/// that may be absent is optional (`?`), and also `| null` when it is an
+/// `Option`. Nested types become their own interfaces, named after the
+/// Rust type (or, for schemas, the parent and field name).
+///
+/// From a build script:
+///
+/// ```no_run
+/// # use field_validator::ValidateFields;
+/// # struct Request;
+/// # impl ValidateFields for Request { fn required_fields() -> &'static [&'static str] { &[] } }
+/// use field_validator::typescript::TypeScriptGenerator;
+///
+/// TypeScriptGenerator::new()
+///     .validators(true)
+///     .add_type::<Request>("Request")
+///     .write_if_changed("web/src/generated/requests.ts")
+///     .expect("write TypeScript bindings");
+/// ```
+///
+/// With `validators(true)`, each interface `X` also gets
+/// `validateX(value): ValidationIssue[]`, reporting missing fields, wrong
+/// types and values outside a field's allowed strings with the same paths
+/// and codes as the server, and a type guard `isX(value)`. Formats are
+/// documented on the field but only checked server-side.
+#[derive(Debug, Default)]
+pub struct TypeScriptGenerator {
+    validators: bool,
+    interfaces: Vec<Interface>,
+    /// Interface names already taken, by the descriptor table they describe
+    /// (null for schema objects, which are never shared).
+    names: HashMap<String, *const FieldDescriptor>,
+}
+
+#[derive(Debug)]
+struct Interface {
+    name: String,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: Ty,
+    required: bool,
+    nullable: bool,
+    required_if: Vec<String>,
+    docs: Vec<String>,
+}
+
+#[derive(Debug)]
+enum Ty {
+    String,
+    /// A string that must be one of these values.
+    Union(Vec<String>),
+    Number,
+    Integer,
+    Boolean,
+    /// An object with arbitrary keys and values.
+    Object,
+    Unknown,
+    Array(Box<Ty>),
+    Record(Box<Ty>),
+    /// Another generated interface.
+    Named(String),
+}
+
+impl TypeScriptGenerator {
+    pub fn new() -> Self {
+        TypeScriptGenerator::default()
+    }
+
+    /// Also emit `validateX` and `isX` functions.
+    pub fn validators(mut self, on: bool) -> Self {
+        self.validators = on;
+        self
+    }
+
+    /// Add `T` as interface `name`, with its nested types.
+    pub fn add_type<T: ValidateFields>(mut self, name: &str) -> Self {
+        if T::fields().is_empty() {
+            // Bare `required_fields()` impls: names only
+            let fields = T::required_fields()
+                .iter()
+                .map(|&field| Field {
+                    name: field.to_string(),
+                    ty: Ty::Unknown,
+                    required: true,
+                    nullable: false,
+                    required_if: Vec::new(),
+                    docs: Vec::new(),
+                })
+                .collect();
+            let name = self.claim(name, std::ptr::null());
+            self.interfaces.push(Interface { name, fields });
+        } else {
+            self.add_descriptors(name, T::fields());
+        }
+        self
+    }
+
+    /// Add a runtime schema as an interface named after it.
+    pub fn add_schema(mut self, schema: &DynamicSchema) -> Self {
+        self.add_schema_fields(schema.name(), schema.fields());
+        self
+    }
+
+    /// The generated module.
+    pub fn generate(&self) -> String {
+        let mut out = String::from("// Generated by field_validator. Do not edit.\n");
+        if self.validators && !self.interfaces.is_empty() {
+            out.push('\n');
+            out.push_str(HELPERS);
+        }
+        for interface in &self.interfaces {
+            out.push('\n');
+            interface.write(&mut out);
+            if self.validators {
+                out.push('\n');
+                interface.write_validator(&mut out);
+            }
+        }
+        out
+    }
+
+    /// Write the module to `path` unless it already has this content, so
+    /// build scripts do not trigger needless rebuilds. Returns whether the
+    /// file was written.
+    pub fn write_if_changed(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        let path = path.as_ref();
+        let code = self.generate();
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == code) {
+            return Ok(false);
+        }
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, code)?;
+        Ok(true)
+    }
+
+    /// `name`, or `name2`, `name3`, ... if another type already has it.
+    fn claim(&mut self, name: &str, table: *const FieldDescriptor) -> String {
+        let mut candidate = name.to_string();
+        let mut n = 2;
+        while self.names.contains_key(&candidate) {
+            candidate = format!("{}{}", name, n);
+            n += 1;
+        }
+        self.names.insert(candidate.clone(), table);
+        candidate
+    }
+
+    /// The interface for a descriptor table, generating it on first use.
+    fn add_descriptors(&mut self, name: &str, table: &'static [FieldDescriptor]) -> String {
+        if let Some((existing, _)) = self.names.iter().find(|(_, &t)| t == table.as_ptr()) {
+            return existing.clone();
+        }
+        // Claimed before the fields so recursive types refer to themselves
+        let name = self.claim(name, table.as_ptr());
+        let fields = table
+            .iter()
+            .filter(|f| f.exists_in(None))
+            .map(|field| {
+                let ty = match (field.shape, field.nested) {
+                    (TypeShape::Object, Some(nested)) => {
+                        Ty::Named(self.add_descriptors(nested.name, (nested.fields)()))
+                    }
+                    (TypeShape::Array(TypeShape::Object), Some(nested)) => Ty::Array(Box::new(Ty::Named(
+                        self.add_descriptors(nested.name, (nested.fields)()),
+                    ))),
+                    (TypeShape::Map(TypeShape::Object), Some(nested)) => Ty::Record(Box::new(Ty::Named(
+                        self.add_descriptors(nested.name, (nested.fields)()),
+                    ))),
+                    (shape, _) => Ty::from_shape(shape)
+                        .restrict(field.allowed().map(|a| a.iter().map(|v| v.to_string()).collect())),
+                };
+                let mut docs: Vec<String> = field
+                    .doc
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                docs.extend(field_notes(field.required_if(), field.format(), field.since, field.deprecated));
+                Field {
+                    name: field.name.to_string(),
+                    ty,
+                    required: !field.optional,
+                    nullable: field.nullable,
+                    required_if: field.required_if().map(str::to_string).collect(),
+                    docs,
+                }
+            })
+            .collect();
+        self.interfaces.push(Interface { name: name.clone(), fields });
+        name
+    }
+
+    fn add_schema_fields(&mut self, name: &str, fields: &[SchemaField]) -> String {
+        let name = self.claim(name, std::ptr::null());
+        let fields = fields
+            .iter()
+            .filter(|f| version_in_range(None, f.since, f.until))
+            .map(|field| {
+                let shape = field.shape().unwrap_or(TypeShape::Any);
+                let nested = || nested_name(&name, &field.name);
+                let ty = match shape {
+                    TypeShape::Object if !field.fields.is_empty() => {
+                        Ty::Named(self.add_schema_fields(&nested(), &field.fields))
+                    }
+                    TypeShape::Array(_) if !field.fields.is_empty() => {
+                        Ty::Array(Box::new(Ty::Named(self.add_schema_fields(&nested(), &field.fields))))
+                    }
+                    TypeShape::Map(_) if !field.fields.is_empty() => {
+                        Ty::Record(Box::new(Ty::Named(self.add_schema_fields(&nested(), &field.fields))))
+                    }
+                    shape => Ty::from_shape(shape).restrict(field.allowed.clone()),
+                };
+                Field {
+                    name: field.name.clone(),
+                    ty,
+                    required: !field.optional && field.required_if.is_none(),
+                    nullable: field.is_nullable(),
+                    required_if: field.required_if.iter().cloned().collect(),
+                    docs: field_notes(
+                        field.required_if.as_deref(),
+                        field.format(),
+                        field.since,
+                        field.deprecated.as_deref(),
+                    ),
+                }
+            })
+            .collect();
+        self.interfaces.push(Interface { name: name.clone(), fields });
+        name
+    }
+}
+
+/// Interface name for a schema's nested object: `Request` + `bike_info`
+/// -> `RequestBikeInfo`.
+fn nested_name(parent: &str, field: &str) -> String {
+    let mut out = parent.to_string();
+    for word in field.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+    out
+}
+
+/// JSDoc lines for the rules a field's type cannot express.
+fn field_notes<'a>(
+    required_if: impl IntoIterator<Item = &'a str>,
+    format: Option<Format>,
+    since: Option<u32>,
+    deprecated: Option<&str>,
+) -> Vec<String> {
+    let mut notes: Vec<String> = required_if
+        .into_iter()
+        .map(|trigger| format!("Required when `{}` is set.", trigger))
+        .collect();
+    if let Some(format) = format {
+        notes.push(format!("Must be {} (`{}`).", format.description(), format));
+    }
+    if let Some(since) = since {
+        notes.push(format!("Since API version {}.", since));
+    }
+    if let Some(note) = deprecated {
+        notes.push(format!("@deprecated {}", note));
+    }
+    notes
+}
+
+impl Ty {
+    fn from_shape(shape: TypeShape) -> Ty {
+        match shape {
+            TypeShape::String => Ty::String,
+            TypeShape::Integer => Ty::Integer,
+            TypeShape::Float => Ty::Number,
+            TypeShape::Bool => Ty::Boolean,
+            TypeShape::Object => Ty::Object,
+            TypeShape::Array(inner) => Ty::Array(Box::new(Ty::from_shape(*inner))),
+            TypeShape::Map(inner) => Ty::Record(Box::new(Ty::from_shape(*inner))),
+            TypeShape::Any | TypeShape::Other => Ty::Unknown,
+        }
+    }
+
+    /// Replace the string leaf with the union of `allowed`, if given.
+    fn restrict(self, allowed: Option<Vec<String>>) -> Ty {
+        match (self, allowed) {
+            (Ty::String, Some(allowed)) => Ty::Union(allowed),
+            (Ty::Array(inner), allowed @ Some(_)) => Ty::Array(Box::new(inner.restrict(allowed))),
+            (Ty::Record(inner), allowed @ Some(_)) => Ty::Record(Box::new(inner.restrict(allowed))),
+            (ty, _) => ty,
+        }
+    }
+
+    /// The TypeScript type expression.
+    fn ts(&self) -> String {
+        match self {
+            Ty::String => "string".to_string(),
+            Ty::Union(values) if values.is_empty() => "never".to_string(),
+            Ty::Union(values) => values.iter().map(|v| literal(v)).collect::<Vec<_>>().join(" | "),
+            Ty::Number | Ty::Integer => "number".to_string(),
+            Ty::Boolean => "boolean".to_string(),
+            Ty::Object => "Record<string, unknown>".to_string(),
+            Ty::Unknown => "unknown".to_string(),
+            Ty::Array(inner) => match **inner {
+                Ty::Array(_) | Ty::Record(_) | Ty::Object | Ty::Union(_) => format!("Array<{}>", inner.ts()),
+                _ => format!("{}[]", inner.ts()),
+            },
+            Ty::Record(inner) => format!("Record<string, {}>", inner.ts()),
+            Ty::Named(name) => name.clone(),
+        }
+    }
+
+    /// The `expected` of a `wrong_type` issue for this type.
+    fn expected(&self) -> &'static str {
+        match self {
+            Ty::String | Ty::Union(_) => "string",
+            Ty::Number => "number",
+            Ty::Integer => "integer",
+            Ty::Boolean => "boolean",
+            Ty::Object | Ty::Named(_) => "object",
+            Ty::Unknown => "any",
+            Ty::Array(_) => "array",
+            Ty::Record(_) => "map",
+        }
+    }
+
+    /// An expression listing the issues of value `v` at path `p`; `depth`
+    /// keeps the names of nested closures apart.
+    fn check(&self, v: &str, p: &str, depth: usize) -> String {
+        let simple = |test: String, expected: &str| {
+            format!("{} ? [] : wrongType({}, \"{}\")", test, p, expected)
+        };
+        match self {
+            Ty::String => simple(format!("typeof {} === \"string\"", v), "string"),
+            Ty::Union(values) => {
+                let values: Vec<String> = values.iter().map(|v| literal(v)).collect();
+                format!(
+                    "typeof {v} === \"string\" ? notAllowed({p}, {v}, [{values}]) : wrongType({p}, \"string\")",
+                    values = values.join(", "),
+                )
+            }
+            Ty::Number => simple(format!("typeof {} === \"number\"", v), "number"),
+            Ty::Integer => simple(format!("Number.isInteger({})", v), "integer"),
+            Ty::Boolean => simple(format!("typeof {} === \"boolean\"", v), "boolean"),
+            Ty::Object => simple(format!("isObject({})", v), "object"),
+            Ty::Unknown => "[]".to_string(),
+            Ty::Named(name) => format!("validate{}({}, {})", name, v, p),
+            Ty::Array(inner) => {
+                let (e, i) = (format!("e{}", depth), format!("i{}", depth));
+                let path = format!("joinPath({}, {})", p, i);
+                format!(
+                    "Array.isArray({v}) ? {v}.flatMap(({e}: unknown, {i}: number) => {e} === null ? [] : {inner}) : wrongType({p}, \"array\")",
+                    inner = inner.check(&e, &path, depth + 1),
+                )
+            }
+            Ty::Record(inner) => {
+                let (e, k) = (format!("e{}", depth), format!("k{}", depth));
+                let path = format!("joinPath({}, {})", p, k);
+                format!(
+                    "isObject({v}) ? Object.entries({v}).flatMap(([{k}, {e}]) => {e} === null ? [] : {inner}) : wrongType({p}, \"map\")",
+                    inner = inner.check(&e, &path, depth + 1),
+                )
+            }
+        }
+    }
+}
+
+impl Interface {
+    fn write(&self, out: &mut String) {
+        let _ = writeln!(out, "export interface {} {{", self.name);
+        for field in &self.fields {
+            match field.docs.as_slice() {
+                [] => {}
+                [line] => {
+                    let _ = writeln!(out, "  /** {} */", line);
+                }
+                lines => {
+                    out.push_str("  /**\n");
+                    for line in lines {
+                        let _ = writeln!(out, "   * {}", line);
+                    }
+                    out.push_str("   */\n");
+                }
+            }
+            let optional = if field.required { "" } else { "?" };
+            let null = if !field.required && field.nullable { " | null" } else { "" };
+            let _ = writeln!(out, "  {}{}: {}{};", property(&field.name), optional, field.ty.ts(), null);
+        }
+        out.push_str("}\n");
+    }
+
+    fn write_validator(&self, out: &mut String) {
+        let name = &self.name;
+        let _ = writeln!(out, "export function validate{}(value: unknown, path: string = \"\"): ValidationIssue[] {{", name);
+        out.push_str("  if (!isObject(value)) return wrongType(path, \"object\");\n");
+        out.push_str("  const issues: ValidationIssue[] = [];\n");
+        for field in &self.fields {
+            let key = serde_json::to_string(&field.name).expect("string serializes");
+            let _ = writeln!(out, "  {{\n    const v = value[{}];\n    const p = joinPath(path, {});", key, key);
+            let check = match field.ty {
+                Ty::Unknown => None,
+                ref ty => Some(ty.check("v", "p", 0)),
+            };
+            let mut absent = String::new();
+            if field.required {
+                absent.push_str("      issues.push({ path: p, code: \"missing_field\" });\n");
+            } else {
+                let mut otherwise = "";
+                if !field.required_if.is_empty() {
+                    let triggers: Vec<String> = field
+                        .required_if
+                        .iter()
+                        .map(|t| {
+                            let t = serde_json::to_string(t).expect("string serializes");
+                            format!("(value[{}] !== undefined && value[{}] !== null)", t, t)
+                        })
+                        .collect();
+                    let _ = writeln!(
+                        absent,
+                        "      if ({}) issues.push({{ path: p, code: \"missing_field\" }});",
+                        triggers.join(" || ")
+                    );
+                    otherwise = "else ";
+                }
+                if !field.nullable {
+                    let _ = writeln!(
+                        absent,
+                        "      {}if (v === null) issues.push(...wrongType(p, \"{}\"));",
+                        otherwise,
+                        field.ty.expected()
+                    );
+                }
+            }
+            match (absent.is_empty(), check) {
+                (true, None) => {}
+                (true, Some(check)) => {
+                    let _ = writeln!(out, "    if (v !== undefined && v !== null) {{\n      issues.push(...({}));\n    }}", check);
+                }
+                (false, None) => {
+                    let _ = write!(out, "    if (v === undefined || v === null) {{\n{}    }}\n", absent);
+                }
+                (false, Some(check)) => {
+                    let _ = write!(
+                        out,
+                        "    if (v === undefined || v === null) {{\n{}    }} else {{\n      issues.push(...({}));\n    }}\n",
+                        absent, check
+                    );
+                }
+            }
+            out.push_str("  }\n");
+        }
+        out.push_str("  return issues;\n}\n\n");
+        let _ = writeln!(
+            out,
+            "export function is{name}(value: unknown): value is {name} {{\n  return validate{name}(value).length === 0;\n}}",
+            name = name
+        );
+    }
+}
+
+/// A string literal.
+fn literal(value: &str) -> String {
+    serde_json::to_string(value).expect("string serializes")
+}
+
+/// A property name, quoted unless it is a plain identifier.
+fn property(name: &str) -> String {
+    let mut chars = name.chars();
+    let plain = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if plain {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).expect("string serializes")
+    }
+}
+
+const HELPERS: &str = r#"export interface ValidationIssue {
+  /** Dotted path of the field, e.g. `bike_info.model`. */
+  path: string;
+  code: "missing_field" | "wrong_type" | "invalid_value";
+  /** For `wrong_type`, what the value should have been. */
+  expected?: string;
+  /** For `invalid_value`, the values the field accepts. */
+  allowed?: string[];
+}
+
+function joinPath(base: string, key: string | number): string {
+  return base === "" ? String(key) : `${base}.${key}`;
+}
+
+function isObject(value: unknown): value is Record<string, unknown> {
+  return typeof value === "object" && value !== null && !Array.isArray(value);
+}
+
+function wrongType(path: string, expected: string): ValidationIssue[] {
+  return [{ path, code: "wrong_type", expected }];
+}
+
+function notAllowed(path: string, value: string, allowed: string[]): ValidationIssue[] {
+  return allowed.includes(value) ? [] : [{ path, code: "invalid_value", allowed }];
+}
+"#;
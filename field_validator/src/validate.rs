@@ -1,10 +1,56 @@
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use serde_json::{self, Value, json};
 use std::fmt;
+use std::io::{self, Read};
+
+use crate::descriptor::FieldDescriptor;
+use crate::finding::Finding;
+use crate::limits::{parse_slice_with_limits, parse_with_limits, LimitError, LimitKind, ParseError};
+use crate::messages::MessageCatalog;
+use crate::migrate::MigrationError;
+use crate::observe::observe;
+use crate::options::ValidatorOptions;
+use crate::plan::{cached_plan, ValidationPlan};
+use crate::redact;
+use crate::report::ValidationReport;
 
 pub trait ValidateFields {
     /// The list of required field names for this type.
     fn required_fields() -> &'static [&'static str];
+
+    /// Fields that are only required when another field is present and
+    /// non-null, as `(field, trigger)` pairs.
+    fn conditional_fields() -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Describe every field of this type: wire and Rust names, shape,
+    /// optionality, constraints, docs and nested types.
+    ///
+    /// Hand-written impls may leave this empty; the derive always fills it.
+    fn fields() -> &'static [FieldDescriptor] {
+        &[]
+    }
+
+    /// Describe every field as `Serialize` writes it, honouring serde's
+    /// serialize-side renames and skips; used to check outgoing values.
+    ///
+    /// Defaults to `fields()`; the derive always fills it.
+    fn serialized_fields() -> &'static [FieldDescriptor] {
+        Self::fields()
+    }
+
+    /// The compiled validation plan for this type, built on first use.
+    ///
+    /// The derive caches it in a per-type `OnceLock`; the default looks it
+    /// up in a shared cache keyed by type.
+    fn validation_plan() -> &'static ValidationPlan
+    where
+        Self: Sized,
+    {
+        cached_plan::<Self>(ValidationPlan::for_type::<Self>)
+    }
 }
 
 /// Error returned when some required fields are missing.
@@ -21,50 +67,367 @@ impl fmt::Display for MissingFieldsError {
 
 impl std::error::Error for MissingFieldsError {}
 
-/// Check that a JSON object has all of T::required_fields() present and non-null.
-pub fn validate_json_for_type<T: ValidateFields>(json: &str) -> Result<(), MissingFieldsError> {
-    let v: Value = serde_json::from_str(json)
-        .map_err(|_| MissingFieldsError { missing_fields: vec![] })?;
-    let obj = match v.as_object() {
-        Some(obj) => obj,
-        None => {
-            return Err(MissingFieldsError { 
-                missing_fields: T::required_fields().iter().map(|s| s.to_string()).collect()
-            })
+/// Error returned by the `*_with_options` entry points.
+#[derive(Debug)]
+pub enum ValidationError {
+    /// The payload is not valid JSON.
+    Syntax(serde_json::Error),
+    /// The payload exceeded one of the limits in `ValidatorOptions`.
+    Limit(LimitError),
+    /// Some required fields are absent or null.
+    MissingFields(MissingFieldsError),
+    /// Supplied fields have the wrong type or are not fields of the type.
+    InvalidFields(Vec<Finding>),
+    /// The payload's version has no migration path to the current one.
+    Migration(MigrationError),
+    /// An outgoing value could not be serialized to JSON.
+    Serialize(serde_json::Error),
+    /// The payload could not be read from its `io::Read` source.
+    Io(io::Error),
+}
+
+impl ValidationError {
+    /// Stable identifier used in responses and metrics.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::Syntax(_) => "invalid_json",
+            ValidationError::Limit(limit) => limit.kind.code(),
+            ValidationError::MissingFields(_) => "missing_fields",
+            ValidationError::InvalidFields(_) => "invalid_fields",
+            ValidationError::Migration(e) => e.code(),
+            ValidationError::Serialize(_) => "unserializable",
+            ValidationError::Io(_) => "unreadable",
         }
-    };
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::Syntax(e) => write!(f, "invalid JSON: {}", e),
+            ValidationError::Limit(e) => e.fmt(f),
+            ValidationError::MissingFields(e) => e.fmt(f),
+            ValidationError::InvalidFields(findings) => {
+                write!(f, "invalid fields: ")?;
+                for (i, finding) in findings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", finding)?;
+                }
+                Ok(())
+            }
+            ValidationError::Migration(e) => e.fmt(f),
+            ValidationError::Serialize(e) => write!(f, "cannot serialize: {}", e),
+            ValidationError::Io(e) => write!(f, "cannot read payload: {}", e),
+        }
+    }
+}
 
-    let mut missing = Vec::new();
-    for &field in T::required_fields() {
-        if !obj.contains_key(field) || obj[field].is_null() {
-            missing.push(field.to_string());
+impl std::error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ValidationError::Syntax(e) => Some(e),
+            ValidationError::Limit(e) => Some(e),
+            ValidationError::MissingFields(e) => Some(e),
+            ValidationError::InvalidFields(_) => None,
+            ValidationError::Migration(e) => Some(e),
+            ValidationError::Serialize(e) => Some(e),
+            ValidationError::Io(e) => Some(e),
         }
     }
-    if missing.is_empty() {
-        Ok(())
-    } else {
-        Err(MissingFieldsError { missing_fields: missing })
+}
+
+impl From<MissingFieldsError> for ValidationError {
+    fn from(e: MissingFieldsError) -> Self {
+        ValidationError::MissingFields(e)
+    }
+}
+
+impl From<ParseError> for ValidationError {
+    fn from(e: ParseError) -> Self {
+        e.into_error(None)
     }
 }
 
+/// Check that a JSON object has all of T::required_fields() present and non-null.
+///
+/// Fields of `nested` types are checked too and reported by dotted path,
+/// e.g. `bike_info.model`.
+///
+/// Parses without limits, as it always has, and reports every missing
+/// field. Only presence is checked: supplied values are not checked for
+/// their type, format or allowed values. Payloads that are not valid JSON
+/// are reported with an empty `missing_fields` list; use
+/// `validate_json_with_options` to enforce limits, check values too, or
+/// tell the cases apart.
+pub fn validate_json_for_type<T: ValidateFields>(json: &str) -> Result<(), MissingFieldsError> {
+    validate_json_for_type_with::<T>(json, &ValidatorOptions::unlimited())
+}
+
+/// Like `validate_json_for_type`, parsing under the limits in `opts` and
+/// honouring its API version, `mode` and `max_errors`, e.g. to stop at the
+/// first missing field with `ValidationMode::FailFast`. Payloads that are
+/// not valid JSON or exceed a limit are reported with an empty
+/// `missing_fields` list.
+pub fn validate_json_for_type_with<T: ValidateFields>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<(), MissingFieldsError> {
+    let v = parse_with_limits(json, opts).map_err(|_| MissingFieldsError { missing_fields: vec![] })?;
+    T::validation_plan().check_report_limited(&v, opts.version_for(&v), opts.error_limit()).map(|_| ())
+}
+
+/// Check required fields like `validate_json_for_type`, parsing under the
+/// limits in `opts`.
+pub fn validate_json_with_options<T: ValidateFields>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<(), ValidationError> {
+    validate_json_with_report::<T>(json, opts).map(|_| ())
+}
+
+/// Like `validate_json_with_options`, returning a report of the non-fatal
+/// findings, such as deprecated fields, for the handler to surface.
+pub fn validate_json_with_report<T: ValidateFields>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<ValidationReport, ValidationError> {
+    validate_slice_with_report::<T>(json.as_bytes(), opts)
+}
+
+/// `validate_json_with_report` for a raw body, such as the `Vec<u8>` of an
+/// HTTP request, without converting it to a `String` first. Invalid UTF-8
+/// is reported as invalid JSON.
+pub fn validate_slice_with_report<T: ValidateFields>(
+    json: &[u8],
+    opts: &ValidatorOptions,
+) -> Result<ValidationReport, ValidationError> {
+    let check = || {
+        let v = parse_slice_with_limits(json, opts).map_err(|e| e.into_error(Some(T::validation_plan())))?;
+        T::validation_plan().check_payload_limited(&v, opts.version_for(&v), opts.error_limit())
+    };
+    observe(opts, std::any::type_name::<T>(), check, |report| report)
+}
+
+/// Read a whole payload from `reader`, failing with a `Bytes` limit error
+/// as soon as it grows past `opts.max_bytes` rather than buffering it all.
+pub fn read_payload<R: Read>(reader: R, opts: &ValidatorOptions) -> Result<Vec<u8>, ValidationError> {
+    let mut body = Vec::new();
+    let cap = (opts.max_bytes as u64).saturating_add(1);
+    reader.take(cap).read_to_end(&mut body).map_err(ValidationError::Io)?;
+    if body.len() > opts.max_bytes {
+        return Err(ValidationError::Limit(LimitError {
+            kind: LimitKind::Bytes,
+            limit: opts.max_bytes,
+            path: String::new(),
+        }));
+    }
+    Ok(body)
+}
+
+/// Check required fields as they were in API `version` of `T`, honouring
+/// `#[field_validator(since, until)]`.
+pub fn validate_for_version<T: ValidateFields>(json: &str, version: u32) -> Result<(), ValidationError> {
+    let opts = ValidatorOptions { api_version: Some(version), ..ValidatorOptions::default() };
+    validate_json_with_options::<T>(json, &opts)
+}
+
+/// Validate a partial payload, as sent to PATCH/update endpoints.
+///
+/// Required-ness is not checked, but every supplied field must have the
+/// right type and format (and be `null` only if it is an `Option`), nested objects are
+/// checked the same way, and keys that are not fields of `T` are rejected.
+pub fn validate_partial<T: ValidateFields>(json: &str) -> Result<(), ValidationError> {
+    validate_partial_with_options::<T>(json, &ValidatorOptions::default())
+}
+
+/// Like `validate_partial`, parsing under the limits in `opts`.
+pub fn validate_partial_with_options<T: ValidateFields>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<(), ValidationError> {
+    let check = || {
+        let v = parse_with_limits(json, opts).map_err(|e| e.into_error(Some(T::validation_plan())))?;
+        let findings = T::validation_plan().check_partial_limited(&v, opts.error_limit());
+        if findings.is_empty() {
+            Ok(ValidationReport::default())
+        } else {
+            Err(ValidationError::InvalidFields(findings))
+        }
+    };
+    observe(opts, std::any::type_name::<T>(), check, |report| report).map(|_| ())
+}
+
+/// Implemented by the derive for structs marked `#[field_validator(patch)]`,
+/// linking `T` to its generated `TPatch` companion.
+pub trait Patchable: ValidateFields + Sized {
+    /// The companion struct: every field optional, absent meaning "unchanged".
+    type Patch: DeserializeOwned;
+
+    /// Copy every field supplied in `patch` onto `self`.
+    fn apply_patch(&mut self, patch: Self::Patch);
+}
+
+/// Implemented by `#[derive(StringEnum)]` for enums of unit variants that
+/// travel as strings, such as ride modes. Fields of such a type marked
+/// `#[field_validator(enum)]` only accept the variants' wire names.
+pub trait StringEnum: Sized + 'static {
+    /// The wire names of the variants, in declaration order, after serde
+    /// renames. Variants serde never deserializes are left out.
+    const VALUES: &'static [&'static str];
+
+    /// This variant's wire name.
+    fn as_str(&self) -> &'static str;
+
+    /// The variant with wire name `value`, if there is one.
+    fn parse(value: &str) -> Option<Self>;
+}
+
+/// Validate a partial payload for `T`, then deserialize it into `T::Patch`.
+pub fn validate_and_deserialize_patch<T: Patchable>(
+    json: &str,
+) -> Result<T::Patch, Box<dyn std::error::Error>> {
+    validate_partial::<T>(json)?;
+    let patch = serde_json::from_str(json)
+        .map_err(|e| redact::deserialize_error(e, T::validation_plan().has_sensitive_fields()))?;
+    Ok(patch)
+}
+
+/// Deserialize a present value (including `null`) as `Some`, so a patch
+/// field of type `Option<Option<T>>` can tell "absent" from "set to null".
+pub fn deserialize_present<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
 /// Validate and then deserialize in one shot.
+///
+/// Parses without limits, as it always has; use
+/// `validate_and_deserialize_with_options` to enforce them. Missing fields
+/// come back boxed as a `MissingFieldsError`, as they always have; every
+/// other validation failure is boxed as a `ValidationError`.
 pub fn validate_and_deserialize<T>(json: &str) 
     -> Result<T, Box<dyn std::error::Error>>
 where
     T: ValidateFields + DeserializeOwned
 {
-    // First check required fields:
-    validate_json_for_type::<T>(json)?;
-    // If ok, then deserialize to T:
-    let data = serde_json::from_str(json)?;
-    Ok(data)
+    validate_and_deserialize_with_options::<T>(json, &ValidatorOptions::unlimited())
+}
+
+/// Validate under the limits in `opts`, then deserialize.
+pub fn validate_and_deserialize_with_options<T>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: ValidateFields + DeserializeOwned,
+{
+    validate_and_deserialize_with_report::<T>(json, opts).map(|(data, _)| data)
+}
+
+/// Like `validate_and_deserialize_with_options`, also returning the report.
+pub fn validate_and_deserialize_with_report<T>(
+    json: &str,
+    opts: &ValidatorOptions,
+) -> Result<(T, ValidationReport), Box<dyn std::error::Error>>
+where
+    T: ValidateFields + DeserializeOwned,
+{
+    validate_and_deserialize_borrowed_with_report::<T>(json.as_bytes(), opts)
+}
+
+/// Validate and then deserialize a type that borrows from `json`, e.g. one
+/// with `&'de str` fields, so strings are not copied out of the buffer.
+///
+/// Borrowed fields fail to deserialize if the input escapes them; use
+/// `Cow<'de, str>` with `#[serde(borrow)]` for fields that may be escaped.
+pub fn validate_and_deserialize_borrowed<'de, T>(json: &'de [u8]) -> Result<T, Box<dyn std::error::Error>>
+where
+    T: ValidateFields + Deserialize<'de>,
+{
+    validate_and_deserialize_borrowed_with_report::<T>(json, &ValidatorOptions::default()).map(|(data, _)| data)
 }
+
+/// Like `validate_and_deserialize_borrowed`, under `opts` and also
+/// returning the report. Owned types work too, for raw `&[u8]` bodies.
+pub fn validate_and_deserialize_borrowed_with_report<'de, T>(
+    json: &'de [u8],
+    opts: &ValidatorOptions,
+) -> Result<(T, ValidationReport), Box<dyn std::error::Error>>
+where
+    T: ValidateFields + Deserialize<'de>,
+{
+    // First check limits and required fields:
+    let report = validate_slice_with_report::<T>(json, opts).map_err(boxed_error)?;
+    // If ok, then deserialize to T, keeping sensitive values out of errors:
+    let data = serde_json::from_slice(json)
+        .map_err(|e| redact::deserialize_error(e, T::validation_plan().has_sensitive_fields()))?;
+    Ok((data, report))
+}
+
+/// Read a payload with `read_payload`, then validate and deserialize it.
+pub fn validate_and_deserialize_reader<T, R>(
+    reader: R,
+    opts: &ValidatorOptions,
+) -> Result<(T, ValidationReport), Box<dyn std::error::Error>>
+where
+    T: ValidateFields + DeserializeOwned,
+    R: Read,
+{
+    let body = read_payload(reader, opts).map_err(boxed_error)?;
+    validate_and_deserialize_borrowed_with_report::<T>(&body, opts)
+}
+
+/// Box a validation failure for the `validate_and_deserialize*` entry
+/// points, unwrapping missing fields so callers can keep downcasting to
+/// `MissingFieldsError`.
+pub(crate) fn boxed_error(e: ValidationError) -> Box<dyn std::error::Error> {
+    match e {
+        ValidationError::MissingFields(missing) => Box::new(missing),
+        other => Box::new(other),
+    }
+}
+
 pub fn handle_json_request<T>(json: &str) -> Value
 where
     T: ValidateFields + DeserializeOwned,
 {
-    match validate_and_deserialize::<T>(json) {
-        Ok(user_data) => json!({
+    handle_json_request_with_options::<T>(json, &ValidatorOptions::unlimited())
+}
+
+/// Like `handle_json_request`, parsing under the limits in `opts` and
+/// rendering messages from `opts.messages` in `opts.locale`.
+pub fn handle_json_request_with_options<T>(json: &str, opts: &ValidatorOptions) -> Value
+where
+    T: ValidateFields + DeserializeOwned,
+{
+    let default_catalog;
+    let catalog = match &opts.messages {
+        Some(catalog) => &**catalog,
+        None => {
+            default_catalog = MessageCatalog::new();
+            &default_catalog
+        }
+    };
+    let locale = opts.locale.as_deref();
+    let plan = Some(T::validation_plan());
+    match validate_and_deserialize_with_report::<T>(json, opts) {
+        Ok((_, report)) if !report.warnings.is_empty() => json!({
+            "statusCode": 200,
+            "body": {
+                "message": "Success",
+                "warnings": report
+                    .warnings
+                    .iter()
+                    .map(|w| catalog.render_finding(locale, w, plan))
+                    .collect::<Vec<_>>()
+            }
+        }),
+        Ok(_) => json!({
             "statusCode": 200,
             "body": {
                 "message": "Success",
@@ -72,27 +435,72 @@ where
             }
         }),
 
-        Err(err) => {
-            // if it’s our MissingFieldsError, return a 400 + missingFields
-            if let Some(mf) = err.downcast_ref::<MissingFieldsError>() {
-                json!({
-                    "statusCode": 400,
-                    "body": {
-                        "error": "Validation Error",
-                        "message": format!("{}", mf),
-                        "missingFields": mf.missing_fields
-                    }
-                })
-            } else {
+        Err(err) => match err.downcast::<MissingFieldsError>() {
+            Ok(missing) => validation_response(&ValidationError::MissingFields(*missing), catalog, locale, plan),
+            Err(err) => match err.downcast_ref::<ValidationError>() {
+                Some(e) => validation_response(e, catalog, locale, plan),
                 // any other deserialization error
-                json!({
+                None => json!({
                     "statusCode": 400,
                     "body": {
                         "error": "Bad Request",
                         "message": format!("{}", err)
                     }
-                })
+                }),
+            },
+        },
+    }
+}
+
+/// The 4xx response for a payload that failed validation.
+fn validation_response(
+    err: &ValidationError,
+    catalog: &MessageCatalog,
+    locale: Option<&str>,
+    plan: Option<&ValidationPlan>,
+) -> Value {
+    match err {
+        // if it’s our MissingFieldsError, return a 400 + missingFields
+        ValidationError::MissingFields(mf) => json!({
+            "statusCode": 400,
+            "body": {
+                "error": "Validation Error",
+                "message": catalog.render_error(locale, err, plan),
+                "missingFields": mf.missing_fields,
+                "fieldErrors": catalog.field_errors(locale, err, plan)
             }
-        }
+        }),
+        // supplied values of the wrong type or format, or not allowed
+        ValidationError::InvalidFields(_) => json!({
+            "statusCode": 400,
+            "body": {
+                "error": "Validation Error",
+                "message": catalog.render_error(locale, err, plan),
+                "fieldErrors": catalog.field_errors(locale, err, plan)
+            }
+        }),
+        // payload too big or too deeply nested
+        ValidationError::Limit(limit) => json!({
+            "statusCode": if limit.kind == LimitKind::Bytes { 413 } else { 400 },
+            "body": {
+                "error": "Limit Exceeded",
+                "message": catalog.render_error(locale, err, plan),
+                "code": limit.kind.code()
+            }
+        }),
+        ValidationError::Syntax(_) => json!({
+            "statusCode": 400,
+            "body": {
+                "error": "Bad Request",
+                "message": catalog.render_error(locale, err, plan)
+            }
+        }),
+        _ => json!({
+            "statusCode": 400,
+            "body": {
+                "error": "Bad Request",
+                "message": format!("{}", err)
+            }
+        }),
     }
-}
\ No newline at end of file
+}
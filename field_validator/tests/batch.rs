@@ -0,0 +1,155 @@
+// field_validator/tests/batch.rs
+
+#![allow(dead_code)]
+
+use std::io::Cursor;
+
+use field_validator::{
+    validate_json_with_report, validate_ndjson, validate_ndjson_each, validate_ndjson_with, BatchStats, DynamicSchema,
+    ValidationError, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+#[derive(ValidateFields, Deserialize)]
+struct Leg {
+    model: String,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Ride {
+    mode: String,
+    #[field_validator(deprecated = "use mode")]
+    legacy_mode: Option<String>,
+    #[field_validator(nested)]
+    history: Option<Vec<Leg>>,
+}
+
+fn batch(ndjson: &str, opts: &ValidatorOptions) -> BatchStats {
+    validate_ndjson::<Ride, _>(Cursor::new(ndjson), opts).unwrap()
+}
+
+fn counts(pairs: &[(&str, usize)]) -> std::collections::BTreeMap<String, usize> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+#[test]
+fn stats_aggregate_codes_paths_and_warnings() {
+    let ndjson = [
+        r#"{"mode": "eco"}"#,
+        r#"{"mode": "eco", "legacy_mode": "eco"}"#,
+        "",
+        r#"{"history": [{"model": "a"}, {}]}"#,
+        r#"{"mode": "eco", "history": [{}, {"model": null}]}"#,
+        "   ",
+        "{not json",
+        r#"{"mode": "eco", "legacy_mode": "x"}"#,
+    ]
+    .join("\n");
+    let stats = batch(&ndjson, &ValidatorOptions::default());
+    assert_eq!(stats.total, 6);
+    assert_eq!(stats.valid, 3);
+    assert_eq!(stats.invalid, 3);
+    assert_eq!(stats.failed_lines, [4, 5, 7]);
+    assert_eq!(stats.by_code, counts(&[("invalid_json", 1), ("missing_fields", 2)]));
+    assert_eq!(stats.by_path, counts(&[("history.*.model", 3), ("mode", 1)]));
+    assert_eq!(stats.warnings_by_path, counts(&[("legacy_mode", 2)]));
+}
+
+#[test]
+fn empty_input_has_empty_stats() {
+    assert_eq!(batch("\n\n", &ValidatorOptions::default()), BatchStats::default());
+}
+
+#[test]
+fn crlf_line_endings_are_stripped() {
+    let stats = batch("{\"mode\": \"eco\"}\r\n{}\r\n", &ValidatorOptions::default());
+    assert_eq!((stats.valid, stats.invalid), (1, 1));
+    assert_eq!(stats.failed_lines, [2]);
+}
+
+#[test]
+fn long_lines_fail_without_being_buffered() {
+    let opts = ValidatorOptions { max_bytes: 32, ..ValidatorOptions::default() };
+    let long = format!(r#"{{"mode": "{}"}}"#, "x".repeat(10_000));
+    let ndjson = format!("{}\n{}\n{}", r#"{"mode": "eco"}"#, long, r#"{"mode": "eco"}"#);
+
+    let mut seen = Vec::new();
+    let stats = validate_ndjson_each(
+        Cursor::new(ndjson),
+        &opts,
+        field_validator::validate_json_with_report::<Ride>,
+        |line, json, result| seen.push((line, json.len(), result.as_ref().err().map(ValidationError::code))),
+    )
+    .unwrap();
+    assert_eq!(seen, [(1, 15, None), (2, 0, Some("max_bytes_exceeded")), (3, 15, None)]);
+    assert_eq!(stats.failed_lines, [2]);
+    assert_eq!(stats.by_code, counts(&[("max_bytes_exceeded", 1)]));
+}
+
+#[test]
+fn lines_at_the_byte_limit_are_validated() {
+    let line = r#"{"mode": "eco"}"#;
+    let opts = ValidatorOptions { max_bytes: line.len(), ..ValidatorOptions::default() };
+    let stats = batch(&format!("{}\n{}", line, line), &opts);
+    assert_eq!((stats.valid, stats.invalid), (2, 0));
+
+    let opts = ValidatorOptions { max_bytes: line.len() - 1, ..ValidatorOptions::default() };
+    let stats = batch(line, &opts);
+    assert_eq!(stats.by_code, counts(&[("max_bytes_exceeded", 1)]));
+}
+
+#[test]
+fn invalid_utf8_lines_are_invalid_records() {
+    let input = b"{\"mode\": \"eco\"}\n{\"mode\": \"\xff\"}\n{\"mode\": \"eco\"}\n".to_vec();
+    let mut seen = Vec::new();
+    let stats = validate_ndjson_each(
+        Cursor::new(input),
+        &ValidatorOptions::default(),
+        validate_json_with_report::<Ride>,
+        |line, text, result| seen.push((line, text.to_string(), result.as_ref().err().map(|e| e.to_string()))),
+    )
+    .unwrap();
+    assert_eq!((stats.total, stats.valid, stats.invalid), (3, 2, 1));
+    assert_eq!(stats.failed_lines, [2]);
+    assert_eq!(stats.by_code.get("invalid_json"), Some(&1));
+    assert_eq!(seen[1].1, "{\"mode\": \"\u{fffd}\"}");
+    assert_eq!(seen[1].2.as_deref(), Some("invalid JSON: invalid UTF-8 after byte 10"));
+}
+
+#[test]
+fn dynamic_schemas_validate_through_validate_ndjson_with() {
+    let schema =
+        DynamicSchema::from_json(r#"{"name": "Ride", "fields": [{"name": "mode", "type": "string"}]}"#).unwrap();
+    let stats = validate_ndjson_with(
+        Cursor::new("{\"mode\": \"eco\"}\n{\"mode\": 3}\n"),
+        &ValidatorOptions::default(),
+        |json, opts| schema.validate_json_with_report(json, opts),
+    )
+    .unwrap();
+    assert_eq!(stats.failed_lines, [2]);
+    assert_eq!(stats.by_path, counts(&[("mode", 1)]));
+}
+
+/// Enough records to span several chunks, so the parallel path has to put
+/// results back in line order.
+#[cfg(feature = "rayon")]
+#[test]
+fn parallel_validation_keeps_line_order() {
+    let ndjson: String = (1..=40_000)
+        .map(|i| if i % 7 == 0 { "{}\n".to_string() } else { format!("{{\"mode\": \"m{}\"}}\n", i) })
+        .collect();
+    let mut seen = Vec::new();
+    let stats = validate_ndjson_each(
+        Cursor::new(ndjson),
+        &ValidatorOptions::default(),
+        field_validator::validate_json_with_report::<Ride>,
+        |line, _, _| seen.push(line),
+    )
+    .unwrap();
+    assert_eq!(seen, (1..=40_000).collect::<Vec<_>>());
+    assert_eq!(stats.total, 40_000);
+    assert_eq!(stats.invalid, 40_000 / 7);
+    assert_eq!(stats.failed_lines, (1..=40_000).filter(|i| i % 7 == 0).collect::<Vec<_>>());
+    assert_eq!(stats.by_path, counts(&[("mode", 40_000 / 7)]));
+}
@@ -0,0 +1,173 @@
+// field_validator/tests/cli.rs
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+const SCHEMA: &str = r#"{
+  "name": "Request",
+  "fields": [
+    { "name": "bike_identifier", "type": "string" },
+    { "name": "current_mode", "type": "string", "optional": true }
+  ]
+}"#;
+
+/// A fresh directory holding the schema and the given input files.
+fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("field-validator-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("schema.json"), SCHEMA).unwrap();
+    for (file, text) in files {
+        fs::write(dir.join(file), text).unwrap();
+    }
+    dir
+}
+
+fn run(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_field-validator"))
+        .current_dir(dir)
+        .args(["--schema", "schema.json"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn valid_inputs_exit_zero() {
+    let dir = workspace(
+        "valid",
+        &[
+            ("one.json", r#"{"bike_identifier": "b1"}"#),
+            ("many.jsonl", "{\"bike_identifier\": \"b2\"}\n\n{\"bike_identifier\": \"b3\"}\n"),
+        ],
+    );
+    let output = run(&dir, &["one.json", "many.jsonl"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "one.json: ok\nmany.jsonl:1: ok\nmany.jsonl:3: ok\nchecked 3 payloads: 3 valid, 0 invalid\n"
+    );
+}
+
+#[test]
+fn invalid_lines_exit_one_and_are_reported_in_order() {
+    let lines =
+        [r#"{"bike_identifier": "b1"}"#, r#"{"current_mode": "eco"}"#, r#"{"bike_identifier": "b3"}"#, "{not json"];
+    let dir = workspace("invalid", &[("rides.ndjson", &lines.join("\n"))]);
+    let output = run(&dir, &["rides.ndjson"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    let reported: Vec<&str> = out.lines().collect();
+    assert_eq!(reported.len(), 5, "{}", out);
+    assert_eq!(reported[0], "rides.ndjson:1: ok");
+    assert!(reported[1].starts_with("rides.ndjson:2: invalid (missing_fields): "), "{}", reported[1]);
+    assert!(reported[1].contains("bike_identifier"), "{}", reported[1]);
+    assert_eq!(reported[2], "rides.ndjson:3: ok");
+    assert!(reported[3].starts_with("rides.ndjson:4: invalid ("), "{}", reported[3]);
+    assert_eq!(reported[4], "checked 4 payloads: 2 valid, 2 invalid");
+}
+
+#[test]
+fn directories_are_walked_in_order() {
+    let dir = workspace("dir", &[]);
+    fs::create_dir_all(dir.join("captured/nested")).unwrap();
+    fs::write(dir.join("captured/b.json"), r#"{"bike_identifier": "b"}"#).unwrap();
+    fs::write(dir.join("captured/nested/a.jsonl"), "{}\n").unwrap();
+    fs::write(dir.join("captured/notes.txt"), "ignored").unwrap();
+    let output = run(&dir, &["captured"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    let reported: Vec<&str> = out.lines().collect();
+    assert_eq!(reported.len(), 3, "{}", out);
+    assert_eq!(reported[0], format!("{}: ok", PathBuf::from("captured/b.json").display()));
+    assert!(reported[1].starts_with(&format!("{}:1: invalid", PathBuf::from("captured/nested/a.jsonl").display())));
+    assert_eq!(reported[2], "checked 2 payloads: 1 valid, 1 invalid");
+}
+
+#[test]
+fn json_format_prints_one_object_per_payload() {
+    let dir = workspace("json", &[("rides.jsonl", "{\"bike_identifier\": \"b1\"}\n{}\n")]);
+    let output = run(&dir, &["--format", "json", "rides.jsonl"]);
+    assert_eq!(output.status.code(), Some(1));
+    let reports: Vec<Value> = stdout(&output).lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0]["source"], "rides.jsonl");
+    assert_eq!(reports[0]["line"], 1);
+    assert_eq!(reports[0]["valid"], true);
+    assert_eq!(reports[1]["line"], 2);
+    assert_eq!(reports[1]["valid"], false);
+    assert_eq!(reports[1]["code"], "missing_fields");
+    assert_eq!(reports[1]["missingFields"], serde_json::json!(["bike_identifier"]));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.trim(), "checked 2 payloads: 1 valid, 1 invalid");
+}
+
+#[test]
+fn ndjson_flag_splits_any_file() {
+    let dir = workspace("flag", &[("rides.txt", "{\"bike_identifier\": \"b1\"}\n{\"bike_identifier\": \"b2\"}\n")]);
+    let output = run(&dir, &["--ndjson", "rides.txt"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).ends_with("checked 2 payloads: 2 valid, 0 invalid\n"));
+}
+
+#[test]
+fn oversized_and_non_utf8_inputs_are_invalid_records() {
+    let dir = workspace("bytes", &[]);
+    let big = format!(r#"{{"bike_identifier": "{}"}}"#, "x".repeat(1024 * 1024));
+    fs::write(dir.join("big.json"), big).unwrap();
+    fs::write(dir.join("latin1.json"), b"{\"bike_identifier\": \"b\xe9\"}".as_slice()).unwrap();
+    fs::write(dir.join("rides.jsonl"), b"{\"bike_identifier\": \"b\xe9\"}\n{\"bike_identifier\": \"b2\"}\n".as_slice())
+        .unwrap();
+    let output = run(&dir, &["big.json", "latin1.json", "rides.jsonl"]);
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    let reported: Vec<&str> = out.lines().collect();
+    assert_eq!(reported.len(), 5, "{}", out);
+    assert!(reported[0].starts_with("big.json: invalid (max_bytes_exceeded): "), "{}", reported[0]);
+    assert!(reported[1].starts_with("latin1.json: invalid (invalid_json): "), "{}", reported[1]);
+    assert!(reported[2].starts_with("rides.jsonl:1: invalid (invalid_json): "), "{}", reported[2]);
+    assert_eq!(reported[3], "rides.jsonl:2: ok");
+    assert_eq!(reported[4], "checked 4 payloads: 1 valid, 3 invalid");
+}
+
+#[test]
+fn usage_and_io_errors_exit_two() {
+    let dir = workspace("usage", &[]);
+    let output = run(&dir, &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("no input paths given"));
+
+    let output = run(&dir, &["missing.jsonl"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("cannot read missing.jsonl"));
+}
+
+#[test]
+fn fail_fast_and_max_errors_flags_cap_each_report() {
+    let dir = workspace("caps", &[("empty.json", "{}")]);
+    let schema = r#"{"name": "Request", "fields": [
+        {"name": "a", "type": "string"}, {"name": "b", "type": "string"}, {"name": "c", "type": "string"}
+    ]}"#;
+    fs::write(dir.join("schema.json"), schema).unwrap();
+    let reported = |args: &[&str]| {
+        let output = run(&dir, &[args, &["--format", "json", "empty.json"]].concat());
+        assert_eq!(output.status.code(), Some(1));
+        let report: Value = serde_json::from_str(stdout(&output).trim()).unwrap();
+        report["missingFields"].as_array().unwrap().len()
+    };
+    assert_eq!(reported(&[]), 3);
+    assert_eq!(reported(&["--fail-fast"]), 1);
+    assert_eq!(reported(&["--max-errors", "2"]), 2);
+
+    let output = run(&dir, &["--max-errors", "0", "empty.json"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("invalid error count `0`"));
+}
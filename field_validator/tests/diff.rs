@@ -0,0 +1,237 @@
+// field_validator/tests/diff.rs
+
+#![allow(dead_code)]
+
+use field_validator::{
+    assert_schema_compatible, ChangeKind, DynamicSchema, Format, Requirement, SchemaChange, SchemaDiff, TypeShape,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+fn schema(fields: &str) -> DynamicSchema {
+    DynamicSchema::from_json(&format!(r#"{{"name": "Request", "fields": [{}]}}"#, fields)).unwrap()
+}
+
+fn changes(old: &str, new: &str) -> Vec<SchemaChange> {
+    schema(old).diff(&schema(new)).changes
+}
+
+fn change(path: &str, kind: ChangeKind) -> SchemaChange {
+    SchemaChange { path: path.to_string(), kind }
+}
+
+const MODE: &str = r#"{"name": "mode", "type": "string"}"#;
+
+#[test]
+fn identical_schemas_have_no_changes() {
+    let diff = schema(MODE).diff(&schema(MODE));
+    assert!(diff.is_empty());
+    assert!(!diff.is_breaking());
+    assert_eq!(diff.to_string(), "");
+}
+
+#[test]
+fn added_fields_break_only_when_required() {
+    let required = changes(MODE, &format!(r#"{}, {{"name": "bike_identifier", "type": "string"}}"#, MODE));
+    assert_eq!(required, [change("bike_identifier", ChangeKind::Added { requirement: Requirement::Always })]);
+    assert!(required[0].is_breaking());
+    assert_eq!(required[0].to_string(), "breaking: `bike_identifier` was added (required)");
+
+    let conditional =
+        changes(MODE, &format!(r#"{}, {{"name": "changed_at", "type": "integer", "required_if": "mode"}}"#, MODE));
+    assert_eq!(conditional[0].kind, ChangeKind::Added { requirement: Requirement::If("mode".to_string()) });
+    assert!(conditional[0].is_breaking());
+
+    let optional = changes(MODE, &format!(r#"{}, {{"name": "note", "type": "string", "optional": true}}"#, MODE));
+    assert_eq!(optional[0].kind, ChangeKind::Added { requirement: Requirement::Never });
+    assert!(!optional[0].is_breaking());
+    assert_eq!(optional[0].to_string(), "compatible: `note` was added (optional)");
+}
+
+#[test]
+fn removed_fields_break_unless_already_retired() {
+    let old = format!(r#"{}, {{"name": "legacy", "type": "string", "optional": true}}"#, MODE);
+    assert_eq!(changes(&old, MODE), [change("legacy", ChangeKind::Removed)]);
+    let retired = format!(r#"{}, {{"name": "legacy", "type": "string", "until": 2}}"#, MODE);
+    assert!(changes(&retired, MODE).is_empty());
+}
+
+#[test]
+fn requirement_changes() {
+    let optional = r#"{"name": "mode", "type": "string", "optional": true}"#;
+    let conditional = r#"{"name": "mode", "type": "string", "required_if": "bike"}, {"name": "bike", "type": "string", "optional": true}"#;
+    let bike = r#", {"name": "bike", "type": "string", "optional": true}"#;
+
+    let tightened = changes(&format!("{}{}", optional, bike), &format!("{}{}", MODE, bike));
+    assert_eq!(
+        tightened,
+        [change("mode", ChangeKind::RequirementChanged { from: Requirement::Never, to: Requirement::Always })]
+    );
+    assert!(tightened[0].is_breaking());
+    assert_eq!(tightened[0].to_string(), "breaking: `mode` changed from optional to required");
+
+    let relaxed = changes(&format!("{}{}", MODE, bike), &format!("{}{}", optional, bike));
+    assert!(!relaxed[0].is_breaking());
+
+    // Required -> conditional relaxes; optional -> conditional tightens.
+    assert!(!changes(&format!("{}{}", MODE, bike), conditional)[0].is_breaking());
+    let conditional_from_optional = changes(&format!("{}{}", optional, bike), conditional);
+    assert!(conditional_from_optional[0].is_breaking());
+    assert_eq!(
+        conditional_from_optional[0].to_string(),
+        "breaking: `mode` changed from optional to required if `bike` is present"
+    );
+}
+
+#[test]
+fn explicit_nullability_changes() {
+    let nullable = r#"{"name": "mode", "type": "string", "nullable": true}"#;
+    let accepted = changes(MODE, nullable);
+    assert_eq!(accepted, [change("mode", ChangeKind::NullabilityChanged { nullable: true })]);
+    assert!(!accepted[0].is_breaking());
+    let rejected = changes(nullable, MODE);
+    assert!(rejected[0].is_breaking());
+    assert_eq!(rejected[0].to_string(), "breaking: `mode` no longer accepts null");
+}
+
+#[test]
+fn type_changes_are_compatible_only_when_widened() {
+    let shape = |ty: &str| format!(r#"{{"name": "count", "type": {}}}"#, ty);
+    let widened = changes(&shape(r#""integer""#), &shape(r#""number""#));
+    assert_eq!(widened, [change("count", ChangeKind::TypeChanged { from: TypeShape::Integer, to: TypeShape::Float })]);
+    assert!(!widened[0].is_breaking());
+    assert!(!changes(&shape(r#""string""#), &shape(r#""any""#))[0].is_breaking());
+    assert!(
+        !changes(&shape(r#""array", "items": "integer""#), &shape(r#""array", "items": "number""#))[0].is_breaking()
+    );
+    assert!(changes(&shape(r#""number""#), &shape(r#""integer""#))[0].is_breaking());
+    assert!(changes(&shape(r#""string""#), &shape(r#""boolean""#))[0].is_breaking());
+    assert!(changes(&shape(r#""array", "items": "string""#), &shape(r#""map", "items": "string""#))[0].is_breaking());
+}
+
+#[test]
+fn new_or_changed_formats_break() {
+    let formatted = |format: &str| format!(r#"{{"name": "id", "type": "string", "format": "{}"}}"#, format);
+    let id = r#"{"name": "id", "type": "string"}"#;
+    let added = changes(id, &formatted("uuid"));
+    assert_eq!(added, [change("id", ChangeKind::FormatChanged { from: None, to: Some(Format::Uuid) })]);
+    assert!(added[0].is_breaking());
+    assert_eq!(added[0].to_string(), "breaking: `id` now requires format uuid");
+    assert!(changes(&formatted("uuid"), &formatted("vin"))[0].is_breaking());
+    let dropped = changes(&formatted("uuid"), id);
+    assert!(!dropped[0].is_breaking());
+    assert_eq!(dropped[0].to_string(), "compatible: `id` no longer requires format uuid");
+}
+
+#[test]
+fn removing_allowed_values_breaks() {
+    let allowed = |values: &str| format!(r#"{{"name": "mode", "type": "string", "enum": [{}]}}"#, values);
+    let added = changes(&allowed(r#""glide""#), &allowed(r#""glide", "combat""#));
+    assert!(!added[0].is_breaking());
+    let removed = changes(&allowed(r#""glide", "combat""#), &allowed(r#""glide""#));
+    assert!(removed[0].is_breaking());
+    assert_eq!(removed[0].to_string(), "breaking: `mode` changed allowed values from glide, combat to glide");
+    assert!(changes(MODE, &allowed(r#""glide""#))[0].is_breaking());
+    assert!(!changes(&allowed(r#""glide""#), MODE)[0].is_breaking());
+}
+
+#[test]
+fn deprecations_and_retirements_are_compatible() {
+    let deprecated = r#"{"name": "mode", "type": "string", "deprecated": "use change_to_mode"}"#;
+    let diff = changes(MODE, deprecated);
+    assert_eq!(diff, [change("mode", ChangeKind::Deprecated { note: "use change_to_mode".to_string() })]);
+    assert!(!diff[0].is_breaking());
+    // Already deprecated: nothing new.
+    assert!(changes(deprecated, deprecated).is_empty());
+
+    let retired = changes(MODE, r#"{"name": "mode", "type": "string", "until": 3}"#);
+    assert_eq!(retired, [change("mode", ChangeKind::Retired { until: 3 })]);
+    assert_eq!(retired[0].to_string(), "compatible: `mode` was retired from API version 3");
+}
+
+#[test]
+fn nested_fields_are_compared_by_path() {
+    let object = |inner: &str| format!(r#"{{"name": "bike_info", "type": "object", "fields": [{}]}}"#, inner);
+    let list = |inner: &str| {
+        format!(r#"{{"name": "history", "type": "array", "items": "object", "optional": true, "fields": [{}]}}"#, inner)
+    };
+    let model = r#"{"name": "model", "type": "string"}"#;
+    let year = r#"{"name": "year", "type": "integer"}"#;
+    let diff = SchemaDiff::between(
+        &schema(&format!("{}, {}", object(model), list(model))),
+        &schema(&format!("{}, {}", object(&format!("{}, {}", model, year)), list(year))),
+    );
+    assert_eq!(
+        diff.changes,
+        [
+            change("bike_info.year", ChangeKind::Added { requirement: Requirement::Always }),
+            change("history.*.model", ChangeKind::Removed),
+            change("history.*.year", ChangeKind::Added { requirement: Requirement::Always }),
+        ]
+    );
+    assert_eq!(diff.breaking().count(), 3);
+    assert_eq!(diff.compatible().count(), 0);
+    // Dropping the nested field list only accepts more payloads.
+    assert!(changes(&object(model), r#"{"name": "bike_info", "type": "object"}"#).is_empty());
+}
+
+#[test]
+fn diff_display_lists_every_change() {
+    let diff = schema(MODE).diff(&schema(r#"{"name": "mode", "type": "string", "optional": true, "format": "uuid"}"#));
+    assert_eq!(
+        diff.to_string(),
+        "compatible: `mode` changed from required to optional\nbreaking: `mode` now requires format uuid\n"
+    );
+    assert!(diff.is_breaking());
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    mode: String,
+}
+
+mod v2 {
+    use super::*;
+
+    #[derive(ValidateFields, Deserialize)]
+    pub struct Request {
+        pub mode: String,
+        pub note: Option<String>,
+    }
+}
+
+mod v3 {
+    use super::*;
+
+    #[derive(ValidateFields, Deserialize)]
+    pub struct Request {
+        pub mode: String,
+        pub bike_identifier: String,
+    }
+}
+
+#[test]
+fn snapshots_are_written_and_guarded() {
+    let dir = std::env::temp_dir().join(format!("field-validator-diff-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let snapshot = dir.join("request.json");
+
+    // A missing snapshot is written.
+    assert!(assert_schema_compatible::<Request>(&snapshot).is_empty());
+    let written = std::fs::read_to_string(&snapshot).unwrap();
+    assert_eq!(written, DynamicSchema::for_type::<Request>("Request").to_json() + "\n");
+
+    // A compatible change passes and updates the snapshot.
+    let diff = assert_schema_compatible::<v2::Request>(&snapshot);
+    assert_eq!(diff.changes, [change("note", ChangeKind::Added { requirement: Requirement::Never })]);
+    assert!(std::fs::read_to_string(&snapshot).unwrap().contains("\"note\""));
+
+    // A breaking change fails and leaves the snapshot alone.
+    let before = std::fs::read_to_string(&snapshot).unwrap();
+    let panic = std::panic::catch_unwind(|| assert_schema_compatible::<v3::Request>(&snapshot)).unwrap_err();
+    let message = panic.downcast_ref::<String>().unwrap();
+    assert!(message.contains("breaking: `bike_identifier` was added (required)"), "{}", message);
+    assert!(message.contains("breaking: `note` was removed"), "{}", message);
+    assert_eq!(std::fs::read_to_string(&snapshot).unwrap(), before);
+}
@@ -0,0 +1,163 @@
+// field_validator/tests/dispatch.rs
+
+#![allow(dead_code)]
+
+use std::sync::LazyLock;
+
+use field_validator::dispatch::{DispatchError, Dispatcher};
+use field_validator::migrate::{self, MigrationRegistry};
+use field_validator::{LimitKind, ValidationError, ValidatorOptions};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(ValidateFields, Deserialize)]
+struct ChangeMode {
+    bike_identifier: String,
+    change_to_mode: String,
+    #[field_validator(deprecated = "use change_to_mode")]
+    mode: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct GetMode {
+    bike_identifier: String,
+    count: Option<u32>,
+}
+
+fn dispatcher() -> Dispatcher<String> {
+    Dispatcher::new("action")
+        .route::<ChangeMode, _>("change_mode", |req| {
+            format!("change {} to {}", req.bike_identifier, req.change_to_mode)
+        })
+        .route::<GetMode, _>("get_mode", |req| format!("get {}", req.bike_identifier))
+}
+
+fn dispatch(dispatcher: &Dispatcher<String>, json: &str) -> Result<String, DispatchError> {
+    dispatcher.dispatch(json, &ValidatorOptions::default()).map(|(out, _)| out)
+}
+
+#[test]
+fn payloads_reach_the_handler_for_their_action() {
+    let dispatcher = dispatcher();
+    assert_eq!(dispatcher.actions(), ["change_mode", "get_mode"]);
+    assert_eq!(
+        dispatch(&dispatcher, r#"{"action": "change_mode", "bike_identifier": "B1", "change_to_mode": "eco"}"#)
+            .unwrap(),
+        "change B1 to eco"
+    );
+    assert_eq!(dispatch(&dispatcher, r#"{"action": "get_mode", "bike_identifier": "B2"}"#).unwrap(), "get B2");
+}
+
+#[test]
+fn reports_carry_the_routed_types_warnings() {
+    let json = r#"{"action": "change_mode", "bike_identifier": "B1", "change_to_mode": "eco", "mode": "eco"}"#;
+    let (_, report) = dispatcher().dispatch(json, &ValidatorOptions::default()).unwrap();
+    let paths: Vec<&str> = report.warnings.iter().map(|w| w.path.as_str()).collect();
+    assert_eq!(paths, ["mode"]);
+}
+
+#[test]
+fn missing_action_without_fallback() {
+    for json in [r#"{"bike_identifier": "B1"}"#, r#"{"action": 3, "bike_identifier": "B1"}"#, "[]"] {
+        let err = dispatch(&dispatcher(), json).unwrap_err();
+        assert_eq!(err.code(), "missing_action");
+        assert_eq!(err.to_string(), "missing `action`; expected one of: change_mode, get_mode");
+        assert!(
+            matches!(err, DispatchError::MissingAction { ref field, ref valid } if field == "action" && valid.len() == 2)
+        );
+    }
+}
+
+#[test]
+fn fallback_routes_payloads_without_the_action() {
+    let dispatcher = dispatcher().fallback("get_mode");
+    assert_eq!(dispatch(&dispatcher, r#"{"bike_identifier": "B1"}"#).unwrap(), "get B1");
+    // An explicit action still wins.
+    let json = r#"{"action": "change_mode", "bike_identifier": "B1", "change_to_mode": "eco"}"#;
+    assert_eq!(dispatch(&dispatcher, json).unwrap(), "change B1 to eco");
+}
+
+#[test]
+fn unknown_actions_list_the_valid_ones() {
+    let err = dispatch(&dispatcher(), r#"{"action": "reboot", "bike_identifier": "B1"}"#).unwrap_err();
+    assert_eq!(err.code(), "unknown_action");
+    assert_eq!(err.to_string(), "unknown action `reboot`; expected one of: change_mode, get_mode");
+    assert!(matches!(err, DispatchError::UnknownAction { ref action, .. } if action == "reboot"));
+
+    // A fallback naming no route is unknown too.
+    let err = dispatch(&dispatcher().fallback("legacy"), "{}").unwrap_err();
+    assert!(matches!(err, DispatchError::UnknownAction { ref action, .. } if action == "legacy"));
+}
+
+#[test]
+fn payloads_are_validated_against_their_actions_type() {
+    let err = dispatch(&dispatcher(), r#"{"action": "change_mode", "bike_identifier": "B1"}"#).unwrap_err();
+    assert_eq!(err.code(), "missing_fields");
+    match err {
+        DispatchError::Invalid { action, error: ValidationError::MissingFields(missing) } => {
+            assert_eq!(action, "change_mode");
+            assert_eq!(missing.missing_fields, ["change_to_mode"]);
+        }
+        other => panic!("expected an invalid payload, got {:?}", other),
+    }
+    // The same payload is fine for a type that doesn't need the field.
+    assert!(dispatch(&dispatcher(), r#"{"action": "get_mode", "bike_identifier": "B1"}"#).is_ok());
+}
+
+#[test]
+fn unparseable_payloads_fail_before_routing() {
+    let err = dispatch(&dispatcher(), r#"{"action": "get_mode""#).unwrap_err();
+    assert!(matches!(err, DispatchError::Parse(_)));
+    assert_eq!(err.code(), "invalid_json");
+
+    let opts = ValidatorOptions { max_bytes: 16, ..ValidatorOptions::default() };
+    let err = dispatcher().dispatch(r#"{"action": "get_mode", "bike_identifier": "B1"}"#, &opts).unwrap_err();
+    assert!(matches!(err, DispatchError::Parse(ValidationError::Limit(ref limit)) if limit.kind == LimitKind::Bytes));
+}
+
+#[test]
+fn deserialize_failures_name_the_action() {
+    let json = r#"{"action": "get_mode", "bike_identifier": "B1", "count": -1}"#;
+    let err = dispatch(&dispatcher(), json).unwrap_err();
+    assert_eq!(err.code(), "invalid_payload");
+    assert!(matches!(err, DispatchError::Deserialize { ref action, .. } if action == "get_mode"));
+    assert!(std::error::Error::source(&err).is_some());
+}
+
+#[test]
+fn slices_and_values_dispatch_like_strings() {
+    let dispatcher = dispatcher();
+    let opts = ValidatorOptions::default();
+    let (out, _) = dispatcher.dispatch_slice(br#"{"action": "get_mode", "bike_identifier": "B1"}"#, &opts).unwrap();
+    assert_eq!(out, "get B1");
+    let (out, _) = dispatcher.dispatch_value(json!({"action": "get_mode", "bike_identifier": "B2"}), &opts).unwrap();
+    assert_eq!(out, "get B2");
+}
+
+#[test]
+fn later_routes_replace_earlier_ones() {
+    let dispatcher = dispatcher().route::<GetMode, _>("get_mode", |req| format!("again {}", req.bike_identifier));
+    assert_eq!(dispatcher.actions(), ["change_mode", "get_mode"]);
+    assert_eq!(dispatch(&dispatcher, r#"{"action": "get_mode", "bike_identifier": "B1"}"#).unwrap(), "again B1");
+}
+
+static MIGRATIONS: LazyLock<MigrationRegistry> = LazyLock::new(|| {
+    MigrationRegistry::new(2).step(1, "rename mode", |v| migrate::rename_field(v, "mode", "change_to_mode"))
+});
+
+#[test]
+fn migrated_routes_upgrade_legacy_payloads() {
+    let dispatcher = Dispatcher::new("action").route_migrated::<ChangeMode, _>("change_mode", &MIGRATIONS, |req| {
+        format!("change {} to {}", req.bike_identifier, req.change_to_mode)
+    });
+    let opts = ValidatorOptions { version_field: Some("version".to_string()), ..ValidatorOptions::default() };
+    let json = r#"{"action": "change_mode", "version": 1, "bike_identifier": "B1", "mode": "eco"}"#;
+    let (out, report) = dispatcher.dispatch(json, &opts).unwrap();
+    assert_eq!(out, "change B1 to eco");
+    assert_eq!(report.migrations.len(), 1);
+
+    let err = dispatcher.dispatch(r#"{"action": "change_mode", "version": 0}"#, &opts).unwrap_err();
+    assert_eq!(err.code(), "unsupported_version");
+    assert!(matches!(err, DispatchError::Invalid { .. }));
+}
@@ -0,0 +1,161 @@
+// field_validator/tests/fail_fast.rs
+
+#![allow(dead_code)]
+
+use field_validator::migrate::{self, MigrationRegistry};
+use field_validator::{
+    validate_json_for_type, validate_json_for_type_with, validate_json_with_options, validate_json_with_report,
+    validate_partial_with_options, Dispatcher, DynamicSchema, ValidateFields, ValidationError, ValidationMode,
+    ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(ValidateFields, Deserialize)]
+struct Leg {
+    model: String,
+    #[field_validator(format = "uuid")]
+    ride_id: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    change_to_mode: String,
+    #[field_validator(deprecated = "use change_to_mode")]
+    mode: Option<String>,
+    #[field_validator(nested)]
+    history: Option<Vec<Leg>>,
+}
+
+const BROKEN: &str = r#"{"mode": "eco", "history": [{}, {}, {"model": "m"}]}"#;
+const BAD_FORMATS: &str = r#"{
+    "bike_identifier": "B1",
+    "change_to_mode": "eco",
+    "history": [{"model": "a", "ride_id": "x"}, {"model": "b", "ride_id": "y"}, {"model": "c", "ride_id": "z"}]
+}"#;
+
+fn fail_fast() -> ValidatorOptions {
+    ValidatorOptions { mode: ValidationMode::FailFast, ..ValidatorOptions::default() }
+}
+
+fn capped(max_errors: usize) -> ValidatorOptions {
+    ValidatorOptions { max_errors, ..ValidatorOptions::default() }
+}
+
+fn missing(json: &str, opts: &ValidatorOptions) -> Vec<String> {
+    match validate_json_with_options::<Request>(json, opts) {
+        Err(ValidationError::MissingFields(missing)) => missing.missing_fields,
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+fn invalid(result: Result<(), ValidationError>) -> Vec<String> {
+    match result {
+        Err(ValidationError::InvalidFields(findings)) => findings.into_iter().map(|f| f.path).collect(),
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn error_limit_follows_the_mode() {
+    assert_eq!(ValidatorOptions::default().error_limit(), usize::MAX);
+    assert_eq!(fail_fast().error_limit(), 1);
+    assert_eq!(ValidatorOptions { max_errors: 5, ..fail_fast() }.error_limit(), 1);
+    assert_eq!(capped(5).error_limit(), 5);
+    assert_eq!(capped(0).error_limit(), 1);
+}
+
+#[test]
+fn every_missing_field_is_reported_by_default() {
+    assert_eq!(
+        missing(BROKEN, &ValidatorOptions::default()),
+        ["bike_identifier", "change_to_mode", "history.0.model", "history.1.model"]
+    );
+}
+
+#[test]
+fn fail_fast_stops_at_the_first_problem() {
+    assert_eq!(missing(BROKEN, &fail_fast()), ["bike_identifier"]);
+    assert_eq!(invalid(validate_json_with_options::<Request>(BAD_FORMATS, &fail_fast())), ["history.0.ride_id"]);
+}
+
+#[test]
+fn max_errors_caps_the_report() {
+    assert_eq!(missing(BROKEN, &capped(3)), ["bike_identifier", "change_to_mode", "history.0.model"]);
+    assert_eq!(missing(BROKEN, &capped(10)).len(), 4);
+    assert_eq!(
+        invalid(validate_json_with_options::<Request>(BAD_FORMATS, &capped(2))),
+        ["history.0.ride_id", "history.1.ride_id"]
+    );
+}
+
+#[test]
+fn validate_json_for_type_with_honours_the_mode() {
+    let missing =
+        |opts: &ValidatorOptions| validate_json_for_type_with::<Request>(BROKEN, opts).unwrap_err().missing_fields;
+    assert_eq!(missing(&ValidatorOptions::default()).len(), 4);
+    assert_eq!(missing(&fail_fast()), ["bike_identifier"]);
+    assert_eq!(missing(&capped(3)), ["bike_identifier", "change_to_mode", "history.0.model"]);
+    assert_eq!(validate_json_for_type::<Request>(BROKEN).unwrap_err().missing_fields.len(), 4);
+    // Still presence only: bad formats pass.
+    validate_json_for_type_with::<Request>(BAD_FORMATS, &fail_fast()).unwrap();
+    let opts = ValidatorOptions { max_bytes: 8, ..fail_fast() };
+    assert!(validate_json_for_type_with::<Request>(BAD_FORMATS, &opts).unwrap_err().missing_fields.is_empty());
+}
+
+#[test]
+fn warnings_are_not_counted() {
+    let json = r#"{"bike_identifier": "B1", "change_to_mode": "eco", "mode": "eco"}"#;
+    let report = validate_json_with_report::<Request>(json, &fail_fast()).unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    // The warning does not use up the single error either.
+    assert_eq!(missing(r#"{"mode": "eco", "change_to_mode": "eco"}"#, &fail_fast()), ["bike_identifier"]);
+}
+
+#[test]
+fn partial_validation_is_capped() {
+    let json = r#"{"bike_identifier": 1, "change_to_mode": 2, "extra": 3}"#;
+    assert_eq!(invalid(validate_partial_with_options::<Request>(json, &ValidatorOptions::default())).len(), 3);
+    assert_eq!(invalid(validate_partial_with_options::<Request>(json, &fail_fast())), ["bike_identifier"]);
+    assert_eq!(invalid(validate_partial_with_options::<Request>(json, &capped(2))).len(), 2);
+    assert_eq!(Request::validation_plan().check_partial_limited(&serde_json::from_str(json).unwrap(), 2).len(), 2);
+}
+
+#[test]
+fn plans_take_the_cap_directly() {
+    let value: serde_json::Value = serde_json::from_str(BROKEN).unwrap();
+    match Request::validation_plan().check_payload_limited(&value, None, 2) {
+        Err(ValidationError::MissingFields(missing)) => assert_eq!(missing.missing_fields.len(), 2),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn schemas_dispatchers_and_migrations_honour_the_cap() {
+    let schema = DynamicSchema::for_type::<Request>("Request");
+    match schema.validate_json_with_report(BROKEN, &fail_fast()) {
+        Err(ValidationError::MissingFields(missing)) => assert_eq!(missing.missing_fields, ["bike_identifier"]),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+    assert_eq!(invalid(schema.validate_partial(r#"{"bike_identifier": 1, "extra": 2}"#, &fail_fast())).len(), 1);
+
+    let dispatcher = Dispatcher::new("action").route::<Request, _>("change_mode", |_| ());
+    let mut payload: serde_json::Value = serde_json::from_str(BROKEN).unwrap();
+    payload["action"] = json!("change_mode");
+    match dispatcher.dispatch_value(payload, &capped(2)) {
+        Err(field_validator::DispatchError::Invalid { error: ValidationError::MissingFields(missing), .. }) => {
+            assert_eq!(missing.missing_fields, ["bike_identifier", "change_to_mode"])
+        }
+        other => panic!("expected an invalid payload, got {:?}", other.map(|_| ())),
+    }
+
+    let registry =
+        MigrationRegistry::new(2).step(1, "rename mode", |v| migrate::rename_field(v, "mode", "change_to_mode"));
+    let opts = ValidatorOptions { version_field: Some("version".to_string()), ..fail_fast() };
+    match registry.validate_json::<Request>(r#"{"version": 1, "history": [{}]}"#, &opts) {
+        Err(ValidationError::MissingFields(missing)) => assert_eq!(missing.missing_fields, ["bike_identifier"]),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
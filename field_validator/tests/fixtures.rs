@@ -0,0 +1,112 @@
+// field_validator/tests/fixtures.rs
+
+#![allow(dead_code)]
+
+use field_validator::{example_json, invalid_variants, validate_and_deserialize, Mutation, ValidateFields};
+use field_validator_derive::{StringEnum, ValidateFields};
+use serde::Deserialize;
+
+#[derive(StringEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Glide,
+    Combat,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeInfo {
+    #[field_validator(format = "uuid")]
+    id: String,
+    model: String,
+    wheels: u8,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Ride {
+    #[field_validator(enum)]
+    mode: Mode,
+    #[field_validator(nested)]
+    bike_info: BikeInfo,
+    #[field_validator(format = "url")]
+    contact: Option<String>,
+    #[field_validator(required_if = "contact")]
+    contact_name: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Node {
+    name: String,
+    #[field_validator(nested)]
+    next: Option<Box<Node>>,
+    #[field_validator(nested)]
+    children: Vec<Node>,
+}
+
+/// Whether `T` refuses `payload`, either in validation or when deserializing.
+fn rejected<T: ValidateFields + serde::de::DeserializeOwned>(payload: &serde_json::Value) -> bool {
+    validate_and_deserialize::<T>(&payload.to_string()).is_err()
+}
+
+#[test]
+fn example_passes_validation() {
+    let example = example_json::<Ride>();
+    assert!(!rejected::<Ride>(&example), "{}", example);
+}
+
+#[test]
+fn every_invalid_variant_fails() {
+    let variants = invalid_variants::<Ride>();
+    for mutation in [
+        Mutation::Removed,
+        Mutation::Nulled,
+        Mutation::WrongType,
+        Mutation::TriggerWithoutField,
+        Mutation::BadFormat,
+        Mutation::NotAllowed,
+    ] {
+        assert!(variants.iter().any(|v| v.mutation == mutation), "no {:?} variant", mutation);
+    }
+    assert!(variants.iter().any(|v| v.path == "bike_info.id" && v.mutation == Mutation::BadFormat));
+    for variant in &variants {
+        assert!(
+            rejected::<Ride>(&variant.payload),
+            "{} ({:?}) was accepted: {}",
+            variant.path,
+            variant.mutation,
+            variant.payload
+        );
+    }
+}
+
+#[test]
+fn recursive_types_terminate() {
+    let example = example_json::<Node>();
+    assert_eq!(example, serde_json::json!({ "name": "example", "children": [] }));
+    let variants = invalid_variants::<Node>();
+    assert!(variants.iter().all(|v| !v.path.contains('.')));
+    assert!(!rejected::<Node>(&example));
+    for variant in &variants {
+        assert!(rejected::<Node>(&variant.payload), "{} ({:?}) was accepted", variant.path, variant.mutation);
+    }
+}
+
+#[cfg(feature = "proptest")]
+mod strategies {
+    use field_validator::fixtures::strategy::{invalid_payload, valid_payload};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn valid_payloads_pass(payload in valid_payload::<Node>()) {
+            prop_assert!(!rejected::<Node>(&payload), "{}", payload);
+        }
+
+        #[test]
+        fn invalid_payloads_fail(fixture in invalid_payload::<Ride>()) {
+            prop_assert!(rejected::<Ride>(&fixture.payload));
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// field_validator/tests/limits.rs
+
+#![allow(dead_code)]
+
+use field_validator::{
+    handle_json_request, validate_and_deserialize, validate_and_deserialize_with_options, validate_json_for_type,
+    LimitKind, MissingFieldsError, ValidationError, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(ValidateFields, Deserialize, Debug)]
+struct Ride {
+    mode: String,
+    stops: Option<Vec<Value>>,
+    meta: Option<Value>,
+}
+
+fn limit_error(json: &str, opts: &ValidatorOptions) -> (LimitKind, usize, String) {
+    let err = validate_and_deserialize_with_options::<Ride>(json, opts).unwrap_err();
+    match err.downcast_ref::<ValidationError>() {
+        Some(ValidationError::Limit(limit)) => (limit.kind, limit.limit, limit.path.clone()),
+        other => panic!("expected a limit error, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_fields_still_downcast_to_missing_fields_error() {
+    let err = validate_and_deserialize::<Ride>(r#"{"stops": []}"#).unwrap_err();
+    let missing = err.downcast_ref::<MissingFieldsError>().expect("a MissingFieldsError");
+    assert_eq!(missing.missing_fields, ["mode"]);
+
+    let response = handle_json_request::<Ride>(r#"{"mode": null}"#);
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(response["body"]["missingFields"], serde_json::json!(["mode"]));
+}
+
+#[test]
+fn other_failures_downcast_to_validation_error() {
+    let err = validate_and_deserialize::<Ride>("{").unwrap_err();
+    assert!(matches!(err.downcast_ref::<ValidationError>(), Some(ValidationError::Syntax(_))));
+}
+
+#[test]
+fn legacy_entry_points_parse_without_limits() {
+    let long = format!(r#"{{"mode": "{}"}}"#, "x".repeat(70 * 1024));
+    let many = format!(r#"{{"mode": "glide", "stops": [{}]}}"#, vec!["1"; 10_001].join(","));
+    for json in [&long, &many] {
+        validate_json_for_type::<Ride>(json).unwrap();
+        validate_and_deserialize::<Ride>(json).unwrap();
+        assert_eq!(handle_json_request::<Ride>(json)["statusCode"], 200);
+        let err = validate_and_deserialize_with_options::<Ride>(json, &ValidatorOptions::default()).unwrap_err();
+        assert!(matches!(err.downcast_ref::<ValidationError>(), Some(ValidationError::Limit(_))));
+    }
+}
+
+#[test]
+fn max_bytes() {
+    let opts = ValidatorOptions { max_bytes: 16, ..ValidatorOptions::default() };
+    let json = r#"{"mode": "glide", "meta": "padding"}"#;
+    assert_eq!(limit_error(json, &opts), (LimitKind::Bytes, 16, String::new()));
+    let response = field_validator::handle_json_request_with_options::<Ride>(json, &opts);
+    assert_eq!(response["statusCode"], 413);
+}
+
+#[test]
+fn max_depth() {
+    let opts = ValidatorOptions { max_depth: 3, ..ValidatorOptions::default() };
+    assert!(validate_and_deserialize_with_options::<Ride>(r#"{"mode": "glide", "meta": {"a": {}}}"#, &opts).is_ok());
+    let json = r#"{"mode": "glide", "meta": {"a": {"b": {}}}}"#;
+    assert_eq!(limit_error(json, &opts), (LimitKind::Depth, 3, "meta.a.b".to_string()));
+}
+
+#[test]
+fn max_array_len() {
+    let opts = ValidatorOptions { max_array_len: 2, ..ValidatorOptions::default() };
+    assert!(validate_and_deserialize_with_options::<Ride>(r#"{"mode": "glide", "stops": [1, 2]}"#, &opts).is_ok());
+    let json = r#"{"mode": "glide", "stops": [1, 2, 3]}"#;
+    assert_eq!(limit_error(json, &opts), (LimitKind::ArrayLength, 2, "stops".to_string()));
+}
+
+#[test]
+fn max_object_keys() {
+    let opts = ValidatorOptions { max_object_keys: 3, ..ValidatorOptions::default() };
+    let json = r#"{"mode": "glide", "meta": {"a": 1, "b": 2, "c": 3, "d": 4}}"#;
+    assert_eq!(limit_error(json, &opts), (LimitKind::ObjectKeys, 3, "meta".to_string()));
+}
+
+#[test]
+fn max_string_len() {
+    let opts = ValidatorOptions { max_string_len: 5, ..ValidatorOptions::default() };
+    assert!(validate_and_deserialize_with_options::<Ride>(r#"{"mode": "glide"}"#, &opts).is_ok());
+    let json = r#"{"mode": "glide", "stops": ["ok", "too long"]}"#;
+    assert_eq!(limit_error(json, &opts), (LimitKind::StringLength, 5, "stops.1".to_string()));
+}
+
+#[test]
+fn limit_errors_name_the_offending_path() {
+    let opts = ValidatorOptions { max_array_len: 1, ..ValidatorOptions::default() };
+    let json = r#"{"mode": "glide", "meta": {"legs": [{"ok": []}, {"points": [1, 2]}]}}"#;
+    let err = validate_and_deserialize_with_options::<Ride>(json, &opts).unwrap_err();
+    assert_eq!(err.to_string(), "array has more elements than the limit of 1 at `meta.legs.1.points`");
+}
@@ -0,0 +1,185 @@
+// field_validator/tests/messages.rs
+
+#![allow(dead_code)]
+
+use field_validator::{
+    validate_json_with_options, CatalogError, FieldMessage, Finding, FindingKind, MessageCatalog, ValidateFields,
+    ValidationError, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+#[derive(ValidateFields, Deserialize)]
+struct Leg {
+    model: String,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    #[field_validator(message = "Tell us which bike: {field} is missing")]
+    bike_identifier: String,
+    change_to_mode: String,
+    #[field_validator(nested)]
+    history: Option<Vec<Leg>>,
+}
+
+fn catalog() -> MessageCatalog {
+    MessageCatalog::new()
+        .default_locale("en")
+        .message("en", "missing_field", "{field} is required")
+        .message("de", "missing_field", "{field} fehlt")
+        .message("de", "missing_fields", "Es fehlen {count} Felder: {fields}")
+        .message("de", "field:history.*.model", "Bitte das Modell angeben")
+        .message("pt", "missing_field", "{field} em falta")
+}
+
+fn error(json: &str) -> ValidationError {
+    validate_json_with_options::<Request>(json, &ValidatorOptions::default()).unwrap_err()
+}
+
+fn missing(path: &str) -> Finding {
+    Finding { path: path.to_string(), kind: FindingKind::Missing }
+}
+
+#[test]
+fn locales_are_normalized_and_sorted() {
+    let catalog = MessageCatalog::new().message("pt_BR", "missing_field", "x").message("DE", "missing_field", "y");
+    assert_eq!(catalog.locales(), ["de", "pt-br"]);
+}
+
+#[test]
+fn negotiate_honours_weights_and_languages() {
+    let catalog = catalog();
+    assert_eq!(catalog.negotiate("de"), Some("de"));
+    assert_eq!(catalog.negotiate("fr;q=0.9, pt;q=0.5, de;q=0.7"), Some("de"));
+    assert_eq!(catalog.negotiate("pt-BR, de;q=0.5"), Some("pt"));
+    assert_eq!(catalog.negotiate("de;q=0, pt;q=0.1"), Some("pt"));
+    assert_eq!(catalog.negotiate("fr, it"), None);
+    assert_eq!(catalog.negotiate(""), None);
+}
+
+#[test]
+fn lookups_fall_back_through_language_and_default_locale() {
+    let catalog = catalog();
+    let plan = Request::validation_plan();
+    let render = |locale| catalog.render_finding(locale, &missing("change_to_mode"), Some(plan));
+    assert_eq!(render(Some("de")), "change_to_mode fehlt");
+    assert_eq!(render(Some("DE_at")), "change_to_mode fehlt");
+    assert_eq!(render(Some("pt-BR")), "change_to_mode em falta");
+    assert_eq!(render(Some("fr")), "change_to_mode is required");
+    assert_eq!(render(None), "change_to_mode is required");
+    // Without any template, the built-in message.
+    assert_eq!(
+        MessageCatalog::new().render_finding(Some("de"), &missing("change_to_mode"), Some(plan)),
+        missing("change_to_mode").to_string()
+    );
+}
+
+#[test]
+fn field_keys_use_wildcard_indexes() {
+    let catalog = catalog();
+    let finding = missing("history.3.model");
+    assert_eq!(catalog.render_finding(Some("de"), &finding, None), "Bitte das Modell angeben");
+    assert_eq!(catalog.render_finding(Some("en"), &finding, None), "history.3.model is required");
+}
+
+#[test]
+fn message_attributes_are_the_default_locales_field_message() {
+    let catalog = catalog();
+    let plan = Some(Request::validation_plan());
+    let finding = missing("bike_identifier");
+    assert_eq!(catalog.render_finding(None, &finding, plan), "Tell us which bike: bike_identifier is missing");
+    // The requested locale still wins, and a default-locale field key beats the attribute.
+    assert_eq!(catalog.render_finding(Some("de"), &finding, plan), "bike_identifier fehlt");
+    let catalog = catalog.message("en", "field:bike_identifier", "Which bike?");
+    assert_eq!(catalog.render_finding(None, &finding, plan), "Which bike?");
+    // Without the plan the attribute is unknown.
+    assert_eq!(MessageCatalog::new().render_finding(None, &finding, None), finding.to_string());
+}
+
+#[test]
+fn whole_errors_fill_their_placeholders() {
+    let catalog = catalog();
+    let err = error("{}");
+    let plan = Some(Request::validation_plan());
+    assert_eq!(catalog.render_error(Some("de"), &err, plan), "Es fehlen 2 Felder: `bike_identifier`, `change_to_mode`");
+    assert_eq!(catalog.render_error(Some("en"), &err, plan), err.to_string());
+
+    let syntax = error("{");
+    let catalog = catalog.message("de", "invalid_json", "Ungültiges JSON ({detail})");
+    let rendered = catalog.render_error(Some("de"), &syntax, None);
+    assert!(rendered.starts_with("Ungültiges JSON (") && rendered.len() > "Ungültiges JSON ()".len(), "{}", rendered);
+}
+
+#[test]
+fn unknown_placeholders_are_left_as_written() {
+    let catalog = MessageCatalog::new().message("de", "missing_field", "{field} fehlt {oops} {");
+    assert_eq!(catalog.render_finding(Some("de"), &missing("mode"), None), "mode fehlt {oops} {");
+}
+
+#[test]
+fn field_errors_list_one_message_per_field() {
+    let catalog = catalog();
+    let err = error(r#"{"bike_identifier": "B1", "history": [{}]}"#);
+    assert_eq!(
+        catalog.field_errors(Some("de"), &err, Some(Request::validation_plan())),
+        [
+            FieldMessage {
+                field: "change_to_mode".to_string(),
+                code: "missing_field",
+                message: "change_to_mode fehlt".to_string()
+            },
+            FieldMessage {
+                field: "history.0.model".to_string(),
+                code: "missing_field",
+                message: "Bitte das Modell angeben".to_string()
+            },
+        ]
+    );
+    assert!(catalog.field_errors(Some("de"), &error("{"), None).is_empty());
+}
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("field-validator-messages-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn catalogs_load_from_a_directory() {
+    let dir = temp_dir("load");
+    std::fs::write(dir.join("de.json"), r#"{"missing_field": "{field} fehlt"}"#).unwrap();
+    std::fs::write(dir.join("pt_BR.json"), r#"{"missing_field": "{field} em falta"}"#).unwrap();
+    std::fs::write(dir.join("README.txt"), "not a catalog").unwrap();
+    #[cfg(feature = "yaml")]
+    std::fs::write(dir.join("fr.yaml"), "missing_field: \"{field} manque\"\n").unwrap();
+
+    let catalog = MessageCatalog::load_dir(&dir).unwrap();
+    #[cfg(feature = "yaml")]
+    {
+        assert_eq!(catalog.locales(), ["de", "fr", "pt-br"]);
+        assert_eq!(catalog.render_finding(Some("fr"), &missing("mode"), None), "mode manque");
+    }
+    #[cfg(not(feature = "yaml"))]
+    assert_eq!(catalog.locales(), ["de", "pt-br"]);
+    assert_eq!(catalog.render_finding(Some("pt-BR"), &missing("mode"), None), "mode em falta");
+}
+
+#[test]
+fn malformed_catalogs_are_errors() {
+    let dir = temp_dir("bad");
+    std::fs::write(dir.join("de.json"), r#"{"missing_field": 3}"#).unwrap();
+    let err = MessageCatalog::load_dir(&dir).unwrap_err();
+    assert!(matches!(err, CatalogError::Parse { .. }), "{}", err);
+    assert!(err.to_string().starts_with("invalid message catalog "), "{}", err);
+
+    let err = MessageCatalog::load_dir(dir.join("missing")).unwrap_err();
+    assert!(matches!(err, CatalogError::Read { .. }));
+
+    let mut catalog = MessageCatalog::new();
+    assert!(catalog.add_json("de", "[]").is_err());
+    catalog.add_json("de", r#"{"unknown_field": "{field} ist unbekannt"}"#).unwrap();
+    let finding = Finding { path: "extra".to_string(), kind: FindingKind::UnknownField };
+    assert_eq!(catalog.render_finding(Some("de"), &finding, None), "extra ist unbekannt");
+}
@@ -0,0 +1,109 @@
+// field_validator/tests/migrate.rs
+
+#![allow(dead_code)]
+
+use field_validator::migrate::{self, MigrationError, MigrationRegistry};
+use field_validator::{ValidationError, ValidatorOptions};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(ValidateFields, Deserialize, Debug)]
+struct BikeInfo {
+    model: String,
+    year: u32,
+}
+
+#[derive(ValidateFields, Deserialize, Debug)]
+struct Request {
+    change_to_mode: String,
+    #[field_validator(nested)]
+    bike_info: BikeInfo,
+}
+
+fn registry() -> MigrationRegistry {
+    MigrationRegistry::new(3)
+        .step(1, "rename mode", |v| migrate::rename_field(v, "mode", "change_to_mode"))
+        .try_step(2, "nest bike_info", |v| migrate::nest_fields(v, &["model", "year"], "bike_info"))
+}
+
+fn opts() -> ValidatorOptions {
+    ValidatorOptions { version_field: Some("version".to_string()), ..ValidatorOptions::default() }
+}
+
+#[test]
+fn every_step_from_the_payload_version_runs_in_order() {
+    let json = r#"{"version": 1, "mode": "glide", "model": "S", "year": 2024}"#;
+    let (request, report) = registry().validate_and_deserialize::<Request>(json, &opts()).unwrap();
+    assert_eq!(request.change_to_mode, "glide");
+    assert_eq!(request.bike_info.model, "S");
+    let steps: Vec<_> = report.migrations.iter().map(|m| (m.from, m.name)).collect();
+    assert_eq!(steps, [(1, "rename mode"), (2, "nest bike_info")]);
+}
+
+#[test]
+fn current_payloads_are_not_migrated() {
+    let json = r#"{"change_to_mode": "glide", "bike_info": {"model": "S", "year": 2024}}"#;
+    let (_, report) = registry().migrate_and_validate::<Request>(json, &opts()).unwrap();
+    assert!(report.migrations.is_empty());
+}
+
+#[test]
+fn missing_steps_are_unsupported_versions() {
+    let err = registry().validate_json::<Request>(r#"{"version": 0}"#, &opts()).unwrap_err();
+    assert!(matches!(err, ValidationError::Migration(MigrationError::MissingStep(0))));
+    assert_eq!(err.code(), "unsupported_version");
+}
+
+#[test]
+fn migrated_payloads_are_validated() {
+    let json = r#"{"version": 2, "change_to_mode": "glide", "model": "S"}"#;
+    match registry().validate_json::<Request>(json, &opts()) {
+        Err(ValidationError::MissingFields(e)) => assert_eq!(e.missing_fields, ["bike_info.year"]),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn nest_fields_merges_into_existing_objects() {
+    let mut v = json!({"model": "S", "year": 2024, "bike_info": {"year": 2020, "color": "red"}});
+    migrate::nest_fields(&mut v, &["model", "year"], "bike_info").unwrap();
+    assert_eq!(v, json!({"bike_info": {"model": "S", "year": 2020, "color": "red"}}));
+}
+
+#[test]
+fn nest_fields_replaces_a_null_target() {
+    let mut v = json!({"model": "S", "bike_info": null});
+    migrate::nest_fields(&mut v, &["model", "year"], "bike_info").unwrap();
+    assert_eq!(v, json!({"bike_info": {"model": "S"}}));
+}
+
+#[test]
+fn nest_fields_keeps_fields_when_the_target_is_not_an_object() {
+    let original = json!({"model": "S", "year": 2024, "bike_info": "S-2024"});
+    let mut v = original.clone();
+    let err = migrate::nest_fields(&mut v, &["model", "year"], "bike_info").unwrap_err();
+    assert_eq!(err, MigrationError::NotAnObject { field: "bike_info".to_string() });
+    assert_eq!(v, original);
+
+    let json = r#"{"version": 2, "change_to_mode": "glide", "model": "S", "bike_info": [1]}"#;
+    let err = registry().validate_json::<Request>(json, &opts()).unwrap_err();
+    assert_eq!(err.code(), "migration_failed");
+    assert_eq!(err.to_string(), "cannot migrate payload: `bike_info` is not an object");
+}
+
+#[test]
+fn nest_fields_without_fields_to_move_is_a_no_op() {
+    let mut v = json!({"bike_info": 5});
+    migrate::nest_fields(&mut v, &["model"], "bike_info").unwrap();
+    assert_eq!(v, json!({"bike_info": 5}));
+    let mut v = Value::Null;
+    migrate::nest_fields(&mut v, &["model"], "bike_info").unwrap();
+}
+
+#[test]
+fn rename_field_keeps_an_existing_target() {
+    let mut v = json!({"mode": "glide", "change_to_mode": "combat"});
+    migrate::rename_field(&mut v, "mode", "change_to_mode");
+    assert_eq!(v, json!({"mode": "glide", "change_to_mode": "combat"}));
+}
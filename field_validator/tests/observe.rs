@@ -0,0 +1,193 @@
+// field_validator/tests/observe.rs
+
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use field_validator::dispatch::Dispatcher;
+use field_validator::{
+    validate_json_with_options, validate_json_with_report, validate_partial_with_options, DynamicSchema,
+    ValidationCounters, ValidationEvent, ValidationObserver, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+#[derive(ValidateFields, Deserialize)]
+struct Leg {
+    model: String,
+    #[field_validator(format = "uuid")]
+    ride_id: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    #[field_validator(deprecated = "use change_to_mode")]
+    mode: Option<String>,
+    #[field_validator(nested)]
+    history: Option<Vec<Leg>>,
+}
+
+const REQUEST: &str = "observe::Request";
+
+type Fields = Vec<(String, &'static str)>;
+
+/// Every event as `(type_name, error_code, fields)`.
+#[derive(Default)]
+struct Recorder {
+    events: Mutex<Vec<(String, Option<&'static str>, Fields)>>,
+}
+
+impl ValidationObserver for Recorder {
+    fn on_validation(&self, event: &ValidationEvent<'_>) {
+        assert_eq!(event.is_valid(), event.error_code().is_none());
+        let fields = event.fields().into_iter().map(|(path, code)| (path.to_string(), code)).collect();
+        self.events.lock().unwrap().push((event.type_name.to_string(), event.error_code(), fields));
+    }
+}
+
+fn observed(observer: Arc<dyn ValidationObserver>) -> ValidatorOptions {
+    ValidatorOptions { observer: Some(observer), ..ValidatorOptions::default() }
+}
+
+fn fields(paths: &[(&str, &'static str)]) -> Fields {
+    paths.iter().map(|(path, code)| (path.to_string(), *code)).collect()
+}
+
+#[test]
+fn events_carry_the_type_code_and_field_problems() {
+    let recorder = Arc::new(Recorder::default());
+    let opts = observed(recorder.clone());
+    validate_json_with_report::<Request>(r#"{"bike_identifier": "B1", "mode": "eco"}"#, &opts).unwrap();
+    validate_json_with_options::<Request>(r#"{"history": [{}]}"#, &opts).unwrap_err();
+    validate_json_with_options::<Request>(r#"{"bike_identifier": 1}"#, &opts).unwrap_err();
+    validate_json_with_options::<Request>("{", &opts).unwrap_err();
+
+    let events = recorder.events.lock().unwrap();
+    let type_names: Vec<_> = events.iter().map(|(type_name, _, _)| type_name.as_str()).collect();
+    assert!(type_names.iter().all(|name| name.ends_with(REQUEST)), "{:?}", type_names);
+    assert_eq!(events[0].1, None);
+    assert_eq!(events[0].2, fields(&[("mode", "deprecated_field")]));
+    assert_eq!(events[1].1, Some("missing_fields"));
+    assert_eq!(events[1].2, fields(&[("bike_identifier", "missing_field"), ("history.0.model", "missing_field")]));
+    assert_eq!(events[2].1, Some("invalid_fields"));
+    assert_eq!(events[2].2, fields(&[("bike_identifier", "wrong_type")]));
+    assert_eq!(events[3].1, Some("invalid_json"));
+    assert!(events[3].2.is_empty());
+}
+
+#[test]
+fn counters_tally_runs_rejections_and_fields_per_type() {
+    let counters = Arc::new(ValidationCounters::new());
+    let opts = observed(counters.clone());
+    validate_json_with_options::<Request>(r#"{"bike_identifier": "B1"}"#, &opts).unwrap();
+    validate_json_with_options::<Request>(r#"{"bike_identifier": "B1", "mode": "eco"}"#, &opts).unwrap();
+    let history = r#"{"bike_identifier": "B1", "history": [{}, {"model": "m"}, {}]}"#;
+    validate_json_with_options::<Request>(history, &opts).unwrap_err();
+    let formats = r#"{"bike_identifier": "B1", "history": [{"model": "m", "ride_id": "x"}]}"#;
+    validate_json_with_options::<Request>(formats, &opts).unwrap_err();
+    validate_json_with_options::<Request>("[", &opts).unwrap_err();
+
+    let snapshot = counters.snapshot();
+    assert_eq!(snapshot.len(), 1);
+    let (type_name, counts) = snapshot.iter().next().unwrap();
+    assert!(type_name.ends_with(REQUEST));
+    assert_eq!(counters.get(type_name).as_ref(), Some(counts));
+    assert_eq!((counts.validated, counts.rejected), (5, 3));
+    let by_code: Vec<_> = counts.by_code.iter().map(|(code, n)| (code.as_str(), *n)).collect();
+    assert_eq!(by_code, [("invalid_fields", 1), ("invalid_json", 1), ("missing_fields", 1)]);
+    let by_field: Vec<_> = counts
+        .by_field
+        .iter()
+        .flat_map(|(path, codes)| codes.iter().map(move |(code, n)| (path.as_str(), code.as_str(), *n)))
+        .collect();
+    assert_eq!(
+        by_field,
+        [
+            ("history.*.model", "missing_field", 2),
+            ("history.*.ride_id", "invalid_format", 1),
+            ("mode", "deprecated_field", 1)
+        ]
+    );
+    assert!(counts.max_duration <= counts.total_duration);
+}
+
+#[test]
+fn take_returns_the_tallies_and_resets_them() {
+    let counters = Arc::new(ValidationCounters::new());
+    let opts = observed(counters.clone());
+    validate_json_with_options::<Request>("{}", &opts).unwrap_err();
+    let taken = counters.take();
+    assert_eq!(taken.values().map(|c| (c.validated, c.rejected)).collect::<Vec<_>>(), [(1, 1)]);
+    assert!(counters.snapshot().is_empty());
+    assert_eq!(counters.get(taken.keys().next().unwrap()), None);
+
+    validate_json_with_options::<Request>(r#"{"bike_identifier": "B1"}"#, &opts).unwrap();
+    let counts = counters.take().into_values().next().unwrap();
+    assert_eq!((counts.validated, counts.rejected), (1, 0));
+    assert!(counts.by_code.is_empty() && counts.by_field.is_empty());
+    assert_eq!(counts.max_duration, counts.total_duration);
+    assert!(counts.total_duration < Duration::from_secs(60));
+}
+
+#[test]
+fn partial_validation_is_observed() {
+    let counters = Arc::new(ValidationCounters::new());
+    let opts = observed(counters.clone());
+    validate_partial_with_options::<Request>(r#"{"mode": "eco"}"#, &opts).unwrap();
+    validate_partial_with_options::<Request>(r#"{"bike_identifier": null, "extra": 1}"#, &opts).unwrap_err();
+    let counts = counters.take().into_values().next().unwrap();
+    assert_eq!((counts.validated, counts.rejected), (2, 1));
+    assert_eq!(counts.by_code.get("invalid_fields"), Some(&1));
+    assert!(counts.by_field.contains_key("bike_identifier"));
+    assert!(counts.by_field.contains_key("extra"));
+}
+
+#[test]
+fn schemas_are_counted_under_their_name() {
+    let schema =
+        DynamicSchema::from_json(r#"{"name": "ChangeMode", "fields": [{"name": "change_to_mode", "type": "string"}]}"#)
+            .unwrap();
+    let counters = Arc::new(ValidationCounters::new());
+    let opts = observed(counters.clone());
+    schema.validate_json_with_report(r#"{"change_to_mode": "eco"}"#, &opts).unwrap();
+    schema.validate_json_with_report("{}", &opts).unwrap_err();
+    schema.validate_partial(r#"{"change_to_mode": 1}"#, &opts).unwrap_err();
+    let counts = counters.get("ChangeMode").unwrap();
+    assert_eq!((counts.validated, counts.rejected), (3, 2));
+    let change_to_mode = &counts.by_field["change_to_mode"];
+    assert_eq!((change_to_mode["missing_field"], change_to_mode["wrong_type"]), (1, 1));
+}
+
+#[test]
+fn dispatchers_observe_routed_payloads_only() {
+    let dispatcher: Dispatcher<()> = Dispatcher::new("action").route::<Request, _>("change_mode", |_| ());
+    let counters = Arc::new(ValidationCounters::new());
+    let opts = observed(counters.clone());
+    dispatcher.dispatch(r#"{"action": "change_mode", "bike_identifier": "B1"}"#, &opts).unwrap();
+    dispatcher.dispatch(r#"{"action": "change_mode"}"#, &opts).unwrap_err();
+    dispatcher.dispatch(r#"{"action": "unknown"}"#, &opts).unwrap_err();
+    dispatcher.dispatch("{", &opts).unwrap_err();
+    let snapshot = counters.snapshot();
+    assert_eq!(snapshot.len(), 1);
+    let counts = snapshot.values().next().unwrap();
+    assert_eq!((counts.validated, counts.rejected), (2, 1));
+    assert_eq!(counts.by_field["bike_identifier"]["missing_field"], 1);
+}
+
+#[test]
+fn observers_are_optional() {
+    let opts = ValidatorOptions::default();
+    assert!(opts.observer.is_none());
+    validate_json_with_options::<Request>(r#"{"bike_identifier": "B1"}"#, &opts).unwrap();
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn the_tracing_observer_accepts_every_outcome() {
+    let opts = observed(Arc::new(field_validator::TracingObserver));
+    validate_json_with_options::<Request>(r#"{"bike_identifier": "B1", "mode": "eco"}"#, &opts).unwrap();
+    validate_json_with_options::<Request>(r#"{"history": [{}]}"#, &opts).unwrap_err();
+    validate_json_with_options::<Request>("{", &opts).unwrap_err();
+}
@@ -0,0 +1,208 @@
+// field_validator/tests/outbound.rs
+
+#![allow(dead_code)]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use field_validator::{
+    assert_serialized_valid, debug_assert_serialized, validate_serialized, FindingKind, ValidateFields, ValidationError,
+};
+use field_validator_derive::ValidateFields;
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(ValidateFields, Serialize, Deserialize)]
+struct Leg {
+    model: String,
+    #[field_validator(format = "uuid")]
+    ride_id: Option<String>,
+}
+
+#[derive(ValidateFields, Serialize)]
+struct SnsPayload {
+    bike_identifier: String,
+    steps: usize,
+    #[field_validator(deprecated = "use steps")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<u32>,
+    #[field_validator(nested)]
+    legs: Vec<Leg>,
+}
+
+fn payload() -> SnsPayload {
+    SnsPayload {
+        bike_identifier: "B1".to_string(),
+        steps: 2,
+        count: None,
+        legs: vec![Leg { model: "S".to_string(), ride_id: Some("67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()) }],
+    }
+}
+
+/// A hand-written `Serialize` that has drifted from the derived contract:
+/// it sends the entries of `wire` instead of its fields.
+#[derive(ValidateFields)]
+struct Drifted {
+    bike_identifier: String,
+    steps: usize,
+    wire: Option<Vec<(&'static str, serde_json::Value)>>,
+}
+
+impl Serialize for Drifted {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let wire = self.wire.as_deref().unwrap_or_default();
+        let mut map = serializer.serialize_map(Some(wire.len()))?;
+        for (key, value) in wire {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+fn drifted(wire: &[(&'static str, serde_json::Value)]) -> Drifted {
+    Drifted { bike_identifier: String::new(), steps: 0, wire: Some(wire.to_vec()) }
+}
+
+#[derive(ValidateFields)]
+struct Unserializable {
+    bike_identifier: String,
+}
+
+impl Serialize for Unserializable {
+    fn serialize<S: Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom("no wire form"))
+    }
+}
+
+#[derive(ValidateFields, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+struct Response {
+    #[serde(rename(serialize = "outName", deserialize = "in_name"))]
+    name: String,
+    bike_identifier: String,
+    #[serde(skip_deserializing)]
+    computed: u32,
+    #[serde(skip_serializing)]
+    secret: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    #[field_validator(nested)]
+    legs: Vec<Leg>,
+}
+
+fn response() -> Response {
+    Response {
+        name: "n".to_string(),
+        bike_identifier: "B1".to_string(),
+        computed: 3,
+        secret: Some("s".to_string()),
+        legs: Vec::new(),
+    }
+}
+
+fn panic_message(f: impl FnOnce()) -> String {
+    let panic = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+    panic.downcast_ref::<String>().cloned().unwrap_or_default()
+}
+
+#[test]
+fn valid_values_pass_with_their_warnings() {
+    assert!(validate_serialized(&payload()).unwrap().warnings.is_empty());
+    let report = validate_serialized(&SnsPayload { count: Some(2), ..payload() }).unwrap();
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].path, "count");
+    assert_eq!(assert_serialized_valid(&SnsPayload { count: Some(2), ..payload() }), report);
+    debug_assert_serialized(&payload());
+}
+
+#[test]
+fn missing_and_null_required_fields_are_reported() {
+    let value = drifted(&[("bike_identifier", serde_json::Value::Null), ("legs", serde_json::json!([{}]))]);
+    match validate_serialized(&value) {
+        Err(ValidationError::MissingFields(e)) => assert_eq!(e.missing_fields, ["bike_identifier", "steps"]),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn wrong_types_formats_and_unknown_keys_are_reported() {
+    let mut value = payload();
+    value.legs.push(Leg { model: "S".to_string(), ride_id: Some("not-a-uuid".to_string()) });
+    match validate_serialized(&value) {
+        Err(ValidationError::InvalidFields(findings)) => {
+            assert_eq!(findings.len(), 1);
+            assert_eq!(findings[0].path, "legs.1.ride_id");
+            assert!(matches!(findings[0].kind, FindingKind::InvalidFormat { .. }));
+        }
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+
+    let value = drifted(&[
+        ("bike_identifier", serde_json::json!("B1")),
+        ("steps", serde_json::json!("2")),
+        ("bikeId", serde_json::json!("B1")),
+    ]);
+    match validate_serialized(&value) {
+        Err(ValidationError::InvalidFields(findings)) => {
+            let paths: Vec<_> = findings.iter().map(|f| (f.path.as_str(), f.kind.code())).collect();
+            assert_eq!(paths, [("bikeId", "unknown_field"), ("steps", "wrong_type")]);
+        }
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn serialization_failures_are_errors() {
+    let err = validate_serialized(&Unserializable { bike_identifier: "B1".to_string() }).unwrap_err();
+    assert!(matches!(err, ValidationError::Serialize(_)));
+    assert_eq!(err.code(), "unserializable");
+    let message = panic_message(|| {
+        assert_serialized_valid(&Unserializable { bike_identifier: "B1".to_string() });
+    });
+    assert!(message.starts_with("cannot serialize outbound::Unserializable: no wire form"), "{}", message);
+}
+
+#[test]
+fn assertions_render_each_problem_in_the_payload() {
+    let value = drifted(&[("bike_identifier", serde_json::json!("B1")), ("steps", serde_json::json!(true))]);
+    let message = panic_message(|| {
+        assert_serialized_valid(&value);
+    });
+    assert!(message.starts_with("outgoing outbound::Drifted breaks its contract:\n"), "{}", message);
+    assert!(message.contains("  --> line 3, column 12"), "{}", message);
+    assert!(message.contains("\"steps\": true"), "{}", message);
+
+    let message = panic_message(|| debug_assert_serialized(&value));
+    assert_eq!(message.is_empty(), !cfg!(debug_assertions));
+}
+
+#[test]
+fn values_are_checked_as_they_are_written() {
+    let report = validate_serialized(&response()).unwrap();
+    assert!(report.warnings.is_empty());
+    assert_serialized_valid(&response());
+
+    let names: Vec<_> = Response::serialized_fields().iter().map(|f| f.name).collect();
+    assert_eq!(names, ["outName", "bikeIdentifier", "computed", "legs"]);
+    let names: Vec<_> = Response::fields().iter().map(|f| f.name).collect();
+    assert_eq!(names, ["in_name", "bike_identifier", "secret", "legs"]);
+}
+
+#[test]
+fn skip_serializing_if_fields_may_be_left_out_but_are_checked_when_written() {
+    let mut value = response();
+    value.legs.push(Leg { model: "S".to_string(), ride_id: Some("x".to_string()) });
+    match validate_serialized(&value) {
+        Err(ValidationError::InvalidFields(findings)) => assert_eq!(findings[0].path, "legs.0.ride_id"),
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn incoming_payloads_still_use_the_deserialize_names() {
+    use field_validator::validate_json_with_options;
+    let opts = field_validator::ValidatorOptions::default();
+    validate_json_with_options::<Response>(r#"{"in_name": "n", "bike_identifier": "B1"}"#, &opts).unwrap();
+    match validate_json_with_options::<Response>(r#"{"outName": "n", "bikeIdentifier": "B1"}"#, &opts) {
+        Err(ValidationError::MissingFields(e)) => assert_eq!(e.missing_fields, ["in_name", "bike_identifier"]),
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
@@ -0,0 +1,177 @@
+// field_validator/tests/plan.rs
+
+#![allow(dead_code)]
+
+use field_validator::{FindingKind, TypeShape, ValidateFields, ValidationError, ValidationPlan};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeDetails {
+    model: String,
+    year: u32,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    change_to_mode: String,
+    current_mode: Option<String>,
+    #[field_validator(required_if = "current_mode")]
+    reason: Option<String>,
+    #[field_validator(nested)]
+    bike_info: BikeDetails,
+}
+
+/// A hand-written impl, validated through the shared plan cache.
+struct Manual;
+
+impl ValidateFields for Manual {
+    fn required_fields() -> &'static [&'static str] {
+        &["bike_identifier", "change_to_mode"]
+    }
+
+    fn conditional_fields() -> &'static [(&'static str, &'static str)] {
+        &[("reason", "current_mode")]
+    }
+}
+
+struct Borrowed<'a>(&'a str);
+
+impl ValidateFields for Borrowed<'_> {
+    fn required_fields() -> &'static [&'static str] {
+        &["name"]
+    }
+}
+
+/// The check `validate_json_for_type` made before plans: a lookup per
+/// required field, then each conditional field whose trigger is set.
+fn legacy_missing<T: ValidateFields>(v: &Value) -> Vec<String> {
+    let Some(obj) = v.as_object() else {
+        return T::required_fields().iter().map(|s| s.to_string()).collect();
+    };
+    let present = |name: &str| obj.get(name).is_some_and(|v| !v.is_null());
+    let mut missing: Vec<String> = T::required_fields()
+        .iter()
+        .filter(|name| !present(name))
+        .map(|s| s.to_string())
+        .collect();
+    for &(field, trigger) in T::conditional_fields() {
+        if present(trigger) && !present(field) {
+            missing.push(field.to_string());
+        }
+    }
+    missing
+}
+
+fn plan_missing(plan: &ValidationPlan, v: &Value) -> Vec<String> {
+    match plan.check(v) {
+        Ok(()) => Vec::new(),
+        Err(e) => e.missing_fields,
+    }
+}
+
+fn payloads() -> Vec<Value> {
+    let bike_info = json!({ "model": "S", "year": 2024 });
+    vec![
+        json!({ "bike_identifier": "b", "change_to_mode": "glide", "bike_info": bike_info }),
+        json!({ "bike_identifier": "b", "bike_info": bike_info }),
+        json!({ "bike_identifier": null, "change_to_mode": null, "bike_info": bike_info }),
+        json!({ "bike_identifier": "b", "change_to_mode": "glide", "current_mode": "combat", "bike_info": bike_info }),
+        json!({ "bike_identifier": "b", "change_to_mode": "glide", "current_mode": null, "bike_info": bike_info }),
+        json!({ "change_to_mode": "glide", "current_mode": "combat", "reason": "test", "bike_info": bike_info }),
+        json!({}),
+        json!([]),
+        json!("not an object"),
+    ]
+}
+
+#[test]
+fn derived_plan_matches_legacy_lookup() {
+    for payload in payloads() {
+        assert_eq!(
+            plan_missing(Request::validation_plan(), &payload),
+            legacy_missing::<Request>(&payload),
+            "{}",
+            payload
+        );
+    }
+}
+
+#[test]
+fn cached_plan_matches_legacy_lookup() {
+    for payload in payloads() {
+        assert_eq!(
+            plan_missing(Manual::validation_plan(), &payload),
+            legacy_missing::<Manual>(&payload),
+            "{}",
+            payload
+        );
+    }
+}
+
+#[test]
+fn nested_fields_are_reported_by_path() {
+    let payload = json!({ "bike_identifier": "b", "change_to_mode": "glide", "bike_info": { "year": 2024 } });
+    assert_eq!(plan_missing(Request::validation_plan(), &payload), ["bike_info.model"]);
+}
+
+#[test]
+fn cached_plans_are_per_type() {
+    let manual = Manual::validation_plan();
+    assert!(std::ptr::eq(manual, Manual::validation_plan()));
+    let borrowed = Borrowed::validation_plan();
+    assert!(std::ptr::eq(borrowed, Borrowed::validation_plan()));
+    assert!(!std::ptr::eq(manual, borrowed));
+    assert_eq!(plan_missing(borrowed, &json!({})), ["name"]);
+}
+
+#[test]
+fn cached_plan_is_shared_across_threads() {
+    let plans: Vec<usize> = (0..8)
+        .map(|_| std::thread::spawn(|| Borrowed::validation_plan() as *const ValidationPlan as usize))
+        .map(|handle| handle.join().unwrap())
+        .collect();
+    assert!(plans.iter().all(|&p| p == plans[0]));
+}
+
+#[test]
+fn full_mode_reports_wrong_types() {
+    let payload = json!({
+        "bike_identifier": 7,
+        "change_to_mode": "glide",
+        "bike_info": { "model": "S", "year": "2024" }
+    });
+    let err = Request::validation_plan().check_payload(&payload, None).unwrap_err();
+    let ValidationError::InvalidFields(findings) = err else {
+        panic!("expected invalid fields, got {:?}", err);
+    };
+    let found: Vec<_> = findings.iter().map(|f| (f.path.as_str(), &f.kind)).collect();
+    assert_eq!(
+        found,
+        [
+            ("bike_identifier", &FindingKind::WrongType { expected: TypeShape::String }),
+            ("bike_info.year", &FindingKind::WrongType { expected: TypeShape::Integer }),
+        ]
+    );
+    // Required-field checks alone still pass
+    assert!(Request::validation_plan().check(&payload).is_ok());
+}
+
+#[test]
+fn full_and_partial_mode_agree_on_types() {
+    let payload = json!({
+        "bike_identifier": ["b"],
+        "change_to_mode": "glide",
+        "bike_info": { "model": false, "year": 2024 }
+    });
+    let plan = Request::validation_plan();
+    let partial: Vec<_> = plan.check_partial(&payload).into_iter().map(|f| f.path).collect();
+    let full: Vec<_> = match plan.check_payload(&payload, None) {
+        Err(ValidationError::InvalidFields(findings)) => findings.into_iter().map(|f| f.path).collect(),
+        other => panic!("expected invalid fields, got {:?}", other),
+    };
+    assert_eq!(partial, ["bike_identifier", "bike_info.model"]);
+    assert_eq!(full, partial);
+}
@@ -0,0 +1,123 @@
+// field_validator/tests/redact.rs
+//
+// The redaction policy is process-wide, so every test takes `POLICY` first.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use field_validator::redact::{self, MASK};
+use field_validator::{
+    set_redaction_policy, validate_and_deserialize, validate_json_with_options, RedactionPolicy, ValidationError,
+    ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+static POLICY: Mutex<()> = Mutex::new(());
+
+fn with_policy(policy: RedactionPolicy) -> MutexGuard<'static, ()> {
+    let guard = POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    set_redaction_policy(policy);
+    guard
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Owner {
+    name: String,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeInfo {
+    model: String,
+    #[field_validator(sensitive)]
+    vin: Option<u32>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Fleet {
+    #[field_validator(nested)]
+    bikes: HashMap<String, BikeInfo>,
+    #[field_validator(nested, sensitive)]
+    owner: Option<Owner>,
+    region: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Plain {
+    count: u32,
+}
+
+fn field_values() -> [String; 4] {
+    [
+        redact::field_value::<Fleet>("region", &"eu"),
+        redact::field_value::<Fleet>("bikes.alice.vin", &42),
+        redact::field_value::<Fleet>("owner.name", &"Ann"),
+        redact::value(&"get_mode"),
+    ]
+}
+
+fn deserialize_message<T: field_validator::ValidateFields + serde::de::DeserializeOwned>(json: &str) -> String {
+    match validate_and_deserialize::<T>(json) {
+        Ok(_) => panic!("{} was accepted", json),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn missing(json: &str) -> Vec<String> {
+    match validate_json_with_options::<Fleet>(json, &ValidatorOptions::default()) {
+        Err(ValidationError::MissingFields(e)) => e.missing_fields,
+        other => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+fn limit_path(json: &str) -> String {
+    let opts = ValidatorOptions { max_string_len: 8, ..ValidatorOptions::default() };
+    match validate_json_with_options::<Fleet>(json, &opts) {
+        Err(ValidationError::Limit(limit)) => limit.path,
+        other => panic!("expected a limit error, got {:?}", other),
+    }
+}
+
+// Values with the right JSON type that serde still rejects
+const BIG_VIN: &str = r#"{"bikes": {"a": {"model": "S", "vin": 99999999999}}}"#;
+const NEGATIVE: &str = r#"{"count": -5}"#;
+const MISSING_MODEL: &str = r#"{"bikes": {"alice": {}}}"#;
+const LONG_MODEL: &str = r#"{"bikes": {"alice": {"model": "far too long"}}}"#;
+const LONG_EXTRA: &str = r#"{"bikes": {}, "extra": {"alice": ["far too long"]}}"#;
+
+#[test]
+fn off_leaves_everything() {
+    let _guard = with_policy(RedactionPolicy::Off);
+    assert_eq!(field_values(), ["eu", "42", "Ann", "get_mode"]);
+    assert!(deserialize_message::<Fleet>(BIG_VIN).contains("99999999999"));
+    assert_eq!(missing(MISSING_MODEL), ["bikes.alice.model"]);
+    assert_eq!(limit_path(LONG_MODEL), "bikes.alice.model");
+    assert_eq!(limit_path(LONG_EXTRA), "extra.alice.0");
+}
+
+#[test]
+fn sensitive_fields_masks_sensitive_fields_and_their_contents() {
+    let _guard = with_policy(RedactionPolicy::SensitiveFields);
+    assert_eq!(field_values(), ["eu", MASK, MASK, "get_mode"]);
+    let message = deserialize_message::<Fleet>(BIG_VIN);
+    assert!(!message.contains("99999999999"), "{}", message);
+    assert!(message.contains(MASK), "{}", message);
+    // Types without sensitive fields keep serde's message
+    assert!(deserialize_message::<Plain>(NEGATIVE).contains("-5"));
+    assert_eq!(missing(MISSING_MODEL), ["bikes.alice.model"]);
+    assert_eq!(limit_path(LONG_MODEL), "bikes.alice.model");
+}
+
+#[test]
+fn all_masks_every_value_and_map_key() {
+    let _guard = with_policy(RedactionPolicy::All);
+    assert_eq!(field_values(), [MASK; 4]);
+    let message = deserialize_message::<Plain>(NEGATIVE);
+    assert!(!message.contains("-5"), "{}", message);
+    assert_eq!(missing(MISSING_MODEL), ["bikes.*.model"]);
+    assert_eq!(limit_path(LONG_MODEL), "bikes.*.model");
+    assert_eq!(limit_path(LONG_EXTRA), "*.*.0");
+    set_redaction_policy(RedactionPolicy::SensitiveFields);
+}
@@ -0,0 +1,112 @@
+// field_validator/tests/render.rs
+//
+// The redaction policy is process-wide, so every test takes `POLICY` first.
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use field_validator::{
+    set_redaction_policy, validate_json_with_options, RedactionPolicy, SourceRenderer, ValidateFields, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+use serde_json::Value;
+
+static POLICY: Mutex<()> = Mutex::new(());
+
+fn with_policy(policy: RedactionPolicy) -> MutexGuard<'static, ()> {
+    let guard = POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    set_redaction_policy(policy);
+    guard
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeInfo {
+    model: String,
+    year: Option<u32>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    mode: String,
+    #[field_validator(nested)]
+    bike_info: Option<BikeInfo>,
+    #[field_validator(nested)]
+    bikes: Option<HashMap<String, BikeInfo>>,
+    extra: Option<Value>,
+}
+
+fn render<T: ValidateFields>(json: &str, opts: &ValidatorOptions) -> String {
+    let err = validate_json_with_options::<T>(json, opts).unwrap_err();
+    SourceRenderer::for_type::<T>(json).render_error(&err)
+}
+
+#[test]
+fn missing_fields_point_at_their_object() {
+    let _policy = with_policy(RedactionPolicy::SensitiveFields);
+    let json = "{\n  \"mode\": \"eco\",\n  \"bike_info\": { \"year\": 2020 }\n}";
+    assert_eq!(
+        render::<Request>(json, &ValidatorOptions::default()),
+        "error[missing_field]: `bike_info.model` is required\n  \
+         --> line 3, column 16\n  \
+         |\n\
+         3 |   \"bike_info\": { \"year\": 2020 }\n  \
+         |                ^ `model` is missing from this object\n"
+    );
+}
+
+#[test]
+fn deeply_nested_payloads_do_not_overflow_the_scan() {
+    let _policy = with_policy(RedactionPolicy::SensitiveFields);
+    let json = format!("{}{}", "[".repeat(200_000), "]".repeat(200_000));
+    let rendered = render::<Request>(&json, &ValidatorOptions::default());
+    assert!(rendered.starts_with("error[max_depth_exceeded]: "), "{}", &rendered[..200]);
+    // The array past the limit, 32 deep.
+    assert!(rendered.contains("  --> line 1, column 33\n"), "{}", &rendered[..200]);
+
+    let json = format!(r#"{{"extra": {}{}, "mode": 3}}"#, "[".repeat(200_000), "]".repeat(200_000));
+    let renderer = SourceRenderer::for_type::<Request>(&json);
+    let finding = field_validator::Finding {
+        path: "mode".to_string(),
+        kind: field_validator::FindingKind::WrongType { expected: field_validator::TypeShape::String },
+    };
+    let rendered = renderer.render_findings(&[finding]);
+    let column = json.len() - 1;
+    assert!(rendered.contains(&format!("  --> line 1, column {}\n", column)), "{}", &rendered[..200]);
+}
+
+#[test]
+fn values_below_the_scan_depth_are_located_as_a_whole() {
+    let _policy = with_policy(RedactionPolicy::SensitiveFields);
+    let opts = ValidatorOptions::unlimited();
+    let json = format!(r#"{{"extra": {}{}, "mode": 3}}"#, "[".repeat(100), "]".repeat(100));
+    let rendered = render::<Request>(&json, &opts);
+    assert!(rendered.starts_with("error[wrong_type]: "), "{}", rendered);
+    assert!(rendered.contains(&format!("  --> line 1, column {}\n", json.len() - 1)), "{}", rendered);
+}
+
+#[test]
+fn masked_map_keys_still_find_their_span() {
+    let _policy = with_policy(RedactionPolicy::All);
+
+    let json = "{\"mode\": \"eco\", \"bikes\": {\"us\": {\"model\": 3}}}";
+    let rendered = render::<Request>(json, &ValidatorOptions::default());
+    assert!(rendered.contains("`bikes.*.model` must be a string"), "{}", rendered);
+    assert!(rendered.contains("  --> line 1, column 43\n"), "{}", rendered);
+
+    let json = "{\"mode\": \"eco\", \"bikes\": {\"eu\": {\"year\": 2020}}}";
+    let rendered = render::<Request>(json, &ValidatorOptions::default());
+    assert!(rendered.contains("`bikes.*.model` is required"), "{}", rendered);
+    assert!(rendered.contains("  --> line 1, column 33\n"), "{}", rendered);
+}
+
+#[test]
+fn ambiguous_masked_paths_point_at_the_map() {
+    let _policy = with_policy(RedactionPolicy::All);
+    let json = "{\n  \"mode\": \"eco\",\n  \"bikes\": {\n    \"eu\": { \"model\": \"S\" },\n    \"us\": { \"model\": 3 }\n  }\n}";
+    let rendered = render::<Request>(json, &ValidatorOptions::default());
+    assert!(rendered.contains("`bikes.*.model` must be a string"), "{}", rendered);
+    assert!(rendered.contains("  --> line 3, column 12\n"), "{}", rendered);
+}
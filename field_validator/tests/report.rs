@@ -0,0 +1,42 @@
+// field_validator/tests/report.rs
+
+use field_validator::{Finding, FindingKind, ValidationReport};
+
+fn report(notes: &[&str]) -> ValidationReport {
+    let warnings = notes
+        .iter()
+        .map(|note| Finding { path: "mode".to_string(), kind: FindingKind::Deprecated { note: note.to_string() } })
+        .collect();
+    ValidationReport { warnings, ..ValidationReport::default() }
+}
+
+#[test]
+fn no_warnings_no_header() {
+    assert_eq!(ValidationReport::default().warning_header(), None);
+}
+
+#[test]
+fn header_joins_one_warn_value_per_warning() {
+    let header = report(&["use change_to_mode", "gone in v4"]).warning_header().unwrap();
+    assert_eq!(
+        header,
+        r#"299 - "`mode` is deprecated: use change_to_mode", 299 - "`mode` is deprecated: gone in v4""#
+    );
+}
+
+#[test]
+fn only_quotes_and_backslashes_are_escaped() {
+    let header = report(&[r#"send "glide" not C:\mode, café"#]).warning_header().unwrap();
+    assert_eq!(header, r#"299 - "`mode` is deprecated: send \"glide\" not C:\\mode, café""#);
+}
+
+#[test]
+fn control_characters_cannot_split_the_header() {
+    let header = report(&["line one\r\nSet-Cookie: x\ttab"]).warning_header().unwrap();
+    assert_eq!(header, r#"299 - "`mode` is deprecated: line one  Set-Cookie: x tab""#);
+}
+
+#[test]
+fn messages_are_unescaped() {
+    assert_eq!(report(&[r#"say "hi""#]).warning_messages(), [r#"`mode` is deprecated: say "hi""#]);
+}
@@ -0,0 +1,222 @@
+// field_validator/tests/schema.rs
+
+#![allow(dead_code)]
+
+use field_validator::{DynamicSchema, ValidationError, ValidatorOptions};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+const JSON_SCHEMA: &str = r#"{
+  "name": "Request",
+  "fields": [
+    { "name": "bike_identifier", "type": "string" },
+    { "name": "current_mode", "type": "string", "optional": true },
+    { "name": "change_to_mode", "type": "string", "required_if": "current_mode" },
+    { "name": "legacy_mode", "type": "string", "until": 2 },
+    { "name": "bike_info", "type": "object", "fields": [
+      { "name": "model", "type": "string" },
+      { "name": "year", "type": "integer" }
+    ] },
+    { "name": "history", "type": "array", "items": "object", "optional": true, "fields": [
+      { "name": "model", "type": "string" }
+    ] }
+  ]
+}"#;
+
+const YAML_SCHEMA: &str = "
+name: Request
+fields:
+  - { name: bike_identifier, type: string }
+  - { name: current_mode, type: string, optional: true }
+  - { name: change_to_mode, type: string, required_if: current_mode }
+  - { name: legacy_mode, type: string, until: 2 }
+  - name: bike_info
+    type: object
+    fields:
+      - { name: model, type: string }
+      - { name: year, type: integer }
+  - name: history
+    type: array
+    items: object
+    optional: true
+    fields:
+      - { name: model, type: string }
+";
+
+fn missing(schema: &DynamicSchema, json: &str) -> Vec<String> {
+    match schema.validate_json_with_report(json, &ValidatorOptions::default()) {
+        Ok(_) => Vec::new(),
+        Err(ValidationError::MissingFields(missing)) => missing.missing_fields,
+        Err(other) => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+fn check_request_schema(schema: &DynamicSchema) {
+    assert_eq!(schema.name(), "Request");
+    assert_eq!(schema.fields().len(), 6);
+    assert!(missing(schema, r#"{"bike_identifier": "b", "bike_info": {"model": "m", "year": 2020}}"#).is_empty());
+    assert_eq!(
+        missing(schema, r#"{"current_mode": "eco", "bike_info": {"year": 2020}, "history": [{}]}"#),
+        ["bike_identifier", "change_to_mode", "bike_info.model", "history.0.model"]
+    );
+    let v1 = ValidatorOptions { api_version: Some(1), ..ValidatorOptions::default() };
+    let err =
+        schema.validate_json_with_report(r#"{"bike_identifier": "b", "bike_info": {"model": "m", "year": 1}}"#, &v1);
+    assert!(matches!(err, Err(ValidationError::MissingFields(m)) if m.missing_fields == ["legacy_mode"]));
+}
+
+#[test]
+fn loads_a_json_schema() {
+    check_request_schema(&DynamicSchema::from_json(JSON_SCHEMA).unwrap());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn loads_a_yaml_schema() {
+    let schema = DynamicSchema::from_yaml(YAML_SCHEMA).unwrap();
+    check_request_schema(&schema);
+    assert_eq!(schema.fields(), DynamicSchema::from_json(JSON_SCHEMA).unwrap().fields());
+}
+
+#[test]
+fn from_path_picks_the_format_from_the_extension() {
+    let dir = std::env::temp_dir().join(format!("field-validator-schema-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("request.json"), JSON_SCHEMA).unwrap();
+    std::fs::write(dir.join("request.yaml"), YAML_SCHEMA).unwrap();
+
+    check_request_schema(&DynamicSchema::from_path(dir.join("request.json")).unwrap());
+    #[cfg(feature = "yaml")]
+    check_request_schema(&DynamicSchema::from_path(dir.join("request.yaml")).unwrap());
+    #[cfg(not(feature = "yaml"))]
+    assert!(matches!(DynamicSchema::from_path(dir.join("request.yaml")), Err(field_validator::SchemaError::Parse(_))));
+
+    let err = DynamicSchema::from_path(dir.join("missing.json")).unwrap_err();
+    assert!(err.to_string().contains("cannot read"), "{}", err);
+}
+
+fn load_error(fields: &str) -> String {
+    let json = format!(r#"{{"name": "Request", "fields": [{}]}}"#, fields);
+    DynamicSchema::from_json(&json).unwrap_err().to_string()
+}
+
+#[test]
+fn required_if_must_name_a_sibling() {
+    assert_eq!(
+        load_error(r#"{"name": "mode", "type": "string", "required_if": "current_mode"}"#),
+        "invalid schema: `required_if` on `mode` refers to unknown field `current_mode`"
+    );
+    assert_eq!(
+        load_error(r#"{"name": "mode", "type": "string", "required_if": "mode"}"#),
+        "invalid schema: `mode` cannot be `required_if` itself"
+    );
+    // A field of another object is not a sibling.
+    assert_eq!(
+        load_error(
+            r#"{"name": "current_mode", "type": "string", "optional": true},
+               {"name": "bike_info", "type": "object", "fields": [
+                 {"name": "model", "type": "string", "required_if": "current_mode"}
+               ]}"#
+        ),
+        "invalid schema: `required_if` on `bike_info.model` refers to unknown field `current_mode`"
+    );
+}
+
+#[test]
+fn until_must_follow_since() {
+    for (since, until) in [(3, 3), (3, 2)] {
+        assert_eq!(
+            load_error(&format!(r#"{{"name": "mode", "type": "string", "since": {}, "until": {}}}"#, since, until)),
+            "invalid schema: `until` on `mode` must be a later version than `since`"
+        );
+    }
+    assert!(DynamicSchema::from_json(
+        r#"{"name": "Request", "fields": [{"name": "mode", "type": "string", "since": 2, "until": 3}]}"#
+    )
+    .is_ok());
+}
+
+#[test]
+fn fields_need_an_object_type() {
+    assert_eq!(
+        load_error(r#"{"name": "mode", "type": "string", "fields": [{"name": "x", "type": "string"}]}"#),
+        "invalid schema: `fields` on `mode` needs an object type or items"
+    );
+    assert_eq!(
+        load_error(
+            r#"{"name": "legs", "type": "array", "items": "string", "fields": [{"name": "x", "type": "string"}]}"#
+        ),
+        "invalid schema: `fields` on `legs` needs an object type or items"
+    );
+    for (ty, items) in [("object", None), ("array", Some("object")), ("map", Some("object")), ("array", None)] {
+        let items = items.map(|items| format!(r#", "items": "{}""#, items)).unwrap_or_default();
+        let fields =
+            format!(r#"{{"name": "leg", "type": "{}"{}, "fields": [{{"name": "x", "type": "string"}}]}}"#, ty, items);
+        assert!(
+            DynamicSchema::from_json(&format!(r#"{{"name": "Request", "fields": [{}]}}"#, fields)).is_ok(),
+            "{}",
+            fields
+        );
+    }
+}
+
+#[test]
+fn field_names_must_be_unique() {
+    assert_eq!(
+        load_error(r#"{"name": "mode", "type": "string"}, {"name": "mode", "type": "integer"}"#),
+        "invalid schema: `mode` is declared more than once"
+    );
+    assert_eq!(
+        load_error(
+            r#"{"name": "bike_info", "type": "object", "fields": [
+                 {"name": "model", "type": "string"}, {"name": "model", "type": "string"}
+               ]}"#
+        ),
+        "invalid schema: `bike_info.model` is declared more than once"
+    );
+    // The same name in different objects is fine.
+    assert!(DynamicSchema::from_json(
+        r#"{"name": "Request", "fields": [
+             {"name": "model", "type": "string"},
+             {"name": "bike_info", "type": "object", "fields": [{"name": "model", "type": "string"}]}
+           ]}"#
+    )
+    .is_ok());
+}
+
+#[test]
+fn unknown_types_formats_and_keys_are_rejected() {
+    assert_eq!(
+        load_error(r#"{"name": "bike_info", "type": "object", "fields": [{"name": "year", "type": "int"}]}"#),
+        "invalid schema: unknown type `int` for `bike_info.year`"
+    );
+    assert!(load_error(r#"{"name": "when", "type": "string", "format": "date"}"#)
+        .starts_with("invalid schema: bad format for `when`"));
+    assert!(load_error(r#"{"name": "mode", "type": "string", "requried": true}"#).contains("requried"));
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeInfo {
+    model: String,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    current_mode: Option<String>,
+    #[field_validator(required_if = "current_mode")]
+    change_to_mode: Option<String>,
+    #[field_validator(nested)]
+    bike_info: BikeInfo,
+}
+
+#[test]
+fn derived_schemas_round_trip_through_json() {
+    let schema = DynamicSchema::for_type::<Request>("Request");
+    let loaded = DynamicSchema::from_json(&schema.to_json()).unwrap();
+    assert_eq!(loaded.fields(), schema.fields());
+    assert_eq!(
+        missing(&loaded, r#"{"current_mode": "eco", "bike_info": {}}"#),
+        ["bike_identifier", "change_to_mode", "bike_info.model"]
+    );
+}
@@ -0,0 +1,191 @@
+// field_validator/tests/typescript.rs
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use field_validator::{DynamicSchema, TypeScriptGenerator};
+use field_validator_derive::{StringEnum, ValidateFields};
+use serde::Deserialize;
+
+#[derive(Deserialize, StringEnum)]
+#[serde(rename_all = "snake_case")]
+enum RideMode {
+    Glide,
+    Combat,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct BikeInfo {
+    /// The marketing name.
+    model: String,
+    year: Option<u32>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    bike_identifier: String,
+    #[field_validator(enum)]
+    change_to_mode: Option<RideMode>,
+    #[field_validator(required_if = "change_to_mode", format = "epoch_seconds")]
+    changed_at: Option<i64>,
+    #[field_validator(nested)]
+    bike_info: BikeInfo,
+    #[field_validator(nested)]
+    fleet: Option<HashMap<String, BikeInfo>>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[field_validator(until = "2")]
+    legacy_mode: Option<String>,
+    #[field_validator(since = "2", deprecated = "use changeToMode")]
+    mode: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Node {
+    name: String,
+    #[field_validator(nested)]
+    children: Option<Vec<Node>>,
+}
+
+struct Legacy;
+
+impl field_validator::ValidateFields for Legacy {
+    fn required_fields() -> &'static [&'static str] {
+        &["bike_identifier"]
+    }
+}
+
+/// The `export interface name { ... }` block of `module`.
+fn interface<'a>(module: &'a str, name: &str) -> &'a str {
+    let start =
+        module.find(&format!("export interface {} {{\n", name)).unwrap_or_else(|| panic!("no {} in\n{}", name, module));
+    let end = start + module[start..].find("\n}\n").unwrap() + 3;
+    &module[start..end]
+}
+
+#[test]
+fn derived_types_become_interfaces() {
+    let module = TypeScriptGenerator::new().add_type::<Request>("Request").generate();
+    assert!(module.starts_with("// Generated by field_validator. Do not edit.\n"));
+    assert_eq!(
+        interface(&module, "Request"),
+        r#"export interface Request {
+  bikeIdentifier: string;
+  changeToMode?: "glide" | "combat" | null;
+  /**
+   * Required when `changeToMode` is set.
+   * Must be Unix epoch seconds (`epoch_seconds`).
+   */
+  changedAt?: number | null;
+  bikeInfo: BikeInfo;
+  fleet?: Record<string, BikeInfo> | null;
+  tags?: string[];
+  /**
+   * Since API version 2.
+   * @deprecated use changeToMode
+   */
+  mode?: string | null;
+}
+"#
+    );
+    assert_eq!(
+        interface(&module, "BikeInfo"),
+        "export interface BikeInfo {\n  /** The marketing name. */\n  model: string;\n  year?: number | null;\n}\n"
+    );
+    // Shared nested types are emitted once; fields of older versions not at all.
+    assert_eq!(module.matches("export interface BikeInfo").count(), 1);
+    assert!(!module.contains("legacyMode"));
+    assert!(!module.contains("function"));
+}
+
+#[test]
+fn validators_mirror_the_server_rules() {
+    let module = TypeScriptGenerator::new().validators(true).add_type::<Request>("Request").generate();
+    assert_eq!(module.matches("export interface ValidationIssue").count(), 1);
+    for function in [
+        "export function validateRequest(value: unknown, path: string = \"\"): ValidationIssue[] {",
+        "export function isRequest(value: unknown): value is Request {",
+        "export function validateBikeInfo(value: unknown, path: string = \"\"): ValidationIssue[] {",
+        "export function isBikeInfo(value: unknown): value is BikeInfo {",
+    ] {
+        assert!(module.contains(function), "missing {}", function);
+    }
+    assert!(module.contains(
+        r#"if ((value["changeToMode"] !== undefined && value["changeToMode"] !== null)) issues.push({ path: p, code: "missing_field" });"#
+    ));
+    assert!(module.contains(r#"notAllowed(p, v, ["glide", "combat"])"#));
+    assert!(module.contains("validateBikeInfo(e0, joinPath(p, k0))"));
+    // `tags` may be absent but not null.
+    assert!(module.contains(r#"if (v === null) issues.push(...wrongType(p, "array"));"#));
+}
+
+#[test]
+fn recursive_types_refer_to_themselves() {
+    let module = TypeScriptGenerator::new().add_type::<Node>("Node").generate();
+    assert_eq!(
+        interface(&module, "Node"),
+        "export interface Node {\n  name: string;\n  children?: Node[] | null;\n}\n"
+    );
+    assert_eq!(module.matches("export interface").count(), 1);
+}
+
+#[test]
+fn clashing_names_are_numbered_and_repeats_reused() {
+    let module = TypeScriptGenerator::new()
+        .add_type::<Node>("Request")
+        .add_type::<Request>("Request")
+        .add_type::<Node>("Tree")
+        .generate();
+    assert!(interface(&module, "Request").contains("children?: Request[] | null;"));
+    assert!(interface(&module, "Request2").contains("bikeIdentifier: string;"));
+    assert!(!module.contains("interface Tree"));
+}
+
+#[test]
+fn bare_impls_list_their_required_fields() {
+    let module = TypeScriptGenerator::new().add_type::<Legacy>("Legacy").generate();
+    assert_eq!(interface(&module, "Legacy"), "export interface Legacy {\n  bike_identifier: unknown;\n}\n");
+}
+
+#[test]
+fn schemas_name_nested_interfaces_after_their_parent() {
+    let schema = DynamicSchema::from_json(
+        r#"{
+          "name": "RideEvent",
+          "fields": [
+            { "name": "bike-id", "type": "string" },
+            { "name": "mode", "type": "string", "enum": ["glide"], "optional": true },
+            { "name": "bike_info", "type": "object", "fields": [{ "name": "model", "type": "string" }] },
+            { "name": "history", "type": "array", "items": "object", "optional": true, "fields": [
+              { "name": "at", "type": "integer", "format": "epoch_seconds" }
+            ] },
+            { "name": "old", "type": "string", "until": 2 }
+          ]
+        }"#,
+    )
+    .unwrap();
+    let module = TypeScriptGenerator::new().add_schema(&schema).generate();
+    assert_eq!(
+        interface(&module, "RideEvent"),
+        "export interface RideEvent {\n  \"bike-id\": string;\n  mode?: \"glide\" | null;\n  bike_info: RideEventBikeInfo;\n  history?: RideEventHistory[] | null;\n}\n"
+    );
+    assert_eq!(interface(&module, "RideEventBikeInfo"), "export interface RideEventBikeInfo {\n  model: string;\n}\n");
+    assert_eq!(
+        interface(&module, "RideEventHistory"),
+        "export interface RideEventHistory {\n  /** Must be Unix epoch seconds (`epoch_seconds`). */\n  at: number;\n}\n"
+    );
+}
+
+#[test]
+fn write_if_changed_skips_identical_files() {
+    let dir = std::env::temp_dir().join(format!("field-validator-ts-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("generated/requests.ts");
+    let generator = TypeScriptGenerator::new().add_type::<BikeInfo>("BikeInfo");
+    assert!(generator.write_if_changed(&path).unwrap());
+    assert!(!generator.write_if_changed(&path).unwrap());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), generator.generate());
+    assert!(TypeScriptGenerator::new().add_type::<Node>("Node").write_if_changed(&path).unwrap());
+}
@@ -0,0 +1,111 @@
+// field_validator/tests/validate.rs
+
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use field_validator::{
+    handle_json_request, handle_json_request_with_options, validate_json_for_type, validate_json_with_options,
+    FindingKind, MessageCatalog, ValidationError, ValidatorOptions,
+};
+use field_validator_derive::{StringEnum, ValidateFields};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize, StringEnum)]
+#[serde(rename_all = "snake_case")]
+enum RideMode {
+    Glide,
+    Combat,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    #[field_validator(format = "uuid")]
+    ride_id: String,
+    #[field_validator(enum)]
+    change_to_mode: RideMode,
+}
+
+const BAD_FORMAT: &str = r#"{"ride_id": "not-a-uuid", "change_to_mode": "glide"}"#;
+const BAD_VALUE: &str = r#"{"ride_id": "6f1c2a9e-3b7d-4c1e-9a57-0d2b8e4f6a13", "change_to_mode": "turbo"}"#;
+
+fn finding_kinds(json: &str) -> Vec<(String, &'static str)> {
+    match validate_json_with_options::<Request>(json, &ValidatorOptions::default()) {
+        Err(ValidationError::InvalidFields(findings)) => {
+            findings.iter().map(|f| (f.path.clone(), f.kind.code())).collect()
+        }
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn validate_json_for_type_only_checks_presence() {
+    assert!(validate_json_for_type::<Request>(BAD_FORMAT).is_ok());
+    assert!(validate_json_for_type::<Request>(BAD_VALUE).is_ok());
+    assert!(validate_json_for_type::<Request>(r#"{"ride_id": 3, "change_to_mode": "glide"}"#).is_ok());
+
+    let missing = validate_json_for_type::<Request>(r#"{"ride_id": "not-a-uuid"}"#).unwrap_err();
+    assert_eq!(missing.missing_fields, ["change_to_mode"]);
+    assert!(validate_json_for_type::<Request>("{").unwrap_err().missing_fields.is_empty());
+}
+
+#[test]
+fn values_are_checked_with_options() {
+    assert_eq!(finding_kinds(BAD_FORMAT), [("ride_id".to_string(), "invalid_format")]);
+    assert_eq!(finding_kinds(BAD_VALUE), [("change_to_mode".to_string(), "invalid_value")]);
+    match validate_json_with_options::<Request>(BAD_VALUE, &ValidatorOptions::default()) {
+        Err(ValidationError::InvalidFields(findings)) => {
+            assert!(
+                matches!(&findings[0].kind, FindingKind::InvalidValue { allowed } if allowed == &["glide", "combat"])
+            );
+        }
+        other => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn invalid_fields_are_validation_errors_with_field_errors() {
+    let response = handle_json_request::<Request>(BAD_FORMAT);
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(response["body"]["error"], "Validation Error");
+    assert_eq!(response["body"]["fieldErrors"][0]["field"], "ride_id");
+    assert_eq!(response["body"]["fieldErrors"][0]["code"], "invalid_format");
+    let message = response["body"]["message"].as_str().unwrap();
+    assert!(message.starts_with("invalid fields: `ride_id`"), "{}", message);
+
+    let response = handle_json_request::<Request>(BAD_VALUE);
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(response["body"]["error"], "Validation Error");
+    assert_eq!(
+        response["body"]["fieldErrors"],
+        json!([{
+            "field": "change_to_mode",
+            "code": "invalid_value",
+            "message": "`change_to_mode` must be one of: glide, combat"
+        }])
+    );
+}
+
+#[test]
+fn invalid_field_responses_are_localized() {
+    let catalog = MessageCatalog::new()
+        .message("de", "invalid_fields", "Ungültige Felder: {detail}")
+        .message("de", "invalid_format", "{field} ist keine {format}")
+        .message("de", "invalid_value", "{field} muss eines von {allowed} sein");
+    let opts = ValidatorOptions {
+        messages: Some(Arc::new(catalog)),
+        locale: Some("de".to_string()),
+        ..ValidatorOptions::default()
+    };
+
+    let response = handle_json_request_with_options::<Request>(BAD_FORMAT, &opts);
+    assert_eq!(response["statusCode"], 400);
+    assert_eq!(response["body"]["message"], "Ungültige Felder: ride_id ist keine uuid");
+    assert_eq!(response["body"]["fieldErrors"][0]["message"], "ride_id ist keine uuid");
+
+    let response = handle_json_request_with_options::<Request>(BAD_VALUE, &opts);
+    assert_eq!(response["body"]["error"], "Validation Error");
+    assert_eq!(response["body"]["message"], "Ungültige Felder: change_to_mode muss eines von glide, combat sein");
+    assert_eq!(response["body"]["fieldErrors"][0]["code"], "invalid_value");
+}
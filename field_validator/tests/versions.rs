@@ -0,0 +1,68 @@
+// field_validator/tests/versions.rs
+
+#![allow(dead_code)]
+
+use field_validator::{
+    parse_version, validate_for_version, validate_json_with_options, ValidateFields, ValidationError,
+    ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    bike_identifier: String,
+    #[field_validator(until = "3", required)]
+    mode: Option<String>,
+    #[field_validator(since = "3", required)]
+    change_to_mode: Option<String>,
+    #[field_validator(since = "2", until = "4", required)]
+    region: Option<String>,
+}
+
+fn missing(result: Result<(), ValidationError>) -> Vec<String> {
+    match result {
+        Ok(()) => Vec::new(),
+        Err(ValidationError::MissingFields(e)) => e.missing_fields,
+        Err(other) => panic!("expected missing fields, got {:?}", other),
+    }
+}
+
+const EMPTY: &str = r#"{"bike_identifier": "b"}"#;
+
+#[test]
+fn fields_are_required_only_within_their_range() {
+    assert_eq!(missing(validate_for_version::<Request>(EMPTY, 1)), ["mode"]);
+    assert_eq!(missing(validate_for_version::<Request>(EMPTY, 2)), ["mode", "region"]);
+    assert_eq!(missing(validate_for_version::<Request>(EMPTY, 3)), ["change_to_mode", "region"]);
+    assert_eq!(missing(validate_for_version::<Request>(EMPTY, 4)), ["change_to_mode"]);
+}
+
+#[test]
+fn latest_version_is_assumed_without_one() {
+    let opts = ValidatorOptions::default();
+    assert_eq!(missing(validate_json_with_options::<Request>(EMPTY, &opts)), ["change_to_mode"]);
+    assert_eq!(Request::required_fields(), ["bike_identifier", "change_to_mode"]);
+}
+
+#[test]
+fn payload_version_wins_over_options() {
+    let opts = ValidatorOptions {
+        api_version: Some(1),
+        version_field: Some("api_version".to_string()),
+        ..ValidatorOptions::default()
+    };
+    assert_eq!(missing(validate_json_with_options::<Request>(EMPTY, &opts)), ["mode"]);
+    let json = r#"{"bike_identifier": "b", "api_version": "v3"}"#;
+    assert_eq!(missing(validate_json_with_options::<Request>(json, &opts)), ["change_to_mode", "region"]);
+    let json = r#"{"bike_identifier": "b", "api_version": 4}"#;
+    assert_eq!(missing(validate_json_with_options::<Request>(json, &opts)), ["change_to_mode"]);
+}
+
+#[test]
+fn versions_parse_with_or_without_prefix() {
+    assert_eq!(parse_version("2"), Some(2));
+    assert_eq!(parse_version(" v3 "), Some(3));
+    assert_eq!(parse_version("V4"), Some(4));
+    assert_eq!(parse_version("latest"), None);
+}
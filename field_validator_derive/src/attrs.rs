@@ -0,0 +1,265 @@
+// field_validator_derive/src/attrs.rs
+
+use field_validator::format::Format;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, LitStr, Result};
+
+/// Keys accepted inside `#[field_validator(...)]` on a field.
+const FIELD_KEYS: &[&str] = &[
+    "optional",
+    "option",
+    "required",
+    "required_if",
+    "nested",
+    "since",
+    "until",
+    "deprecated",
+    "sensitive",
+    "message",
+    "format",
+    "enum",
+];
+
+/// Parsed contents of every `#[field_validator(...)]` attribute on a field.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// `optional`: never treat the field as required.
+    pub optional: Option<proc_macro2::Span>,
+    /// `option`: the field's type is an `Option` in disguise (e.g. an alias).
+    pub option: Option<proc_macro2::Span>,
+    /// `required`: always required, even if the type is an `Option`.
+    pub required: Option<proc_macro2::Span>,
+    /// `nested`: the field's type also derives `ValidateFields`.
+    pub nested: Option<proc_macro2::Span>,
+    /// `sensitive`: messages about the field must not echo its value.
+    pub sensitive: Option<proc_macro2::Span>,
+    /// `enum`: the field's type derives `StringEnum` and travels as a string.
+    pub string_enum: Option<proc_macro2::Span>,
+    /// `required_if = "other"`: required only when `other` is present and non-null.
+    pub required_if: Option<LitStr>,
+    /// `since = "2"`: the field exists from this API version on.
+    pub since: Option<(u32, LitStr)>,
+    /// `until = "3"`: the field no longer exists from this API version on.
+    pub until: Option<(u32, LitStr)>,
+    /// `deprecated = "use bike_info.model"`: supplying the field yields a warning.
+    pub deprecated: Option<LitStr>,
+    /// `message = "..."`: the message for problems with the field.
+    pub message: Option<LitStr>,
+    /// `format = "uuid"`: supplied values must have this built-in format.
+    pub format: Option<(Format, LitStr)>,
+}
+
+impl FieldAttrs {
+    /// Parse all `field_validator` attributes of a field, reporting unknown
+    /// keys, malformed values, duplicates and contradictory combinations.
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = FieldAttrs::default();
+        let mut errors: Option<Error> = None;
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("field_validator")) {
+            let res = attr.parse_nested_meta(|meta| {
+                let flag = if meta.path.is_ident("optional") {
+                    Some(("optional", &mut out.optional))
+                } else if meta.path.is_ident("option") {
+                    Some(("option", &mut out.option))
+                } else if meta.path.is_ident("required") {
+                    Some(("required", &mut out.required))
+                } else if meta.path.is_ident("nested") {
+                    Some(("nested", &mut out.nested))
+                } else if meta.path.is_ident("sensitive") {
+                    Some(("sensitive", &mut out.sensitive))
+                } else if meta.path.is_ident("enum") {
+                    Some(("enum", &mut out.string_enum))
+                } else {
+                    None
+                };
+                if let Some((key, slot)) = flag {
+                    if !meta.input.is_empty() && !meta.input.peek(syn::Token![,]) {
+                        return Err(meta.error(format!("`{}` does not take a value", key)));
+                    }
+                    if slot.is_some() {
+                        return Err(meta.error(format!("duplicate `{}` attribute", key)));
+                    }
+                    *slot = Some(meta.path.span());
+                    Ok(())
+                } else if meta.path.is_ident("required_if") {
+                    let lit: LitStr = meta.value()?.parse().map_err(|e| {
+                        Error::new(e.span(), "`required_if` expects a string literal naming another field")
+                    })?;
+                    if out.required_if.is_some() {
+                        return Err(meta.error("duplicate `required_if` attribute"));
+                    }
+                    out.required_if = Some(lit);
+                    Ok(())
+                } else if meta.path.is_ident("deprecated") {
+                    let lit: LitStr = meta.value()?.parse().map_err(|e| {
+                        Error::new(e.span(), "`deprecated` expects a string literal saying what to use instead")
+                    })?;
+                    if out.deprecated.is_some() {
+                        return Err(meta.error("duplicate `deprecated` attribute"));
+                    }
+                    out.deprecated = Some(lit);
+                    Ok(())
+                } else if meta.path.is_ident("message") {
+                    let lit: LitStr = meta.value()?.parse().map_err(|e| {
+                        Error::new(e.span(), "`message` expects a string literal")
+                    })?;
+                    if out.message.is_some() {
+                        return Err(meta.error("duplicate `message` attribute"));
+                    }
+                    out.message = Some(lit);
+                    Ok(())
+                } else if meta.path.is_ident("format") {
+                    let lit: LitStr = meta.value()?.parse().map_err(|e| {
+                        Error::new(e.span(), "`format` expects a string literal such as \"uuid\"")
+                    })?;
+                    let format = Format::parse(&lit.value()).map_err(|msg| Error::new(lit.span(), msg))?;
+                    if out.format.is_some() {
+                        return Err(meta.error("duplicate `format` attribute"));
+                    }
+                    out.format = Some((format, lit));
+                    Ok(())
+                } else if meta.path.is_ident("since") || meta.path.is_ident("until") {
+                    let key = if meta.path.is_ident("since") { "since" } else { "until" };
+                    let lit: LitStr = meta.value()?.parse().map_err(|e| {
+                        Error::new(e.span(), format!("`{}` expects a version string like \"2\"", key))
+                    })?;
+                    let version: u32 = lit.value().parse().map_err(|_| {
+                        Error::new(lit.span(), format!("`{}` expects a version string like \"2\"", key))
+                    })?;
+                    let slot = if key == "since" { &mut out.since } else { &mut out.until };
+                    if slot.is_some() {
+                        return Err(meta.error(format!("duplicate `{}` attribute", key)));
+                    }
+                    *slot = Some((version, lit));
+                    Ok(())
+                } else {
+                    let key = meta
+                        .path
+                        .get_ident()
+                        .map(|i| i.to_string())
+                        .unwrap_or_else(|| "<path>".to_string());
+                    Err(meta.error(format!(
+                        "unknown field_validator attribute `{}`; expected one of: {}",
+                        key,
+                        FIELD_KEYS.join(", ")
+                    )))
+                }
+            });
+            if let Err(e) = res {
+                push_error(&mut errors, e);
+            }
+        }
+
+        if let (Some(span), Some(_)) = (out.optional, &out.required_if) {
+            push_error(
+                &mut errors,
+                Error::new(span, "`optional` cannot be combined with `required_if`"),
+            );
+        }
+        if let (Some(span), Some(_)) = (out.required, out.optional) {
+            push_error(
+                &mut errors,
+                Error::new(span, "`required` cannot be combined with `optional`"),
+            );
+        }
+        if let (Some(span), Some(_)) = (out.required, &out.required_if) {
+            push_error(
+                &mut errors,
+                Error::new(span, "`required` cannot be combined with `required_if`"),
+            );
+        }
+
+        if let (Some(_), Some((_, lit))) = (out.nested, &out.format) {
+            push_error(
+                &mut errors,
+                Error::new(lit.span(), "`format` cannot be combined with `nested`"),
+            );
+        }
+        if let (Some(span), Some(_)) = (out.string_enum, out.nested) {
+            push_error(
+                &mut errors,
+                Error::new(span, "`enum` cannot be combined with `nested`"),
+            );
+        }
+        if let (Some(span), Some(_)) = (out.string_enum, &out.format) {
+            push_error(
+                &mut errors,
+                Error::new(span, "`enum` cannot be combined with `format`"),
+            );
+        }
+
+        if let (Some((since, _)), Some((until, lit))) = (&out.since, &out.until) {
+            if until <= since {
+                push_error(
+                    &mut errors,
+                    Error::new(lit.span(), "`until` must be a later version than `since`"),
+                );
+            }
+        }
+
+        match errors {
+            Some(e) => Err(e),
+            None => Ok(out),
+        }
+    }
+}
+
+/// Keys accepted inside `#[field_validator(...)]` on the struct itself.
+const CONTAINER_KEYS: &[&str] = &["patch"];
+
+/// Parsed contents of `#[field_validator(...)]` on the struct itself.
+#[derive(Default)]
+pub struct ContainerAttrs {
+    /// `patch`: also generate a `{Name}Patch` struct of all-optional fields.
+    pub patch: Option<proc_macro2::Span>,
+}
+
+impl ContainerAttrs {
+    pub fn from_attrs(attrs: &[Attribute]) -> Result<Self> {
+        let mut out = ContainerAttrs::default();
+        let mut errors: Option<Error> = None;
+
+        for attr in attrs.iter().filter(|a| a.path().is_ident("field_validator")) {
+            let res = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("patch") {
+                    if !meta.input.is_empty() && !meta.input.peek(syn::Token![,]) {
+                        return Err(meta.error("`patch` does not take a value"));
+                    }
+                    if out.patch.is_some() {
+                        return Err(meta.error("duplicate `patch` attribute"));
+                    }
+                    out.patch = Some(meta.path.span());
+                    Ok(())
+                } else {
+                    let key = meta
+                        .path
+                        .get_ident()
+                        .map(|i| i.to_string())
+                        .unwrap_or_else(|| "<path>".to_string());
+                    Err(meta.error(format!(
+                        "unknown field_validator struct attribute `{}`; expected one of: {}",
+                        key,
+                        CONTAINER_KEYS.join(", ")
+                    )))
+                }
+            });
+            if let Err(e) = res {
+                push_error(&mut errors, e);
+            }
+        }
+
+        match errors {
+            Some(e) => Err(e),
+            None => Ok(out),
+        }
+    }
+}
+
+/// Accumulate `err` into `acc` so all problems are reported at once.
+pub fn push_error(acc: &mut Option<Error>, err: Error) {
+    match acc {
+        Some(existing) => existing.combine(err),
+        None => *acc = Some(err),
+    }
+}
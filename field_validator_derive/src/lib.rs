@@ -4,16 +4,79 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
+use syn::ext::IdentExt;
 use syn::{parse_macro_input, DeriveInput, Data, Fields, Type};
 use syn::LitStr;
+use field_validator::format::Format;
+
+mod attrs;
+mod patch;
+mod serde_attrs;
+mod shape;
+mod string_enum;
+use attrs::{push_error, ContainerAttrs, FieldAttrs};
+use serde_attrs::{RenameRule, SerdeContainer, SerdeField};
+use shape::{is_option_type, leaf_type, shape_tokens, Leaf};
 
 /// Derive macro for ValidateFields trait
 ///
 /// This macro will automatically implement the ValidateFields trait
 /// for your struct, identifying required fields based on their type:
-/// - Fields with non-optional types (not Option<T>) are considered required
+/// - Fields with non-optional types (not Option<T>) are considered required;
+///   `Option`, `std::option::Option` and `core::option::Option` are all
+///   recognized
 /// - Fields with attributes like #[serde(skip_serializing_if="Option::is_none")]
 ///   or #[serde(default)] are considered optional
+///
+/// Field names are the wire names, honouring `#[serde(rename)]` and
+/// `#[serde(rename_all)]`, and `#[serde(alias)]` names are accepted too.
+/// Fields with `#[serde(skip)]` are left out, and `#[serde(default)]` on the
+/// struct makes every field optional. `#[serde(flatten)]` fields are a
+/// compile error, since their keys cannot be checked.
+///
+/// `serialized_fields()` describes the fields as `Serialize` writes them,
+/// for checking outgoing values: it uses the serialize-side names of
+/// `rename` and `rename_all`, leaves out `skip_serializing` fields and keeps
+/// `skip_deserializing` ones.
+///
+/// Structs may have lifetime parameters, so request types can borrow from
+/// the input (see `field_validator::validate_and_deserialize_borrowed`);
+/// type and const parameters are not supported.
+///
+/// Field attributes:
+/// - `#[field_validator(optional)]` never requires the field
+/// - `#[field_validator(option)]` treats the field's type as an `Option`, for
+///   aliases such as `type MaybeMode = Option<String>`
+/// - `#[field_validator(required)]` always requires the field, overriding
+///   `Option` types and serde defaults
+/// - `#[field_validator(required_if = "other")]` requires the field only when
+///   `other` is present and non-null
+/// - `#[field_validator(nested)]` links the field to its own type's
+///   descriptors; the type must also implement `ValidateFields`
+/// - `#[field_validator(since = "2", until = "3")]` limits the field to API
+///   versions 2 (inclusive) through 3 (exclusive); outside that range it is
+///   never required. `required_fields()` reflects the latest version.
+/// - `#[field_validator(deprecated = "use bike_info.model")]` accepts the
+///   field but reports a warning whenever a payload supplies it
+/// - `#[field_validator(sensitive)]` keeps the field's value out of error
+///   messages (see `field_validator::redact`)
+/// - `#[field_validator(message = "Tell us which bike: {field} is missing")]`
+///   replaces the message for problems with the field; `{field}` and the
+///   other placeholders of `field_validator::messages` are filled in
+/// - `#[field_validator(format = "uuid")]` requires supplied values to have
+///   a built-in format: `epoch_seconds`, `epoch_seconds(min, max)`,
+///   `rfc3339`, `latitude`, `longitude`, `vin`, `uuid`, `ip` or `url`
+/// - `#[field_validator(enum)]` accepts only the wire names of the field's
+///   type, which must derive `StringEnum`; works through `Option` and
+///   containers, e.g. `Vec<RideMode>`
+///
+/// Struct attributes:
+/// - `#[field_validator(patch)]` also generates `{Name}Patch`, a
+///   `Deserialize` struct with every field wrapped in `Option`, and
+///   implements `field_validator::Patchable` to apply it onto `{Name}`
+///
+/// Unknown keys, malformed values and contradictory combinations are
+/// reported as compile errors pointing at the offending attribute.
 #[proc_macro_derive(ValidateFields, attributes(field_validator))]
 pub fn derive_validate_fields(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
@@ -22,86 +85,414 @@ pub fn derive_validate_fields(input: TokenStream) -> TokenStream {
     // Get the name of the struct
     let name = &input.ident;
 
-    // Extract the list of field names that should be required
-    let required_list = extract_required_fields(&input.data);
+    // Collect everything we know about the struct and each field
+    let container = SerdeContainer::from_attrs(&input.attrs);
+    let container_attrs = ContainerAttrs::from_attrs(&input.attrs);
+    let fields = extract_fields(&input.data, &container);
+    let (container_attrs, (fields, serialized)) = match (container_attrs, fields) {
+        (Ok(attrs), Ok(fields)) => (attrs, fields),
+        (Err(mut e), Err(more)) => {
+            e.combine(more);
+            return TokenStream::from(e.to_compile_error());
+        }
+        (Err(e), _) | (_, Err(e)) => return TokenStream::from(e.to_compile_error()),
+    };
+    if let Err(e) = check_generics(&input, &container_attrs) {
+        return TokenStream::from(e.to_compile_error());
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    // Convert each field name into a string literal for code generation
-    let lits: Vec<LitStr> = required_list
-        .into_iter()
-        .map(|f| LitStr::new(&f, Span::call_site()))
+    // Convert each required field name into a string literal for code generation
+    let lits: Vec<LitStr> = fields
+        .iter()
+        .filter(|f| f.required && f.until.is_none())
+        .map(|f| LitStr::new(&f.wire_name, Span::call_site()))
+        .collect();
+    let conditional: Vec<(LitStr, LitStr)> = fields
+        .iter()
+        .filter(|f| f.until.is_none())
+        .filter_map(|f| {
+            let trigger = f.required_if.as_ref()?;
+            Some((
+                LitStr::new(&f.wire_name, Span::call_site()),
+                LitStr::new(trigger, Span::call_site()),
+            ))
+        })
         .collect();
+    let cond_fields = conditional.iter().map(|(f, _)| f);
+    let cond_triggers = conditional.iter().map(|(_, t)| t);
+    let descriptors = fields.iter().map(FieldInfo::descriptor_tokens);
+    let serialized = serialized.iter().map(FieldInfo::descriptor_tokens);
+    let patch = container_attrs
+        .patch
+        .map(|_| patch::patch_tokens(&input, &fields))
+        .unwrap_or_default();
 
     // Generate implementation. required_fields returns a static slice of &str.
     let expanded = quote! {
-        impl field_validator::ValidateFields for #name {
+        impl #impl_generics field_validator::ValidateFields for #name #ty_generics #where_clause {
             fn required_fields() -> &'static [&'static str] {
                 &[#(#lits),*]
             }
+
+            fn conditional_fields() -> &'static [(&'static str, &'static str)] {
+                &[#((#cond_fields, #cond_triggers)),*]
+            }
+
+            fn fields() -> &'static [field_validator::FieldDescriptor] {
+                const FIELDS: &[field_validator::FieldDescriptor] = &[#(#descriptors),*];
+                FIELDS
+            }
+
+            fn serialized_fields() -> &'static [field_validator::FieldDescriptor] {
+                const FIELDS: &[field_validator::FieldDescriptor] = &[#(#serialized),*];
+                FIELDS
+            }
+
+            fn validation_plan() -> &'static field_validator::ValidationPlan {
+                static PLAN: ::std::sync::OnceLock<field_validator::ValidationPlan> =
+                    ::std::sync::OnceLock::new();
+                PLAN.get_or_init(field_validator::ValidationPlan::for_type::<Self>)
+            }
         }
+
+        #patch
     };
 
     // Return the generated code
     TokenStream::from(expanded)
 }
 
-/// Extract required fields based on their type and attributes
-fn extract_required_fields(data: &Data) -> Vec<String> {
-    match data {
-        Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(fields) => fields
-                .named
-                .iter()
-                .filter_map(|field| {
-                    let field_name = field.ident.as_ref()?;
-
-                    // Skip if field has #[field_validator(optional)] attribute
-                    let has_optional_attr = field.attrs.iter().any(|attr| {
-                        attr.path().is_ident("field_validator")
-                            && attr
-                                .meta
-                                .require_list()
-                                .ok()
-                                .map(|list| list.tokens.to_string().contains("optional"))
-                                .unwrap_or(false)
-                    });
-
-                    // Skip if field has serde(default) or skip_serializing_if attributes
-                    let has_serde_optional = field.attrs.iter().any(|attr| {
-                        attr.path().is_ident("serde")
-                            && attr
-                                .meta
-                                .require_list()
-                                .ok()
-                                .map(|list| {
-                                    let tokens = list.tokens.to_string();
-                                    tokens.contains("default") || tokens.contains("skip_serializing_if")
-                                })
-                                .unwrap_or(false)
-                    });
-
-                    // Skip if field type is Option<T>
-                    let is_option_type = is_option_type(&field.ty);
-
-                    // Include as required if none of the optional criteria are met
-                    if !has_optional_attr && !has_serde_optional && !is_option_type {
-                        Some(field_name.to_string())
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
-            _ => Vec::new(),
-        },
-        _ => Vec::new(),
+/// Only lifetime parameters are supported: the plan is cached once per
+/// impl, and the patch companion must own its data.
+fn check_generics(input: &DeriveInput, attrs: &ContainerAttrs) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    for param in &input.generics.params {
+        if !matches!(param, syn::GenericParam::Lifetime(_)) {
+            push_error(
+                &mut errors,
+                syn::Error::new_spanned(param, "`ValidateFields` can only be derived with lifetime parameters"),
+            );
+        }
+    }
+    if let (Some(span), false) = (attrs.patch, input.generics.params.is_empty()) {
+        push_error(
+            &mut errors,
+            syn::Error::new(span, "`patch` cannot be used on a struct with generic parameters"),
+        );
+    }
+    match errors {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Derive macro for the `StringEnum` trait
+///
+/// For enums of unit variants, lists the variants' wire names, honouring
+/// `#[serde(rename)]` on variants and `#[serde(rename_all)]` on the enum,
+/// and leaving out `#[serde(skip)]` variants. Variants with data and
+/// `#[serde(other)]` are compile errors, since neither has a fixed list of
+/// values.
+#[proc_macro_derive(StringEnum)]
+pub fn derive_string_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match string_enum::string_enum_tokens(&input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(e) => TokenStream::from(e.to_compile_error()),
+    }
+}
+
+/// What the derive learned about a single field.
+#[derive(Clone)]
+struct FieldInfo {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    rust_name: String,
+    wire_name: String,
+    aliases: Vec<String>,
+    /// serde's `deserialize_with` (or `with`) function for the field.
+    deserialize_with: Option<LitStr>,
+    /// Whether serde's `serialize_with` (or `with`) writes the field.
+    serialize_with: bool,
+    /// Whether this describes the field as `Serialize` writes it rather
+    /// than as it is read.
+    serialized: bool,
+    ty: Type,
+    required: bool,
+    nullable: bool,
+    required_if: Option<String>,
+    since: Option<u32>,
+    until: Option<u32>,
+    deprecated: Option<String>,
+    sensitive: bool,
+    message: Option<String>,
+    format: Option<Format>,
+    nested: bool,
+    string_enum: bool,
+    doc: String,
+}
+
+impl FieldInfo {
+    /// A `field_validator::FieldDescriptor` literal for this field.
+    fn descriptor_tokens(&self) -> proc_macro2::TokenStream {
+        let name = &self.wire_name;
+        let rust_name = &self.rust_name;
+        let leaf = match (self.nested, self.string_enum) {
+            (true, _) => Leaf::Object,
+            (_, true) => Leaf::String,
+            _ => Leaf::Other,
+        };
+        // A custom (de)serializer decides what it accepts or writes
+        let custom = match self.serialized {
+            true => self.serialize_with,
+            false => self.deserialize_with.is_some(),
+        };
+        let shape = match custom {
+            true => quote!(field_validator::TypeShape::Any),
+            false => shape_tokens(&self.ty, leaf),
+        };
+        let optional = !self.required;
+        let nullable = self.nullable;
+        let doc = &self.doc;
+        let since = option_tokens(self.since);
+        let until = option_tokens(self.until);
+        let deprecated = option_tokens(self.deprecated.as_deref());
+        let sensitive = self.sensitive;
+        let message = option_tokens(self.message.as_deref());
+        let constraints = self
+            .required_if
+            .iter()
+            .map(|t| quote!(field_validator::Constraint::RequiredIf(#t)))
+            .chain(self.format.map(|f| {
+                let format = format_tokens(f);
+                quote!(field_validator::Constraint::Format(#format))
+            }))
+            .chain(self.aliases.iter().map(|alias| quote!(field_validator::Constraint::Alias(#alias))))
+            .chain(self.string_enum.then(|| {
+                let leaf = leaf_type(&self.ty);
+                quote!(field_validator::Constraint::OneOf(
+                    <#leaf as field_validator::StringEnum>::VALUES
+                ))
+            }));
+        let nested = if self.nested {
+            let leaf = leaf_type(&self.ty);
+            let leaf_name = match leaf {
+                Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .map(|seg| seg.ident.to_string())
+                    .unwrap_or_default(),
+                other => quote!(#other).to_string(),
+            };
+            let fields = match self.serialized {
+                true => quote!(serialized_fields),
+                false => quote!(fields),
+            };
+            quote!(::core::option::Option::Some(field_validator::NestedType {
+                name: #leaf_name,
+                fields: <#leaf as field_validator::ValidateFields>::#fields,
+            }))
+        } else {
+            quote!(::core::option::Option::None)
+        };
+        quote! {
+            field_validator::FieldDescriptor {
+                name: #name,
+                rust_name: #rust_name,
+                shape: #shape,
+                optional: #optional,
+                nullable: #nullable,
+                constraints: &[#(#constraints),*],
+                since: #since,
+                until: #until,
+                deprecated: #deprecated,
+                sensitive: #sensitive,
+                message: #message,
+                doc: #doc,
+                nested: #nested,
+            }
+        }
     }
 }
 
-/// Check if a type is Option<T>
-fn is_option_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if type_path.path.segments.len() == 1 {
-            return type_path.path.segments[0].ident == "Option";
+/// A `field_validator::Format` literal.
+fn format_tokens(format: Format) -> proc_macro2::TokenStream {
+    match format {
+        Format::EpochSeconds { min, max } => {
+            let min = option_tokens(min);
+            let max = option_tokens(max);
+            quote!(field_validator::Format::EpochSeconds { min: #min, max: #max })
         }
+        Format::Rfc3339 => quote!(field_validator::Format::Rfc3339),
+        Format::Latitude => quote!(field_validator::Format::Latitude),
+        Format::Longitude => quote!(field_validator::Format::Longitude),
+        Format::Vin => quote!(field_validator::Format::Vin),
+        Format::Uuid => quote!(field_validator::Format::Uuid),
+        Format::Ip => quote!(field_validator::Format::Ip),
+        Format::Url => quote!(field_validator::Format::Url),
     }
-    false
+}
+
+/// `Some(v)` / `None` as tokens.
+fn option_tokens<T: quote::ToTokens>(value: Option<T>) -> proc_macro2::TokenStream {
+    match value {
+        Some(v) => quote!(::core::option::Option::Some(#v)),
+        None => quote!(::core::option::Option::None),
+    }
+}
+
+/// Extract per-field information based on types and attributes, as the
+/// fields are read and as `Serialize` writes them.
+fn extract_fields(data: &Data, container: &SerdeContainer) -> syn::Result<(Vec<FieldInfo>, Vec<FieldInfo>)> {
+    let fields = match data {
+        Data::Struct(data_struct) => match &data_struct.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Ok((Vec::new(), Vec::new())),
+        },
+        _ => return Ok((Vec::new(), Vec::new())),
+    };
+
+    // Rust name -> (read, written) wire names, used to resolve `required_if` targets
+    let wire_names: Vec<(String, (String, String))> = fields
+        .iter()
+        .filter_map(|f| {
+            let ident = f.ident.as_ref()?.unraw().to_string();
+            let serde = SerdeField::from_attrs(&f.attrs);
+            let wire = |rename: Option<String>, rule: Option<RenameRule>| match (rename, rule) {
+                (Some(rename), _) => rename,
+                (None, Some(rule)) => rule.apply(&ident),
+                (None, None) => ident.clone(),
+            };
+            let read = wire(serde.rename, container.rename_all);
+            let written = wire(serde.serialize_rename, container.serialize_rename_all);
+            Some((ident, (read, written)))
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    let mut serialized = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+
+    for (field, (rust_name, (wire_name, written_name))) in fields.iter().zip(wire_names.iter()) {
+        let attrs = match FieldAttrs::from_attrs(&field.attrs) {
+            Ok(attrs) => attrs,
+            Err(e) => {
+                push_error(&mut errors, e);
+                continue;
+            }
+        };
+        let serde = SerdeField::from_attrs(&field.attrs);
+        if serde.skip && serde.skip_serializing {
+            continue;
+        }
+        if let Some(span) = serde.flatten {
+            push_error(
+                &mut errors,
+                syn::Error::new(
+                    span,
+                    "`ValidateFields` does not support `#[serde(flatten)]`; declare the fields directly or mark a nested field",
+                ),
+            );
+            continue;
+        }
+
+        let mut required_if = None;
+        let mut written_if = None;
+        if let Some(trigger) = &attrs.required_if {
+            let target = trigger.value();
+            if *rust_name == target {
+                push_error(
+                    &mut errors,
+                    syn::Error::new(trigger.span(), "a field cannot be `required_if` itself"),
+                );
+            } else if let Some((_, (read, written))) = wire_names.iter().find(|(rust, _)| *rust == target) {
+                required_if = Some(read.clone());
+                written_if = Some(written.clone());
+            } else {
+                push_error(
+                    &mut errors,
+                    syn::Error::new(
+                        trigger.span(),
+                        format!("`required_if` refers to unknown field `{}`", target),
+                    ),
+                );
+            }
+        }
+
+        // Skip if field has serde(default) or skip_serializing_if attributes, or
+        // the struct has serde(default)
+        let has_serde_optional = serde.default || serde.skip_serializing_if || container.default;
+
+        // Skip if field type is Option<T>, or declared to be one
+        let is_option_type = attrs.option.is_some() || is_option_type(&field.ty);
+
+        // Include as required if forced, or if none of the optional criteria are met
+        let required = attrs.required.is_some()
+            || (attrs.optional.is_none()
+                && attrs.required_if.is_none()
+                && !has_serde_optional
+                && !is_option_type);
+
+        let info = FieldInfo {
+            ident: field.ident.clone().expect("named field"),
+            vis: field.vis.clone(),
+            rust_name: rust_name.clone(),
+            wire_name: wire_name.clone(),
+            aliases: serde.aliases.clone(),
+            deserialize_with: serde.deserialize_with.clone(),
+            serialize_with: serde.serialize_with,
+            serialized: false,
+            ty: field.ty.clone(),
+            required,
+            nullable: is_option_type,
+            required_if,
+            since: attrs.since.as_ref().map(|(v, _)| *v),
+            until: attrs.until.as_ref().map(|(v, _)| *v),
+            deprecated: attrs.deprecated.as_ref().map(LitStr::value),
+            sensitive: attrs.sensitive.is_some(),
+            message: attrs.message.as_ref().map(LitStr::value),
+            format: attrs.format.as_ref().map(|(f, _)| *f),
+            nested: attrs.nested.is_some(),
+            string_enum: attrs.string_enum.is_some(),
+            doc: doc_comment(&field.attrs),
+        };
+        if !serde.skip_serializing {
+            // Aliases are only accepted when reading
+            serialized.push(FieldInfo {
+                wire_name: written_name.clone(),
+                aliases: Vec::new(),
+                required_if: written_if,
+                serialized: true,
+                ..info.clone()
+            });
+        }
+        if !serde.skip {
+            out.push(info);
+        }
+    }
+
+    match errors {
+        Some(e) => Err(e),
+        None => Ok((out, serialized)),
+    }
+}
+
+/// Join a field's `///` lines into a single string, trimming the leading
+/// space rustdoc keeps after the slashes.
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
 }
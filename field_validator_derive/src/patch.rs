@@ -0,0 +1,97 @@
+// field_validator_derive/src/patch.rs
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{DeriveInput, LitStr};
+
+use crate::FieldInfo;
+
+/// Generate `{Name}Patch` and the `Patchable` impl linking it to `{Name}`.
+///
+/// Every field becomes `Option<FieldType>` under its wire name, absent
+/// meaning "leave unchanged". Fields that are already `Option`s become
+/// `Option<Option<T>>`, so an explicit `null` clears them. Nested structs
+/// are replaced as a whole rather than patched recursively.
+///
+/// Aliases and `deserialize_with` / `with` functions carry over, the latter
+/// through a hidden helper that wraps the original's result in `Some`.
+/// serde is reached through `field_validator`'s re-export, so the user's
+/// crate does not need its own dependency on it.
+pub fn patch_tokens(input: &DeriveInput, fields: &[FieldInfo]) -> TokenStream2 {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let patch_name = format_ident!("{}Patch", name);
+    let doc = format!("All-optional companion of [`{}`] for partial updates.", name);
+
+    let mut helpers = Vec::new();
+    let patch_fields: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let ident = &f.ident;
+            let field_vis = &f.vis;
+            let ty = &f.ty;
+            let wire = &f.wire_name;
+            let aliases = &f.aliases;
+            let deserialize_with = match &f.deserialize_with {
+                Some(original) => {
+                    let helper = format_ident!("__deserialize_{}", ident.unraw());
+                    let original: syn::ExprPath = match original.parse() {
+                        Ok(path) => path,
+                        Err(e) => return e.to_compile_error(),
+                    };
+                    helpers.push(quote! {
+                        #[doc(hidden)]
+                        fn #helper<'de, D>(
+                            deserializer: D,
+                        ) -> ::core::result::Result<::core::option::Option<#ty>, D::Error>
+                        where
+                            D: field_validator::serde::Deserializer<'de>,
+                        {
+                            #original(deserializer).map(::core::option::Option::Some)
+                        }
+                    });
+                    let path = LitStr::new(&format!("{}::{}", patch_name, helper), ident.span());
+                    quote!(, deserialize_with = #path)
+                }
+                None if f.nullable => {
+                    quote!(, deserialize_with = "field_validator::validate::deserialize_present")
+                }
+                None => quote!(),
+            };
+            quote! {
+                #[serde(default, rename = #wire #(, alias = #aliases)* #deserialize_with)]
+                #field_vis #ident: ::core::option::Option<#ty>
+            }
+        })
+        .collect();
+    let applies = fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote! {
+            if let ::core::option::Option::Some(value) = patch.#ident {
+                self.#ident = value;
+            }
+        }
+    });
+
+    quote! {
+        #[doc = #doc]
+        #[derive(field_validator::serde::Deserialize, Default)]
+        #[serde(crate = "field_validator::serde")]
+        #vis struct #patch_name {
+            #(#patch_fields),*
+        }
+
+        impl #patch_name {
+            #(#helpers)*
+        }
+
+        impl field_validator::Patchable for #name {
+            type Patch = #patch_name;
+
+            fn apply_patch(&mut self, patch: #patch_name) {
+                #(#applies)*
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// field_validator_derive/src/serde_attrs.rs
+
+use proc_macro2::Span;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{Attribute, LitStr, Result};
+
+/// The subset of `#[serde(...)]` on a field, or on an enum variant, that
+/// affects the wire shape.
+#[derive(Default)]
+pub struct SerdeField {
+    /// `rename = "..."` or `rename(deserialize = "...")`.
+    pub rename: Option<String>,
+    /// `rename = "..."` or `rename(serialize = "...")`.
+    pub serialize_rename: Option<String>,
+    /// Every `alias = "..."`.
+    pub aliases: Vec<String>,
+    /// `flatten`, with its span for error reporting.
+    pub flatten: Option<Span>,
+    /// `deserialize_with = "path"`, or `with = "module"` as `module::deserialize`.
+    pub deserialize_with: Option<LitStr>,
+    /// `default` or `default = "path"`.
+    pub default: bool,
+    /// `skip_serializing_if = "..."`.
+    pub skip_serializing_if: bool,
+    /// `serialize_with = "path"` or `with = "module"`: a custom serializer
+    /// decides what is written.
+    pub serialize_with: bool,
+    /// `skip` or `skip_deserializing`: the field is never read from the wire.
+    pub skip: bool,
+    /// `skip` or `skip_serializing`: the field is never written to the wire.
+    pub skip_serializing: bool,
+    /// `other`: the variant catches every unknown value.
+    pub other: bool,
+}
+
+/// The subset of `#[serde(...)]` on a container that affects field names
+/// and required-ness.
+#[derive(Default)]
+pub struct SerdeContainer {
+    pub rename_all: Option<RenameRule>,
+    /// `rename_all = "..."` or `rename_all(serialize = "...")`.
+    pub serialize_rename_all: Option<RenameRule>,
+    /// `default` or `default = "path"`: missing fields are filled in.
+    pub default: bool,
+}
+
+impl SerdeField {
+    /// Read serde field attributes, ignoring keys we do not care about.
+    /// serde itself reports malformed attributes, so parse errors here are
+    /// swallowed rather than duplicated.
+    pub fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut out = SerdeField::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let (deserialize, serialize) = names(&meta)?;
+                    out.rename = deserialize.or(out.rename.take());
+                    out.serialize_rename = serialize.or(out.serialize_rename.take());
+                } else if meta.path.is_ident("alias") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    out.aliases.push(lit.value());
+                } else if meta.path.is_ident("flatten") {
+                    out.flatten = Some(meta.path.span());
+                } else if meta.path.is_ident("deserialize_with") {
+                    out.deserialize_with = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("serialize_with") {
+                    out.serialize_with = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("with") {
+                    out.serialize_with = true;
+                    let module: LitStr = meta.value()?.parse()?;
+                    let path = format!("{}::deserialize", module.value());
+                    out.deserialize_with.get_or_insert(LitStr::new(&path, module.span()));
+                } else if meta.path.is_ident("default") {
+                    out.default = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("skip_serializing_if") {
+                    out.skip_serializing_if = true;
+                    skip_value(&meta)?;
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                    out.skip_serializing = true;
+                } else if meta.path.is_ident("skip_deserializing") {
+                    out.skip = true;
+                } else if meta.path.is_ident("skip_serializing") {
+                    out.skip_serializing = true;
+                } else if meta.path.is_ident("other") {
+                    out.other = true;
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            });
+        }
+        out
+    }
+}
+
+impl SerdeContainer {
+    pub fn from_attrs(attrs: &[Attribute]) -> Self {
+        let mut out = SerdeContainer::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    let (deserialize, serialize) = names(&meta)?;
+                    if let Some(rule) = deserialize {
+                        out.rename_all = RenameRule::from_str(&rule).or(out.rename_all);
+                    }
+                    if let Some(rule) = serialize {
+                        out.serialize_rename_all = RenameRule::from_str(&rule).or(out.serialize_rename_all);
+                    }
+                } else if meta.path.is_ident("default") {
+                    out.default = true;
+                    skip_value(&meta)?;
+                } else {
+                    skip_value(&meta)?;
+                }
+                Ok(())
+            });
+        }
+        out
+    }
+}
+
+/// Read `key = "name"` or `key(deserialize = "name", serialize = "name")`
+/// as the `(deserialize, serialize)` names.
+fn names(meta: &ParseNestedMeta) -> Result<(Option<String>, Option<String>)> {
+    if meta.input.peek(syn::Token![=]) {
+        let lit: LitStr = meta.value()?.parse()?;
+        return Ok((Some(lit.value()), Some(lit.value())));
+    }
+    let (mut deserialize, mut serialize) = (None, None);
+    meta.parse_nested_meta(|inner| {
+        if inner.path.is_ident("deserialize") {
+            let lit: LitStr = inner.value()?.parse()?;
+            deserialize = Some(lit.value());
+        } else if inner.path.is_ident("serialize") {
+            let lit: LitStr = inner.value()?.parse()?;
+            serialize = Some(lit.value());
+        } else {
+            skip_value(&inner)?;
+        }
+        Ok(())
+    })?;
+    Ok((deserialize, serialize))
+}
+
+/// Consume whatever value follows a key we are not interested in.
+fn skip_value(meta: &ParseNestedMeta) -> Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        meta.parse_nested_meta(|inner| skip_value(&inner))?;
+    }
+    Ok(())
+}
+
+/// serde's `rename_all` rules, applied to snake_case field identifiers or
+/// PascalCase variant identifiers.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    pub fn apply(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_string(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+            RenameRule::Pascal | RenameRule::Camel => {
+                let mut out = String::with_capacity(field.len());
+                let mut upper = matches!(self, RenameRule::Pascal);
+                for ch in field.chars() {
+                    if ch == '_' {
+                        upper = true;
+                    } else if upper {
+                        out.push(ch.to_ascii_uppercase());
+                        upper = false;
+                    } else {
+                        out.push(ch);
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_string(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => {
+                let mut chars = variant.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            RenameRule::Snake | RenameRule::ScreamingSnake | RenameRule::Kebab | RenameRule::ScreamingKebab => {
+                let mut snake = String::with_capacity(variant.len() + 4);
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                match self {
+                    RenameRule::Snake => snake,
+                    RenameRule::ScreamingSnake => snake.to_ascii_uppercase(),
+                    RenameRule::Kebab => snake.replace('_', "-"),
+                    _ => snake.to_ascii_uppercase().replace('_', "-"),
+                }
+            }
+        }
+    }
+}
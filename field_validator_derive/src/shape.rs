@@ -0,0 +1,149 @@
+// field_validator_derive/src/shape.rs
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{GenericArgument, PathArguments, Type};
+
+/// Check if a type is Option<T>, written either bare or through its
+/// `std::option` / `core::option` path.
+pub fn is_option_type(ty: &Type) -> bool {
+    option_inner(ty).is_some()
+}
+
+/// The `T` of an `Option<T>`, if `ty` is one.
+pub fn option_inner(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Group(group) => option_inner(&group.elem),
+        Type::Paren(paren) => option_inner(&paren.elem),
+        Type::Path(type_path) if type_path.qself.is_none() => {
+            let segments: Vec<String> = type_path
+                .path
+                .segments
+                .iter()
+                .map(|seg| seg.ident.to_string())
+                .collect();
+            let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+            if matches!(
+                segments.as_slice(),
+                ["Option"] | ["std" | "core", "option", "Option"]
+            ) {
+                generic_args(ty).first().copied()
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Generic type arguments of the last path segment, e.g. `[K, V]` for `HashMap<K, V>`.
+fn generic_args(ty: &Type) -> Vec<&Type> {
+    let Type::Path(type_path) = ty else { return Vec::new() };
+    let Some(last) = type_path.path.segments.last() else { return Vec::new() };
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Name of the last path segment, e.g. `Vec` for `std::vec::Vec<u8>`.
+fn last_ident(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(type_path) => type_path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// Peel wrappers that do not change the wire shape (`Box`, `Rc`, `Arc`,
+/// references, groups).
+fn peel(ty: &Type) -> &Type {
+    match ty {
+        Type::Group(group) => peel(&group.elem),
+        Type::Paren(paren) => peel(&paren.elem),
+        Type::Reference(reference) => peel(&reference.elem),
+        _ => match last_ident(ty).as_deref() {
+            Some("Box" | "Rc" | "Arc") => match generic_args(ty).first() {
+                Some(inner) => peel(inner),
+                None => ty,
+            },
+            _ => ty,
+        },
+    }
+}
+
+/// The element type of a sequence or the value type of a map, if `ty` is one.
+fn container_inner(ty: &Type) -> Option<(bool, &Type)> {
+    let ty = peel(ty);
+    match ty {
+        Type::Array(array) => Some((false, &array.elem)),
+        Type::Slice(slice) => Some((false, &slice.elem)),
+        _ => match last_ident(ty).as_deref() {
+            Some("Vec" | "VecDeque" | "HashSet" | "BTreeSet") => {
+                generic_args(ty).first().map(|t| (false, *t))
+            }
+            Some("HashMap" | "BTreeMap") => generic_args(ty).get(1).map(|t| (true, *t)),
+            _ => None,
+        },
+    }
+}
+
+/// The innermost type a field's value is built from, looking through
+/// `Option` and containers. Used to link `nested` fields to their type.
+pub fn leaf_type(ty: &Type) -> &Type {
+    let ty = peel(option_inner(ty).unwrap_or(ty));
+    match container_inner(ty) {
+        Some((_, inner)) => leaf_type(inner),
+        None => ty,
+    }
+}
+
+/// What an unrecognized leaf type is on the wire.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Leaf {
+    Other,
+    /// Marked `nested`: an object with its own descriptors.
+    Object,
+    /// Marked `enum`: a `StringEnum` sent as one of its variant names.
+    String,
+}
+
+/// Build a `field_validator::TypeShape` expression for `ty`. Unrecognized
+/// types become `leaf`.
+pub fn shape_tokens(ty: &Type, leaf: Leaf) -> TokenStream2 {
+    let ty = peel(option_inner(ty).unwrap_or(ty));
+    if let Some((is_map, inner)) = container_inner(ty) {
+        let inner = shape_tokens(inner, leaf);
+        return if is_map {
+            quote!(field_validator::TypeShape::Map(&#inner))
+        } else {
+            quote!(field_validator::TypeShape::Array(&#inner))
+        };
+    }
+    if let Type::Path(_) = ty {
+        match last_ident(ty).as_deref() {
+            Some("String" | "str" | "char" | "Cow") => {
+                return quote!(field_validator::TypeShape::String)
+            }
+            Some("bool") => return quote!(field_validator::TypeShape::Bool),
+            Some(
+                "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64"
+                | "i128" | "isize",
+            ) => return quote!(field_validator::TypeShape::Integer),
+            Some("f32" | "f64") => return quote!(field_validator::TypeShape::Float),
+            Some("Value") => return quote!(field_validator::TypeShape::Any),
+            _ => {}
+        }
+    }
+    match leaf {
+        Leaf::Other => quote!(field_validator::TypeShape::Other),
+        Leaf::Object => quote!(field_validator::TypeShape::Object),
+        Leaf::String => quote!(field_validator::TypeShape::String),
+    }
+}
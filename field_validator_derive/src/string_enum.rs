@@ -0,0 +1,90 @@
+// field_validator_derive/src/string_enum.rs
+
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Error, Fields, LitStr};
+
+use crate::attrs::push_error;
+use crate::serde_attrs::{SerdeContainer, SerdeField};
+
+/// The `field_validator::StringEnum` impl for a fieldless enum, listing the
+/// variants' wire names in declaration order.
+pub fn string_enum_tokens(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new(input.ident.span(), "`StringEnum` can only be derived for enums"));
+    };
+    let container = SerdeContainer::from_attrs(&input.attrs);
+
+    let mut errors: Option<Error> = None;
+    // (variant, wire name, accepted on the wire)
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            push_error(
+                &mut errors,
+                Error::new(variant.ident.span(), "`StringEnum` variants cannot carry data"),
+            );
+            continue;
+        }
+        let serde = SerdeField::from_attrs(&variant.attrs);
+        if serde.other {
+            push_error(
+                &mut errors,
+                Error::new(
+                    variant.ident.span(),
+                    "`StringEnum` cannot list the values of an enum with a `#[serde(other)]` variant",
+                ),
+            );
+            continue;
+        }
+        if !serde.aliases.is_empty() {
+            push_error(
+                &mut errors,
+                Error::new(
+                    variant.ident.span(),
+                    "`StringEnum` does not support `#[serde(alias)]`; add a variant for each accepted value",
+                ),
+            );
+            continue;
+        }
+        let ident = variant.ident.unraw().to_string();
+        let wire = match (serde.rename, container.rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.apply_to_variant(&ident),
+            (None, None) => ident,
+        };
+        variants.push((&variant.ident, LitStr::new(&wire, variant.ident.span()), !serde.skip));
+    }
+    if let Some(e) = errors {
+        return Err(e);
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let accepted: Vec<_> = variants.iter().filter(|(_, _, accepted)| *accepted).collect();
+    let values = accepted.iter().map(|(_, wire, _)| wire);
+    let parse_arms = accepted.iter().map(|(ident, wire, _)| {
+        quote!(#wire => ::core::option::Option::Some(Self::#ident))
+    });
+    let str_arms = variants.iter().map(|(ident, wire, _)| quote!(Self::#ident => #wire));
+
+    Ok(quote! {
+        impl #impl_generics field_validator::StringEnum for #name #ty_generics #where_clause {
+            const VALUES: &'static [&'static str] = &[#(#values),*];
+
+            fn as_str(&self) -> &'static str {
+                match *self {
+                    #(#str_arms,)*
+                }
+            }
+
+            fn parse(value: &str) -> ::core::option::Option<Self> {
+                match value {
+                    #(#parse_arms,)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    })
+}
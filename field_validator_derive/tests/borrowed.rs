@@ -0,0 +1,29 @@
+// field_validator_derive/tests/borrowed.rs
+
+use std::borrow::Cow;
+
+use field_validator::{validate_and_deserialize_borrowed, ValidateFields};
+use field_validator_derive::ValidateFields;
+use serde::Deserialize;
+
+#[derive(ValidateFields, Deserialize, Debug)]
+struct Request<'a> {
+    bike_identifier: &'a str,
+    #[serde(borrow)]
+    change_to_mode: Cow<'a, str>,
+}
+
+#[test]
+fn borrows_from_the_body() {
+    let body = br#"{"bike_identifier":"bike-1","change_to_mode":"glide"}"#.to_vec();
+    let request: Request = validate_and_deserialize_borrowed(&body).unwrap();
+    assert_eq!(request.bike_identifier, "bike-1");
+    assert!(matches!(request.change_to_mode, Cow::Borrowed("glide")));
+    assert_eq!(Request::required_fields(), ["bike_identifier", "change_to_mode"]);
+}
+
+#[test]
+fn validates_before_borrowing() {
+    let err = validate_and_deserialize_borrowed::<Request>(br#"{"change_to_mode":"glide"}"#).unwrap_err();
+    assert!(err.to_string().contains("bike_identifier"));
+}
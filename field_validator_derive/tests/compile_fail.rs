@@ -0,0 +1,7 @@
+// field_validator_derive/tests/compile_fail.rs
+
+#[test]
+fn diagnostics() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,79 @@
+// field_validator_derive/tests/option_detection.rs
+
+#![allow(dead_code)]
+
+use field_validator::ValidateFields;
+use field_validator_derive::ValidateFields;
+
+type MaybeMode = Option<String>;
+
+#[derive(ValidateFields)]
+struct BareOption {
+    id: String,
+    mode: Option<String>,
+}
+
+#[derive(ValidateFields)]
+struct QualifiedOptions {
+    id: String,
+    std_mode: std::option::Option<String>,
+    core_mode: core::option::Option<String>,
+    rooted_mode: ::std::option::Option<String>,
+}
+
+#[derive(ValidateFields)]
+struct AliasedOption {
+    id: String,
+    unmarked: MaybeMode,
+    #[field_validator(option)]
+    marked: MaybeMode,
+}
+
+#[derive(ValidateFields)]
+struct ForcedRequired {
+    #[field_validator(required)]
+    mode: Option<String>,
+    #[field_validator(required)]
+    qualified: std::option::Option<String>,
+    #[field_validator(option, required)]
+    aliased: MaybeMode,
+    optional: Option<String>,
+}
+
+mod shadowed {
+    /// A user type that happens to be called `Option`.
+    pub struct Option<T>(pub T);
+}
+
+#[derive(ValidateFields)]
+struct ShadowedOption {
+    value: shadowed::Option<String>,
+}
+
+#[test]
+fn bare_option_is_optional() {
+    assert_eq!(BareOption::required_fields(), &["id"]);
+}
+
+#[test]
+fn qualified_option_paths_are_optional() {
+    assert_eq!(QualifiedOptions::required_fields(), &["id"]);
+}
+
+#[test]
+fn alias_is_required_unless_marked_option() {
+    assert_eq!(AliasedOption::required_fields(), &["id", "unmarked"]);
+}
+
+#[test]
+fn required_overrides_option_types() {
+    assert_eq!(
+        ForcedRequired::required_fields(),
+        &["mode", "qualified", "aliased"]
+    );
+}
+
+#[test]
+fn foreign_option_type_is_required() {
+    assert_eq!(ShadowedOption::required_fields(), &["value"]);
+}
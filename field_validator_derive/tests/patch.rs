@@ -0,0 +1,129 @@
+// field_validator_derive/tests/patch.rs
+
+#![allow(dead_code)]
+
+use field_validator::{validate_and_deserialize_patch, validate_partial, FindingKind, Patchable, ValidationError};
+use field_validator_derive::ValidateFields;
+use serde::{Deserialize, Deserializer};
+
+#[derive(ValidateFields, Deserialize, Debug, Clone, PartialEq)]
+struct BikeInfo {
+    model: String,
+    year: u32,
+}
+
+/// Speeds travel as strings such as `"25kmh"`.
+fn parse_speed<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.trim_end_matches("kmh").parse().map_err(serde::de::Error::custom)
+}
+
+mod upper {
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        String::deserialize(deserializer).map(|s| s.to_uppercase())
+    }
+}
+
+#[derive(ValidateFields, Deserialize, Debug, Clone, PartialEq)]
+#[field_validator(patch)]
+#[serde(rename_all = "camelCase")]
+struct Ride {
+    #[serde(alias = "bike_id")]
+    bike_identifier: String,
+    #[serde(with = "upper")]
+    mode: String,
+    #[serde(deserialize_with = "parse_speed")]
+    top_speed: u32,
+    note: Option<String>,
+    #[field_validator(format = "latitude")]
+    lat: Option<f64>,
+    #[field_validator(nested)]
+    bike_info: BikeInfo,
+}
+
+fn stored() -> Ride {
+    Ride {
+        bike_identifier: "b-1".to_string(),
+        mode: "GLIDE".to_string(),
+        top_speed: 20,
+        note: Some("first ride".to_string()),
+        lat: None,
+        bike_info: BikeInfo { model: "S".to_string(), year: 2023 },
+    }
+}
+
+fn patched(json: &str) -> Ride {
+    let mut ride = stored();
+    ride.apply_patch(validate_and_deserialize_patch::<Ride>(json).unwrap());
+    ride
+}
+
+#[test]
+fn absent_fields_are_left_unchanged() {
+    assert_eq!(patched("{}"), stored());
+    let ride = patched(r#"{"topSpeed": "32kmh"}"#);
+    assert_eq!(ride, Ride { top_speed: 32, ..stored() });
+}
+
+#[test]
+fn null_clears_optional_fields() {
+    assert_eq!(patched(r#"{"note": null}"#).note, None);
+    assert_eq!(patched(r#"{"note": "second"}"#).note.as_deref(), Some("second"));
+}
+
+#[test]
+fn patch_fields_keep_serde_attributes() {
+    let ride = patched(r#"{"bike_id": "b-2", "mode": "combat"}"#);
+    assert_eq!(ride.bike_identifier, "b-2");
+    assert_eq!(ride.mode, "COMBAT");
+    let patch: RidePatch = serde_json::from_str(r#"{"topSpeed": "45kmh"}"#).unwrap();
+    assert_eq!(patch.top_speed, Some(45));
+    assert!(serde_json::from_str::<RidePatch>(r#"{"topSpeed": "fast"}"#).is_err());
+}
+
+#[test]
+fn nested_structs_are_replaced_whole() {
+    let ride = patched(r#"{"bikeInfo": {"model": "X", "year": 2025}}"#);
+    assert_eq!(ride.bike_info, BikeInfo { model: "X".to_string(), year: 2025 });
+}
+
+fn findings(json: &str) -> Vec<(String, &'static str)> {
+    match validate_partial::<Ride>(json) {
+        Ok(()) => Vec::new(),
+        Err(ValidationError::InvalidFields(findings)) => {
+            findings.into_iter().map(|f| (f.path, f.kind.code())).collect()
+        }
+        Err(other) => panic!("expected invalid fields, got {:?}", other),
+    }
+}
+
+#[test]
+fn partial_validation_checks_supplied_fields_only() {
+    assert!(findings("{}").is_empty());
+    assert!(findings(r#"{"bikeInfo": {"year": 2025}}"#).is_empty());
+    assert_eq!(
+        findings(r#"{"note": 1, "bikeIdentifier": null, "lat": 91.0, "color": "red"}"#),
+        [
+            ("bikeIdentifier".to_string(), "wrong_type"),
+            ("color".to_string(), "unknown_field"),
+            ("lat".to_string(), "invalid_format"),
+            ("note".to_string(), "wrong_type"),
+        ]
+    );
+    assert_eq!(findings(r#"{"bikeInfo": {"year": "new"}}"#), [("bikeInfo.year".to_string(), "wrong_type")]);
+    // Custom deserializers decide what they accept
+    assert!(findings(r#"{"topSpeed": "32kmh", "mode": "glide"}"#).is_empty());
+}
+
+#[test]
+fn rejected_patches_are_not_deserialized() {
+    let Err(err) = validate_and_deserialize_patch::<Ride>(r#"{"note": 5}"#) else {
+        panic!("a wrongly typed patch was accepted");
+    };
+    let Some(ValidationError::InvalidFields(findings)) = err.downcast_ref::<ValidationError>() else {
+        panic!("expected invalid fields, got {}", err);
+    };
+    assert_eq!(findings[0].kind, FindingKind::WrongType { expected: field_validator::TypeShape::String });
+}
@@ -0,0 +1,73 @@
+// field_validator_derive/tests/serde_attrs.rs
+
+use field_validator::{
+    validate_and_deserialize, validate_json_with_report, validate_partial, validate_serialized,
+    ValidateFields, ValidationError, ValidatorOptions,
+};
+use field_validator_derive::ValidateFields;
+use serde::{Deserialize, Serialize};
+
+#[derive(ValidateFields, Deserialize, Serialize, Debug)]
+struct Request {
+    #[serde(alias = "bike_id", alias = "bikeId")]
+    bike_identifier: String,
+    #[serde(alias = "mode")]
+    change_to_mode: Option<String>,
+    #[field_validator(required_if = "change_to_mode")]
+    current_mode: Option<String>,
+}
+
+#[derive(ValidateFields, Deserialize, Debug, Default)]
+#[serde(default)]
+struct Settings {
+    units: String,
+    #[field_validator(required)]
+    locale: String,
+}
+
+fn check<T: ValidateFields>(json: &str) -> Result<(), ValidationError> {
+    validate_json_with_report::<T>(json, &ValidatorOptions::default()).map(|_| ())
+}
+
+#[test]
+fn aliases_satisfy_required_fields() {
+    check::<Request>(r#"{"bike_id":"b1"}"#).unwrap();
+    check::<Request>(r#"{"bikeId":"b1"}"#).unwrap();
+    let request: Request = validate_and_deserialize(r#"{"bike_id":"b1"}"#).unwrap();
+    assert_eq!(request.bike_identifier, "b1");
+    match check::<Request>(r#"{"bike":"b1"}"#) {
+        Err(ValidationError::MissingFields(e)) => assert_eq!(e.missing_fields, ["bike_identifier"]),
+        other => panic!("expected missing bike_identifier, got {:?}", other),
+    }
+}
+
+#[test]
+fn aliases_trigger_required_if() {
+    match check::<Request>(r#"{"bike_identifier":"b1","mode":"glide"}"#) {
+        Err(ValidationError::MissingFields(e)) => assert_eq!(e.missing_fields, ["current_mode"]),
+        other => panic!("expected missing current_mode, got {:?}", other),
+    }
+}
+
+#[test]
+fn aliases_are_known_in_partial_mode() {
+    validate_partial::<Request>(r#"{"bikeId":"b1","mode":"glide"}"#).unwrap();
+    match validate_partial::<Request>(r#"{"bike":"b1"}"#) {
+        Err(ValidationError::InvalidFields(findings)) => assert_eq!(findings[0].path, "bike"),
+        other => panic!("expected an unknown field, got {:?}", other),
+    }
+}
+
+#[test]
+fn serialized_values_use_the_primary_name() {
+    let request = Request { bike_identifier: "b1".into(), change_to_mode: None, current_mode: None };
+    validate_serialized(&request).unwrap();
+}
+
+#[test]
+fn container_default_makes_fields_optional() {
+    assert_eq!(Settings::required_fields(), ["locale"]);
+    check::<Settings>(r#"{"locale":"en"}"#).unwrap();
+    let settings: Settings = validate_and_deserialize(r#"{"locale":"en"}"#).unwrap();
+    assert_eq!(settings.units, "");
+}
@@ -0,0 +1,53 @@
+// field_validator_derive/tests/string_enum.rs
+
+#![allow(dead_code)]
+
+use field_validator::{StringEnum, ValidateFields};
+use field_validator_derive::{StringEnum, ValidateFields};
+use serde::Deserialize;
+
+#[allow(non_camel_case_types)]
+#[derive(StringEnum, Deserialize, Debug, PartialEq)]
+enum Mode {
+    glide,
+    combat,
+    #[serde(rename = "ballistic_v2")]
+    ballistic,
+}
+
+#[derive(StringEnum, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum Unit {
+    KiloMeters,
+    Miles,
+    #[serde(skip)]
+    Internal,
+}
+
+#[derive(ValidateFields, Deserialize)]
+struct Request {
+    #[field_validator(enum)]
+    mode: Mode,
+    #[field_validator(enum)]
+    units: Option<Vec<Unit>>,
+}
+
+#[test]
+fn values_follow_serde_renames() {
+    assert_eq!(Mode::VALUES, ["glide", "combat", "ballistic_v2"]);
+    assert_eq!(Unit::VALUES, ["kilo-meters", "miles"]);
+    assert_eq!(Mode::ballistic.as_str(), "ballistic_v2");
+    assert_eq!(Mode::parse("combat"), Some(Mode::combat));
+    assert_eq!(Unit::parse("internal"), None);
+    for value in Unit::VALUES {
+        let parsed: Unit = serde_json::from_value(serde_json::json!(value)).unwrap();
+        assert_eq!(parsed.as_str(), *value);
+    }
+}
+
+#[test]
+fn enum_fields_list_allowed_values() {
+    let fields = Request::fields();
+    assert_eq!(fields[0].allowed(), Some(Mode::VALUES));
+    assert_eq!(fields[1].allowed(), Some(Unit::VALUES));
+}
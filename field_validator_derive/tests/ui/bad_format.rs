@@ -0,0 +1,11 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+struct Request {
+    #[field_validator(format = "email")]
+    a: String,
+    #[field_validator(format = "epoch_seconds(10)")]
+    b: u64,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use field_validator_derive::{StringEnum, ValidateFields};
+
+#[derive(ValidateFields)]
+struct Inner {
+    id: String,
+}
+
+#[derive(StringEnum)]
+enum Mode {
+    Glide,
+}
+
+#[derive(ValidateFields)]
+struct Request {
+    a: Option<String>,
+    #[field_validator(optional, required_if = "a")]
+    b: Option<String>,
+    #[field_validator(required, optional)]
+    c: Option<String>,
+    #[field_validator(required, required_if = "a")]
+    d: Option<String>,
+    #[field_validator(nested, format = "uuid")]
+    e: Inner,
+    #[field_validator(enum, nested)]
+    f: Mode,
+    #[field_validator(enum, format = "uuid")]
+    g: Mode,
+    #[field_validator(since = "3", until = "3")]
+    h: String,
+}
+
+fn main() {}
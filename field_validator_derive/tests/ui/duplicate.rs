@@ -0,0 +1,20 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+#[field_validator(patch, patch)]
+struct Request {
+    #[field_validator(optional, optional)]
+    a: String,
+    #[field_validator(required_if = "a", required_if = "a")]
+    b: Option<String>,
+    #[field_validator(deprecated = "use a", deprecated = "use a")]
+    c: Option<String>,
+    #[field_validator(message = "x", message = "y")]
+    d: String,
+    #[field_validator(format = "uuid", format = "uuid")]
+    e: String,
+    #[field_validator(since = "2", since = "3")]
+    f: String,
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+struct Request {
+    a: String,
+    #[field_validator(required_if = a)]
+    b: Option<String>,
+    #[field_validator(deprecated = 1)]
+    c: Option<String>,
+    #[field_validator(message = 1)]
+    d: String,
+    #[field_validator(format = uuid)]
+    e: String,
+    #[field_validator(since = 2)]
+    f: String,
+    #[field_validator(until = "three")]
+    g: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+#[field_validator(patch = true)]
+struct Request {
+    #[field_validator(optional = true)]
+    bike_identifier: Option<String>,
+    #[field_validator(sensitive = "yes")]
+    token: String,
+}
+
+fn main() {}
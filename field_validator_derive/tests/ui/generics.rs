@@ -0,0 +1,14 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+struct Typed<T> {
+    value: T,
+}
+
+#[derive(ValidateFields)]
+#[field_validator(patch)]
+struct Borrowed<'a> {
+    value: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+struct Request {
+    #[field_validator(required_if = "a")]
+    a: Option<String>,
+    #[field_validator(required_if = "missing")]
+    b: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+use field_validator_derive::StringEnum;
+use serde::Deserialize;
+
+#[derive(StringEnum)]
+struct NotAnEnum;
+
+#[derive(StringEnum)]
+enum WithData {
+    Glide(u8),
+    Combat { level: u8 },
+}
+
+#[derive(StringEnum, Deserialize)]
+enum CatchAll {
+    Glide,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(StringEnum, Deserialize)]
+enum Aliased {
+    #[serde(alias = "slow")]
+    Glide,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use field_validator_derive::ValidateFields;
+
+#[derive(ValidateFields)]
+#[field_validator(partial)]
+struct Request {
+    #[field_validator(requird)]
+    bike_identifier: String,
+}
+
+fn main() {}